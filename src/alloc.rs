@@ -0,0 +1,49 @@
+//! Counts the heap allocations made by each thread, when built with the `alloc-stats` feature, to
+//! show that the search itself doesn't make any.
+
+pub const ENABLED: bool = cfg!(feature = "alloc-stats");
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    thread_local! {
+        pub static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            // The thread-local may already be gone while a thread is shutting down.
+            let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+}
+
+/// The number of allocations (and reallocations) made by this thread so far, or always 0 if the
+/// counts aren't enabled.
+pub fn thread_allocations() -> usize {
+    #[cfg(feature = "alloc-stats")]
+    {
+        counting::ALLOCATIONS.with(|a| a.get())
+    }
+    #[cfg(not(feature = "alloc-stats"))]
+    {
+        0
+    }
+}
@@ -0,0 +1,64 @@
+//! Letters are stored as `u8` codes rather than `char`s, so the search only ever compares bytes.
+//! Lowercase ASCII letters have fixed codes, and any other letter is given the next free code the
+//! first time it's seen.
+
+use std::sync::RwLock;
+
+/// The code for a letter that's been used up, which is shown as `_`.
+pub const BLANK: u8 = 0;
+/// The code that fills out a word after its letters, which no letter is ever given.
+pub const PAD: u8 = u8::MAX;
+
+const ASCII_START: u8 = 1;
+const ASCII_LETTERS: u8 = 26;
+const OTHER_LETTER_LIMIT: usize = (PAD - ASCII_START - ASCII_LETTERS) as usize;
+
+/// Letters outside of `a..=z`, in the order they were first seen.
+static OTHER_LETTERS: RwLock<Vec<char>> = RwLock::new(Vec::new());
+
+pub fn encode(letter: char) -> Result<u8, &'static str> {
+    match letter {
+        '_' => Ok(BLANK),
+        'a'..='z' => Ok(letter as u8 - b'a' + ASCII_START),
+        _ => {
+            let position = |letters: &[char]| letters.iter().position(|c| *c == letter);
+            let code = |position: usize| position as u8 + ASCII_START + ASCII_LETTERS;
+
+            if let Some(p) = position(&OTHER_LETTERS.read().unwrap()) {
+                return Ok(code(p));
+            }
+
+            let mut letters = OTHER_LETTERS.write().unwrap();
+            match position(&letters) {
+                Some(p) => Ok(code(p)),
+                None if letters.len() < OTHER_LETTER_LIMIT => {
+                    letters.push(letter);
+                    Ok(code(letters.len() - 1))
+                }
+                None => Err("too many distinct letters"),
+            }
+        }
+    }
+}
+
+pub fn decode(code: u8) -> char {
+    match code {
+        BLANK => '_',
+        c if c < ASCII_START + ASCII_LETTERS => (c - ASCII_START + b'a') as char,
+        c => OTHER_LETTERS.read().unwrap()[(c - ASCII_START - ASCII_LETTERS) as usize],
+    }
+}
+
+/// For serializing letter codes as the letters themselves.
+#[cfg(feature = "serde")]
+pub mod serde_letter {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(code: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(super::decode(*code))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+        super::encode(char::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,97 @@
+//! Finds openers that are bound to score the same as an anagram of theirs, so only one of them has
+//! to be searched.  After its feedback, an opener's game only depends on which guesses are still
+//! possible, so two openers that aren't answers themselves score the same if they leave the same
+//! guesses possible for every answer.  That's rare, but it's what happens when they differ only in
+//! where they put letters none of the answers have, and anagrams are where to look for it.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::solver::Solver;
+use crate::word_list::WordList;
+use crate::{filter_word_list, get_constraints, get_pattern, Word, MAX_WORD_LENGTH};
+
+/// The search words that have the same letters as another, in groups in the order they came in.
+pub fn classes(words: &[Word]) -> Vec<Vec<Word>> {
+    let mut classes = HashMap::<Word, Vec<Word>>::new();
+    let mut order = Vec::new();
+    for word in words {
+        let mut letters = *word;
+        letters.letters_mut().sort_unstable();
+        let class = classes.entry(letters).or_default();
+        if class.is_empty() {
+            order.push(letters);
+        }
+        if !class.contains(word) {
+            class.push(*word);
+        }
+    }
+    order
+        .into_iter()
+        .map(|letters| classes.remove(&letters).unwrap())
+        .filter(|c| c.len() > 1)
+        .collect()
+}
+
+/// Labels each of `answers` with the group `opener`'s feedback puts it in, numbered as they first
+/// turn up, so that openers that split them the same way get the same labels.
+fn split(opener: &Word, answers: &[Word]) -> Vec<usize> {
+    let mut labels = HashMap::new();
+    answers
+        .iter()
+        .map(|a| {
+            let next = labels.len();
+            *labels.entry(get_pattern(a, opener)).or_insert(next)
+        })
+        .collect()
+}
+
+/// Whether `a` and `b` leave the same guesses possible after every one of `answers`.
+fn equivalent(a: &Word, b: &Word, answers: &[Word], list: &WordList) -> bool {
+    if answers.contains(a) || answers.contains(b) {
+        return false;
+    }
+    let mut seen = HashMap::new();
+    let mut constraints = Vec::with_capacity(MAX_WORD_LENGTH);
+    let (mut left_a, mut left_b) = (Vec::new(), Vec::new());
+    answers.iter().all(|answer| {
+        let patterns = (get_pattern(answer, a), get_pattern(answer, b));
+        *seen.entry(patterns).or_insert_with(|| {
+            get_constraints(answer, a, &mut constraints);
+            filter_word_list(list, list.indices(), &constraints, &mut left_a);
+            get_constraints(answer, b, &mut constraints);
+            filter_word_list(list, list.indices(), &constraints, &mut left_b);
+            left_a == left_b
+        })
+    })
+}
+
+/// The openers in `classes` that score the same as one before them, by the one that's searched.
+pub fn equivalents(classes: &[Vec<Word>], solver: &Solver) -> HashMap<Word, Vec<Word>> {
+    let answers = solver.answers();
+    let list = solver.guess_words();
+    // Any guesses that are also answers are left possible only by the feedback the answer gets, so
+    // openers that split those up differently can't be equivalent, which saves looking closer.
+    let answer_set = answers.iter().collect::<HashSet<_>>();
+    let listed = list
+        .indices()
+        .iter()
+        .map(|i| *list.word(*i))
+        .filter(|w| answer_set.contains(w))
+        .collect::<Vec<_>>();
+
+    let mut equivalents = HashMap::<Word, Vec<Word>>::new();
+    for class in classes {
+        let mut searched = Vec::<(Word, Vec<usize>)>::new();
+        for word in class {
+            let labels = split(word, &listed);
+            match searched
+                .iter()
+                .find(|(s, l)| *l == labels && equivalent(s, word, answers, list))
+            {
+                Some((s, _)) => equivalents.entry(*s).or_default().push(*word),
+                None => searched.push((*word, labels)),
+            }
+        }
+    }
+    equivalents
+}
@@ -0,0 +1,375 @@
+//! Analyses of finished scores and of openers on their own: which letters and positions make good
+//! openers, clusters of openers that play alike, bootstrap intervals on the scores, and the
+//! smaller measures the results' columns report.
+
+use std::mem;
+use std::thread;
+
+use rand::Rng;
+
+use crate::alphabet::{self, BLANK};
+use crate::seed;
+use crate::word_list::WordList;
+use crate::{
+    combine_scores, filter_word_list, get_constraints, get_pattern, pattern_count, Word,
+    MAX_WORD_LENGTH,
+};
+
+/// How much an opener feature (a letter in a given position, or anywhere) is worth.
+#[derive(Clone, Copy, Debug)]
+pub struct LetterEffect {
+    pub letter: char,
+    /// `None` means the letter appears anywhere in the word.
+    pub position: Option<usize>,
+    /// The number of openers that have this feature.
+    pub count: usize,
+    /// Mean average guesses of openers that have the feature, minus the mean of those that don't.
+    pub average_effect: f32,
+    /// Same as `average_effect`, but for the success rate.
+    pub success_effect: f32,
+}
+
+/// Attributes opener scores to the letters and positions they use, across all of the scores.
+pub fn letter_effects(word_scores: &[(Word, (f32, f32))]) -> Vec<LetterEffect> {
+    let mut letters = word_scores
+        .iter()
+        .flat_map(|(w, _)| w.letters().to_vec())
+        .collect::<Vec<_>>();
+    letters.sort_unstable();
+    letters.dedup();
+
+    let length = word_scores
+        .iter()
+        .map(|(w, _)| w.length())
+        .max()
+        .unwrap_or(0);
+    let positions = (0..length).map(Some).chain([None]);
+
+    let mut effects = positions
+        .flat_map(|p| letters.iter().map(move |c| (*c, p)))
+        .filter_map(|(letter, position)| {
+            let has_feature = |word: &Word| match position {
+                Some(i) => word.letters().get(i) == Some(&letter),
+                None => word.letters().contains(&letter),
+            };
+
+            let (with, without): (Vec<_>, Vec<_>) =
+                word_scores.iter().partition(|(w, _)| has_feature(w));
+
+            if with.is_empty() || without.is_empty() {
+                return None;
+            }
+
+            let mean = |scores: &[&(Word, (f32, f32))], metric: fn(&(f32, f32)) -> f32| {
+                scores.iter().map(|(_, s)| metric(s)).sum::<f32>() / scores.len() as f32
+            };
+
+            Some(LetterEffect {
+                letter: alphabet::decode(letter),
+                position,
+                count: with.len(),
+                average_effect: mean(&with, |s| s.0) - mean(&without, |s| s.0),
+                success_effect: mean(&with, |s| s.1) - mean(&without, |s| s.1),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    effects.sort_by(|a, b| a.average_effect.partial_cmp(&b.average_effect).unwrap());
+    effects
+}
+
+/// Groups openers whose partitions of `answers` by feedback pattern are nearly identical, using the
+/// normalized mutual information between partitions as the similarity.  Only openers sharing all
+/// but one of their letters are compared.  Each cluster is a list of indices into `word_scores`,
+/// headed by its best-scoring member, assuming `word_scores` is sorted best first.
+pub fn cluster_openers(
+    word_scores: &[(Word, (f32, f32))],
+    answers: &[Word],
+    threshold: f32,
+) -> Vec<Vec<usize>> {
+    let partitions = word_scores
+        .iter()
+        .map(|(w, _)| {
+            answers
+                .iter()
+                .map(|a| get_pattern(a, w))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    let patterns = pattern_count(answers.first().map_or(0, Word::length));
+    let mut joint_counts = vec![0; patterns * patterns];
+
+    for (i, (word, _)) in word_scores.iter().enumerate() {
+        let cluster = clusters.iter_mut().find(|c| {
+            let representative = &word_scores[c[0]].0;
+            shared_letters(word, representative) + 1 >= word.length()
+                && mutual_information(
+                    &partitions[i],
+                    &partitions[c[0]],
+                    patterns,
+                    &mut joint_counts,
+                ) >= threshold
+        });
+
+        match cluster {
+            Some(c) => c.push(i),
+            None => clusters.push(vec![i]),
+        }
+    }
+
+    clusters
+}
+
+fn shared_letters(a: &Word, b: &Word) -> usize {
+    let mut b = *b;
+    a.letters()
+        .iter()
+        .filter(|c| match b.letters_mut().iter_mut().find(|d| d == c) {
+            Some(d) => {
+                *d = BLANK;
+                true
+            }
+            None => false,
+        })
+        .count()
+}
+
+/// Normalized mutual information between two partitions into `patterns` parts, 1.0 meaning they're
+/// identical.
+fn mutual_information(a: &[usize], b: &[usize], patterns: usize, joint_counts: &mut [u32]) -> f32 {
+    let count = a.len() as f32;
+    let mut a_counts = vec![0; patterns];
+    let mut b_counts = vec![0; patterns];
+
+    joint_counts.iter_mut().for_each(|c| *c = 0);
+    for (x, y) in a.iter().zip(b.iter()) {
+        a_counts[*x] += 1;
+        b_counts[*y] += 1;
+        joint_counts[x * patterns + y] += 1;
+    }
+
+    let entropy = |counts: &[u32]| {
+        counts
+            .iter()
+            .filter(|c| **c > 0)
+            .map(|c| {
+                let p = *c as f32 / count;
+                -p * p.log2()
+            })
+            .sum::<f32>()
+    };
+
+    let (a_entropy, b_entropy) = (entropy(&a_counts), entropy(&b_counts));
+    if a_entropy == 0.0 || b_entropy == 0.0 {
+        return if a_entropy == b_entropy { 1.0 } else { 0.0 };
+    }
+
+    let information = a_entropy + b_entropy - entropy(joint_counts);
+    information / (a_entropy * b_entropy).sqrt()
+}
+
+/// An opener's average guess count and rank, with 95% confidence intervals from resampling.
+#[derive(Clone, Copy, Debug)]
+pub struct BootstrapInterval {
+    pub word: Word,
+    pub average: f32,
+    pub average_range: (f32, f32),
+    /// 1 is the best rank.
+    pub rank: usize,
+    pub rank_range: (usize, usize),
+}
+
+/// Resamples the answers (with replacement) `resamples` times, rescoring and reranking every
+/// opener from its scores against each answer.  The resamples are spread over `threads`, each
+/// drawn from its own stream of `seed`, so they're the same however many threads there are.  The
+/// results are sorted by average, and openers that tie are ranked alphabetically.  There has to be
+/// at least one resample.
+pub fn bootstrap(
+    word_answer_scores: &[(Word, Vec<(f32, f32)>)],
+    resamples: usize,
+    seed: u64,
+    threads: usize,
+) -> Vec<BootstrapInterval> {
+    assert!(resamples > 0, "a bootstrap needs at least one resample");
+    let answer_count = word_answer_scores.first().map_or(0, |(_, s)| s.len());
+    let mut alphabetical = (0..word_answer_scores.len()).collect::<Vec<_>>();
+    alphabetical.sort_by_key(|i| word_answer_scores[*i].0);
+
+    // Each thread's averages and ranks for each opener, in no particular order, since the
+    // intervals only depend on which there are.
+    let work = |thread: usize| {
+        let mut averages = vec![Vec::new(); word_answer_scores.len()];
+        let mut ranks = vec![Vec::new(); word_answer_scores.len()];
+        let mut weights = vec![0.0; answer_count];
+        let mut order = alphabetical.clone();
+
+        for resample in (thread..resamples).step_by(threads) {
+            let mut rng = seed::stream(seed, "bootstrap", resample as u64);
+            weights.iter_mut().for_each(|w| *w = 0.0);
+            for _ in 0..answer_count {
+                weights[rng.gen_range(0..answer_count)] += 1.0;
+            }
+
+            let resampled = word_answer_scores
+                .iter()
+                .map(|(_, s)| combine_scores(s, Some(&weights)).0)
+                .collect::<Vec<_>>();
+
+            order.copy_from_slice(&alphabetical);
+            order.sort_by(|a, b| resampled[*a].partial_cmp(&resampled[*b]).unwrap());
+            for (rank, i) in order.iter().enumerate() {
+                averages[*i].push(resampled[*i]);
+                ranks[*i].push(rank + 1);
+            }
+        }
+        (averages, ranks)
+    };
+
+    let threads = threads.max(1);
+    let mut averages = vec![Vec::with_capacity(resamples); word_answer_scores.len()];
+    let mut ranks = vec![Vec::with_capacity(resamples); word_answer_scores.len()];
+    thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|thread| scope.spawn(move || work(thread)))
+            .collect::<Vec<_>>();
+        for worker in workers {
+            let (worker_averages, worker_ranks) = worker.join().unwrap();
+            for (all, some) in averages.iter_mut().zip(worker_averages) {
+                all.extend(some);
+            }
+            for (all, some) in ranks.iter_mut().zip(worker_ranks) {
+                all.extend(some);
+            }
+        }
+    });
+
+    /// The middle 95% of `values`, of which there's one for each resample.
+    fn interval<T: Copy + PartialOrd>(values: &mut [T]) -> (T, T) {
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let bound = |q: f32| values[((values.len() - 1) as f32 * q).round() as usize];
+        (bound(0.025), bound(0.975))
+    }
+
+    let mut intervals = word_answer_scores
+        .iter()
+        .zip(averages.iter_mut().zip(ranks.iter_mut()))
+        .map(
+            |((word, answer_scores), (averages, ranks))| BootstrapInterval {
+                word: *word,
+                average: combine_scores(answer_scores, None).0,
+                average_range: interval(averages),
+                rank: 0,
+                rank_range: interval(ranks),
+            },
+        )
+        .collect::<Vec<_>>();
+
+    intervals.sort_by(|a, b| {
+        a.average
+            .partial_cmp(&b.average)
+            .unwrap()
+            .then(a.word.cmp(&b.word))
+    });
+    intervals
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, interval)| interval.rank = i + 1);
+
+    intervals
+}
+
+/// How many guesses the entropy curve follows.
+pub const CURVE_GUESSES: usize = 3;
+
+/// The expected entropy, in bits, of the answers still possible after each of the first few
+/// guesses, starting with `opener` and following up with guesses drawn as the search draws them.
+/// An opener that narrows things down sooner has a curve that falls faster.
+pub fn entropy_curve(
+    opener: &Word,
+    answers: &[Word],
+    guess_words: &WordList,
+) -> [f32; CURVE_GUESSES] {
+    let mut curve = [0.0; CURVE_GUESSES];
+    for answer in answers {
+        add_entropy(
+            answer,
+            opener,
+            answers,
+            guess_words.indices(),
+            guess_words,
+            1.0 / answers.len() as f32,
+            &mut curve,
+        );
+    }
+    curve
+}
+
+/// Adds `guess`'s share of the entropy left after it to the first of `curve`, and after each of
+/// the guesses that follow it to the rest.  Once the answer's guessed, there's none left.
+fn add_entropy(
+    answer: &Word,
+    guess: &Word,
+    candidates: &[Word],
+    words: &[u32],
+    list: &WordList,
+    weight: f32,
+    curve: &mut [f32],
+) {
+    if guess == answer {
+        return;
+    }
+
+    let pattern = get_pattern(answer, guess);
+    let candidates = candidates
+        .iter()
+        .filter(|c| get_pattern(c, guess) == pattern)
+        .copied()
+        .collect::<Vec<_>>();
+    curve[0] += weight * (candidates.len() as f32).log2();
+
+    if curve.len() == 1 {
+        return;
+    }
+
+    let mut constraints = Vec::with_capacity(MAX_WORD_LENGTH);
+    let mut next_words = Vec::new();
+    get_constraints(answer, guess, &mut constraints);
+    filter_word_list(list, words, &constraints, &mut next_words);
+
+    let weight_sum = next_words.iter().map(|i| list.weight(*i)).sum::<f32>();
+    for i in &next_words {
+        add_entropy(
+            answer,
+            list.word(*i),
+            &candidates,
+            &next_words,
+            list,
+            weight * list.weight(*i) / weight_sum,
+            &mut curve[1..],
+        );
+    }
+}
+
+/// The chance of solving in exactly two guesses after `opener`, with the second guess played as
+/// well as it can be: as one of the answers left by the opener's feedback, which is right once for
+/// each distinct feedback pattern.
+pub fn two_guess_chance(opener: &Word, answers: &[Word]) -> f32 {
+    let mut seen = vec![false; pattern_count(opener.length())];
+    let patterns = answers
+        .iter()
+        .filter(|a| *a != opener)
+        .filter(|a| !mem::replace(&mut seen[get_pattern(a, opener)], true))
+        .count();
+    patterns as f32 / answers.len() as f32
+}
+
+/// The most answers left by any of the feedback `opener` can get, not counting the answer itself.
+pub fn largest_group(opener: &Word, answers: &[Word]) -> usize {
+    let mut counts = vec![0; pattern_count(opener.length())];
+    for answer in answers.iter().filter(|a| *a != opener) {
+        counts[get_pattern(answer, opener)] += 1;
+    }
+    counts.into_iter().max().unwrap_or(0)
+}
@@ -0,0 +1,81 @@
+//! Solves Spelling Bee puzzles: words of four or more letters made only from the puzzle's seven,
+//! reusing them as often as they like, and always using the center one.  A four-letter word
+//! scores a point and a longer one a point per letter, with seven more for a pangram, which uses
+//! every letter.  Unlike the rest of brutal, this works on words of any length.
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+
+use crate::exit::{self, OrInvalidInput};
+use crate::paths;
+
+const MIN_LENGTH: usize = 4;
+const PANGRAM_BONUS: usize = 7;
+
+fn score(word: &str, pangram: bool) -> usize {
+    match word.chars().count() {
+        MIN_LENGTH => 1,
+        length => length + if pangram { PANGRAM_BONUS } else { 0 },
+    }
+}
+
+/// Lists the words in `dictionary` that the puzzle accepts, highest scoring first, along with the
+/// puzzle's pangrams and the most it's possible to score.
+pub fn solve(dictionary: &Path, letters: &str, center: char) {
+    let letters = letters.to_lowercase().chars().collect::<Vec<_>>();
+    let center = center.to_ascii_lowercase();
+    if !letters.contains(&center) {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!("the center letter {} isn't one of the letters", center),
+        );
+    }
+    let mut distinct = letters.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    let dictionary = paths::find_list(dictionary);
+    let contents =
+        fs::read_to_string(&dictionary).or_invalid_input(|| dictionary.display().to_string());
+
+    let mut words = contents
+        .lines()
+        .map(str::trim)
+        .filter(|w| w.chars().count() >= MIN_LENGTH)
+        .filter(|w| w.chars().all(|c| letters.contains(&c)))
+        .filter(|w| w.contains(center))
+        .map(|w| {
+            let pangram = distinct.iter().all(|c| w.contains(*c));
+            (w, pangram, score(w, pangram))
+        })
+        .collect::<Vec<_>>();
+    words.sort_unstable_by_key(|(w, _, score)| (Reverse(*score), *w));
+    words.dedup_by_key(|(w, _, _)| *w);
+
+    let width = words
+        .iter()
+        .map(|(w, _, _)| w.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("word".len());
+    println!("{:w$} score", "word,", w = width + 1);
+    for (word, pangram, score) in &words {
+        println!(
+            "{:w$} {:5}{}",
+            format!("{},", word),
+            score,
+            if *pangram { ", pangram" } else { "" },
+            w = width + 1
+        );
+    }
+
+    let pangrams = words.iter().filter(|(_, p, _)| *p).count();
+    println!();
+    println!(
+        "Words: {}, pangrams: {}, maximum score: {}",
+        words.len(),
+        pangrams,
+        words.iter().map(|(_, _, s)| s).sum::<usize>()
+    );
+}
@@ -0,0 +1,163 @@
+//! What `brutal solve` found after each feedback to an opener, kept in the cache directory, so
+//! playing the same opener again looks the second guess up rather than searching for it again,
+//! and the only search left is for the few answers after that.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use crate::clue;
+use crate::fingerprint::Fingerprint;
+use crate::finish::Suggestion;
+use crate::paths;
+use crate::solver::Solver;
+use crate::storage::{Files, Storage};
+use crate::Word;
+
+const HEADER: &str = "# brutal book";
+const BOOKS_DIR: &str = "books";
+
+/// The best second guess found for one of the opener's feedback.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Entry {
+    /// The answers the feedback leaves.
+    pub answers: usize,
+    pub guess: Word,
+    /// The guesses the answers take in total with it.
+    pub total: usize,
+    /// Whether it's certainly the best, rather than the best found in time.
+    pub exact: bool,
+}
+
+pub struct Book {
+    storage: Files,
+    key: PathBuf,
+    opener: Word,
+    /// By the opener's feedback, as `get_pattern` gives it.
+    entries: HashMap<usize, Entry>,
+}
+
+/// Identifies what the second guesses depend on: the answers, the guesses in order of preference,
+/// and hard mode.
+fn fingerprint(solver: &Solver) -> u64 {
+    let mut fingerprint = Fingerprint::new("book");
+    fingerprint.add("answers");
+    solver.answers().iter().for_each(|w| {
+        fingerprint.add(w);
+    });
+    fingerprint.add("guesses");
+    let guess_words = solver.guess_words();
+    for i in guess_words.indices() {
+        fingerprint
+            .add(guess_words.word(*i))
+            .add_weight(guess_words.weight(*i));
+    }
+    if solver.hard_mode() {
+        fingerprint.add("hard mode");
+    }
+    fingerprint.finish()
+}
+
+impl Book {
+    /// The book for `opener`, in games with `solver`'s lists and rules, empty if it hasn't been
+    /// played with them before.  `None` if there's no cache directory to keep it in.
+    pub fn open(solver: &Solver, opener: Word) -> Option<Self> {
+        let storage = Files::new(paths::cache_dir()?);
+        let key = PathBuf::from(BOOKS_DIR)
+            .join(format!("{:016x}", fingerprint(solver)))
+            .join(opener.to_string());
+        let mut book = Self {
+            storage,
+            key,
+            opener,
+            entries: HashMap::new(),
+        };
+        match book.read() {
+            Ok(()) => (),
+            Err(e) => {
+                eprintln!(
+                    "warning: {}: {}, so it's started over",
+                    book.storage.locate(&book.key),
+                    e
+                );
+                book.entries.clear();
+            }
+        }
+        Some(book)
+    }
+
+    fn read(&mut self) -> Result<(), String> {
+        let contents = match self.storage.read(&self.key).map_err(|e| e.to_string())? {
+            Some(contents) => String::from_utf8(contents).map_err(|e| e.to_string())?,
+            None => return Ok(()),
+        };
+        let length = self.opener.length();
+        for (i, line) in contents.lines().enumerate() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let malformed = || format!("line {} is malformed", i + 1);
+            let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+            let [feedback, answers, guess, total, kind] = fields[..] else {
+                return Err(malformed());
+            };
+            let entry = Entry {
+                answers: answers.parse().map_err(|_| malformed())?,
+                guess: guess.parse().map_err(|_| malformed())?,
+                total: total.parse().map_err(|_| malformed())?,
+                exact: match kind {
+                    "exact" => true,
+                    "found" => false,
+                    _ => return Err(malformed()),
+                },
+            };
+            self.entries.insert(clue::pattern(feedback, length)?, entry);
+        }
+        Ok(())
+    }
+
+    /// What's been found for the opener getting `pattern`.
+    pub fn get(&self, pattern: usize) -> Option<&Entry> {
+        self.entries.get(&pattern)
+    }
+
+    /// Keeps `suggestion` for the opener getting `pattern`, if it was searched and it's better
+    /// than what was kept, or as good and certain, and writes the book back.
+    pub fn insert(&mut self, pattern: usize, suggestion: &Suggestion) {
+        if !suggestion.searched {
+            return;
+        }
+        let entry = Entry {
+            answers: suggestion.answers,
+            guess: suggestion.guess,
+            total: suggestion.total,
+            exact: suggestion.exact,
+        };
+        if let Some(kept) = self.entries.get(&pattern) {
+            if (kept.total, !kept.exact) <= (entry.total, !entry.exact) {
+                return;
+            }
+        }
+        self.entries.insert(pattern, entry);
+
+        let mut patterns = self.entries.keys().copied().collect::<Vec<_>>();
+        patterns.sort_unstable();
+        let mut contents = format!("{}\n", HEADER);
+        for pattern in patterns {
+            let entry = &self.entries[&pattern];
+            writeln!(
+                contents,
+                "{}, {}, {}, {}, {}",
+                clue::feedback(pattern, self.opener.length()),
+                entry.answers,
+                entry.guess,
+                entry.total,
+                if entry.exact { "exact" } else { "found" }
+            )
+            .unwrap();
+        }
+        if let Err(e) = self.storage.write(&self.key, contents.as_bytes()) {
+            eprintln!("warning: {}: {}", self.storage.locate(&self.key), e);
+        }
+    }
+}
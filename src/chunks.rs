@@ -0,0 +1,105 @@
+//! Results split across the files of a directory, for runs too big for one file to be handy.  Each
+//! chunk is a results file of its own, with the usual schema line and header, and the directory's
+//! index lists them in order, as lines of `file, words, min_avg, max_avg` after a line of
+//! `# brutal results index`.  Anything that reads results files reads a directory like this as
+//! all of its chunks together.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::alphabet;
+use crate::exit::{self, OrInvalidInput};
+use crate::Word;
+
+pub const INDEX: &str = "index.csv";
+const INDEX_HEADER: &str = "# brutal results index";
+
+/// How the results are split into chunks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Split {
+    /// A chunk for each first letter.
+    Letter,
+    /// A chunk for each tenth of a guess of average, like `3.6` for averages from 3.6 up to 3.7.
+    Average,
+}
+
+impl FromStr for Split {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "letter" => Ok(Self::Letter),
+            "average" => Ok(Self::Average),
+            _ => Err(format!("unknown split: {}", s)),
+        }
+    }
+}
+
+impl Split {
+    /// The chunk `word`, with an average of `average`, goes in, which sorts in the chunks' order.
+    pub fn chunk(self, word: &Word, average: f32) -> String {
+        match self {
+            Self::Letter => alphabet::decode(word.letters()[0]).to_string(),
+            Self::Average => format!("{:.1}", (average * 10.0).floor() / 10.0),
+        }
+    }
+}
+
+/// One of the chunks in an index.
+pub struct Chunk {
+    pub name: String,
+    pub words: usize,
+    pub min_average: f32,
+    pub max_average: f32,
+}
+
+impl Chunk {
+    pub fn file_name(&self) -> String {
+        format!("{}.csv", self.name)
+    }
+}
+
+pub fn write_index(dir: &Path, chunks: &[Chunk]) {
+    let filename = dir.join(INDEX);
+    let file = File::create(&filename).or_invalid_input(|| filename.display().to_string());
+    let mut file = BufWriter::new(file);
+    writeln!(file, "{}", INDEX_HEADER).expect("cannot write header");
+    writeln!(file, "{:9} {:>6}, min_avg, max_avg", "file,", "words").expect("cannot write header");
+    for chunk in chunks {
+        writeln!(
+            file,
+            "{:9} {:6}, {:7.3}, {:7.3}",
+            format!("{},", chunk.file_name()),
+            chunk.words,
+            chunk.min_average,
+            chunk.max_average
+        )
+        .expect("cannot write line");
+    }
+    file.flush().expect("cannot write line");
+}
+
+/// Whether `path` is a directory of chunks.
+pub fn is_chunked(path: &Path) -> bool {
+    path.join(INDEX).is_file()
+}
+
+/// The chunk files listed in `dir`'s index, in order.
+pub fn read_index(dir: &Path) -> Vec<PathBuf> {
+    let filename = dir.join(INDEX);
+    let contents =
+        fs::read_to_string(&filename).or_invalid_input(|| filename.display().to_string());
+    let mut lines = contents.lines();
+    if lines.next() != Some(INDEX_HEADER) {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!("{} isn't a results index", filename.display()),
+        );
+    }
+    lines
+        .filter(|l| !l.trim().is_empty() && !l.starts_with("file,"))
+        .map(|l| dir.join(l.split(',').next().unwrap().trim()))
+        .collect()
+}
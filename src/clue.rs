@@ -0,0 +1,464 @@
+//! What's known about a word, from a game's feedback or a crossword's filled-in squares, and the
+//! words that fit it.
+
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::alphabet::{self, BLANK};
+use crate::{get_pattern, Word, MAX_WORD_LENGTH};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Clue {
+    /// A guess and the feedback it got, like `crane:g.y..`, as `get_pattern` would give it.
+    Feedback { guess: Word, pattern: usize },
+    /// The known letters in their places, with `_` for the unknown ones, like `_r_n_`.
+    Template(Word),
+    /// Letters the word has, at least as many times as they're listed.
+    Contains(Vec<u8>),
+    /// Letters the word doesn't have.
+    Excludes(Vec<u8>),
+    /// The letters the word is made from, each used as many times as it's listed, or as many times
+    /// as it likes if they can be reused.
+    Bank { letters: Vec<u8>, reuse: bool },
+}
+
+impl FromStr for Clue {
+    type Err = String;
+
+    /// Parses feedback, like `crane:g.y..` with `g` for green, `y` for yellow and `.`, `-`, `x` or
+    /// `b` for gray, or else a template like `_r_n_`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (guess, feedback) = match s.split_once(':') {
+            Some(clue) => clue,
+            None => {
+                return s
+                    .replace('?', "_")
+                    .parse()
+                    .map(Self::Template)
+                    .map_err(|e| format!("{}: {}", e, s))
+            }
+        };
+        let guess = guess
+            .parse::<Word>()
+            .map_err(|e| format!("{}: {}", e, guess))?;
+
+        Ok(Self::Feedback {
+            guess,
+            pattern: pattern(feedback, guess.length())?,
+        })
+    }
+}
+
+/// Parses feedback for a guess of `length` letters, like `g.y..`, into a pattern as `get_pattern`
+/// would give it: `g` for green, `y` for yellow and `.`, `-`, `x` or `b` for gray.
+pub fn pattern(feedback: &str, length: usize) -> Result<usize, String> {
+    if feedback.chars().count() != length {
+        return Err(format!("feedback has incorrect length: {}", feedback));
+    }
+    feedback.chars().try_fold(0, |pattern, c| {
+        let digit = match c.to_ascii_lowercase() {
+            'g' => 2,
+            'y' => 1,
+            '.' | '-' | 'x' | 'b' => 0,
+            _ => return Err(format!("unknown feedback: {}", c)),
+        };
+        Ok(pattern * 3 + digit)
+    })
+}
+
+/// A pattern from `get_pattern`, for a guess of `length` letters, as feedback is written in a
+/// clue, like `g.y..`.
+pub fn feedback(mut pattern: usize, length: usize) -> String {
+    let mut feedback = vec![b'.'; length];
+    for f in feedback.iter_mut().rev() {
+        *f = [b'.', b'y', b'g'][pattern % 3];
+        pattern /= 3;
+    }
+    String::from_utf8(feedback).unwrap()
+}
+
+/// The feedback `guess` got, without colors: each letter followed by `*` if it's green, `?` if it's
+/// yellow and `-` if it's gray, like `c*r-a?n-e-`.
+pub fn annotated(guess: &Word, pattern: usize) -> String {
+    guess
+        .to_string()
+        .chars()
+        .zip(feedback(pattern, guess.length()).chars())
+        .flat_map(|(letter, f)| {
+            let symbol = match f {
+                'g' => '*',
+                'y' => '?',
+                _ => '-',
+            };
+            [letter, symbol]
+        })
+        .collect()
+}
+
+impl Clue {
+    /// The letters the words the clue's about have, if it says.
+    fn length(&self) -> Option<usize> {
+        match self {
+            Self::Feedback { guess, .. } => Some(guess.length()),
+            Self::Template(template) => Some(template.length()),
+            _ => None,
+        }
+    }
+
+    fn fits(&self, word: &Word) -> bool {
+        match self {
+            Self::Feedback { guess, pattern } => get_pattern(word, guess) == *pattern,
+            Self::Template(template) => template
+                .letters()
+                .iter()
+                .zip(word.letters())
+                .all(|(t, c)| *t == BLANK || t == c),
+            Self::Contains(letters) => {
+                let mut word = *word;
+                letters
+                    .iter()
+                    .all(|c| match word.letters_mut().iter_mut().find(|d| *d == c) {
+                        Some(d) => {
+                            *d = BLANK;
+                            true
+                        }
+                        None => false,
+                    })
+            }
+            Self::Excludes(letters) => !letters.iter().any(|c| word.letters().contains(c)),
+            Self::Bank {
+                letters,
+                reuse: true,
+            } => word.letters().iter().all(|c| letters.contains(c)),
+            Self::Bank {
+                letters,
+                reuse: false,
+            } => {
+                let mut letters = letters.clone();
+                word.letters()
+                    .iter()
+                    .all(|c| match letters.iter_mut().find(|d| *d == c) {
+                        Some(d) => {
+                            *d = BLANK;
+                            true
+                        }
+                        None => false,
+                    })
+            }
+        }
+    }
+}
+
+/// What's known about one letter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct LetterBounds {
+    letter: u8,
+    /// The fewest and most times it's in the word.
+    min: usize,
+    max: usize,
+    /// The places it isn't.
+    not_at: [bool; MAX_WORD_LENGTH],
+}
+
+/// Everything a set of clues other than banks says about a word, merged letter by letter into the
+/// tightest bounds they add up to, so a word fits the bounds exactly when it fits every clue.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bounds {
+    /// The letter known to be in each place.
+    places: [Option<u8>; MAX_WORD_LENGTH],
+    /// Sorted by letter.
+    letters: Vec<LetterBounds>,
+    /// The clues contradict each other, so nothing fits.
+    impossible: bool,
+    /// The letters the words have.
+    length: usize,
+}
+
+impl Bounds {
+    /// The bounds on `letter`, starting from knowing nothing about it.
+    fn letter(&mut self, letter: u8) -> &mut LetterBounds {
+        let i = match self.letters.binary_search_by_key(&letter, |l| l.letter) {
+            Ok(i) => i,
+            Err(i) => {
+                self.letters.insert(
+                    i,
+                    LetterBounds {
+                        letter,
+                        min: 0,
+                        max: self.length,
+                        not_at: [false; MAX_WORD_LENGTH],
+                    },
+                );
+                i
+            }
+        };
+        &mut self.letters[i]
+    }
+
+    fn place(&mut self, letter: u8, i: usize) {
+        match self.places[i] {
+            Some(placed) if placed != letter => self.impossible = true,
+            _ => self.places[i] = Some(letter),
+        }
+    }
+
+    /// Feedback says where each of the guess's letters is, or isn't, and how many of each there
+    /// are: at least as many as are green or yellow, and exactly that many if any are gray.
+    fn add_feedback(&mut self, guess: &Word, pattern: usize) {
+        let mut digits = vec![0; self.length];
+        let mut rest = pattern;
+        for d in digits.iter_mut().rev() {
+            *d = rest % 3;
+            rest /= 3;
+        }
+
+        let mut letters = guess.letters().to_vec();
+        letters.sort_unstable();
+        letters.dedup();
+        for letter in letters {
+            let mut found = 0;
+            let mut gray = false;
+            for (i, d) in digits
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| guess.letters()[*i] == letter)
+            {
+                match d {
+                    2 => {
+                        self.place(letter, i);
+                        found += 1;
+                    }
+                    _ => {
+                        self.letter(letter).not_at[i] = true;
+                        // Copies are only yellow while the answer has more of them, reading left
+                        // to right, so a yellow after a gray can't happen.
+                        if *d == 1 && gray {
+                            self.impossible = true;
+                        }
+                        found += (*d == 1) as usize;
+                        gray |= *d == 0;
+                    }
+                }
+            }
+
+            let bounds = self.letter(letter);
+            bounds.min = bounds.min.max(found);
+            if gray {
+                bounds.max = bounds.max.min(found);
+            }
+        }
+    }
+
+    /// Merges `clues` about words of `length` letters into bounds, and returns them along with the
+    /// clues that can't be merged.
+    pub fn from_clues(clues: &[Clue], length: usize) -> (Self, Vec<Clue>) {
+        let mut bounds = Self {
+            length,
+            ..Self::default()
+        };
+        let mut rest = Vec::new();
+        for clue in clues {
+            match clue {
+                Clue::Feedback { guess, pattern } => bounds.add_feedback(guess, *pattern),
+                Clue::Template(template) => template
+                    .letters()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c != BLANK)
+                    .for_each(|(i, c)| bounds.place(*c, i)),
+                Clue::Contains(letters) => {
+                    for letter in letters {
+                        let count = letters.iter().filter(|c| *c == letter).count();
+                        let letter = bounds.letter(*letter);
+                        letter.min = letter.min.max(count);
+                    }
+                }
+                Clue::Excludes(letters) => letters.iter().for_each(|c| bounds.letter(*c).max = 0),
+                Clue::Bank { .. } => rest.push(clue.clone()),
+            }
+        }
+        bounds.tighten();
+        (bounds, rest)
+    }
+
+    /// Counts the known places into each letter's bounds, checks the bounds agree, and drops
+    /// whatever the rest of them already imply.
+    fn tighten(&mut self) {
+        let places = self.places;
+        for (i, placed) in places.iter().enumerate() {
+            if let Some(placed) = *placed {
+                let count = self.places.iter().filter(|p| **p == Some(placed)).count();
+                let bounds = self.letter(placed);
+                bounds.min = bounds.min.max(count);
+                if bounds.not_at[i] {
+                    self.impossible = true;
+                }
+            }
+        }
+        let min_sum = self.letters.iter().map(|l| l.min).sum::<usize>();
+        if min_sum > self.length || self.letters.iter().any(|l| l.min > l.max) {
+            self.impossible = true;
+        }
+
+        for bounds in &mut self.letters {
+            let placed = places.iter().filter(|p| **p == Some(bounds.letter)).count();
+            for (i, not_at) in bounds.not_at.iter_mut().enumerate() {
+                // A place that has a known letter, or all of the letter's copies being placed,
+                // already rules it out.
+                if places[i].is_some() || bounds.max == placed {
+                    *not_at = false;
+                }
+            }
+            if bounds.min == placed {
+                bounds.min = 0;
+            }
+        }
+        self.letters
+            .retain(|l| l.min > 0 || l.max < self.length || l.not_at.contains(&true));
+    }
+
+    pub fn fits(&self, word: &Word) -> bool {
+        if self.impossible {
+            return false;
+        }
+        if self
+            .places
+            .iter()
+            .zip(word.letters())
+            .any(|(p, c)| p.is_some_and(|p| p != *c))
+        {
+            return false;
+        }
+        self.letters.iter().all(|bounds| {
+            let count = word
+                .letters()
+                .iter()
+                .filter(|c| **c == bounds.letter)
+                .count();
+            count >= bounds.min
+                && count <= bounds.max
+                && !word
+                    .letters()
+                    .iter()
+                    .zip(bounds.not_at)
+                    .any(|(c, not_at)| not_at && *c == bounds.letter)
+        })
+    }
+
+    /// Writes the bounds a line each: the known places, and then each letter's count and the
+    /// places it isn't, with places counted from 1.
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.impossible {
+            return writeln!(out, "Nothing fits: the clues contradict each other.");
+        }
+        let places = self.places[..self.length]
+            .iter()
+            .map(|p| alphabet::decode(p.unwrap_or(BLANK)))
+            .collect::<String>();
+        writeln!(out, "places: {}", places)?;
+
+        for bounds in &self.letters {
+            let placed = self
+                .places
+                .iter()
+                .filter(|p| **p == Some(bounds.letter))
+                .count();
+            let mut parts = Vec::new();
+            match (bounds.min.max(placed), bounds.max) {
+                (_, 0) => parts.push("none".to_string()),
+                (min, max) if min == max => parts.push(format!("exactly {}", max)),
+                (min, max) => {
+                    if min > placed {
+                        parts.push(format!("at least {}", min));
+                    }
+                    if max < self.length {
+                        parts.push(format!("at most {}", max));
+                    }
+                }
+            }
+            let not_at = (0..self.length)
+                .filter(|i| bounds.not_at[*i])
+                .map(|i| (i + 1).to_string())
+                .collect::<Vec<_>>();
+            if !not_at.is_empty() {
+                parts.push(format!("not in {}", not_at.join(", ")));
+            }
+            writeln!(
+                out,
+                "{}: {}",
+                alphabet::decode(bounds.letter),
+                parts.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The words in `words` that fit every one of `clues`, found as they're asked for.  Feedback is
+/// matched exactly, which is stricter than the search's constraints: they don't rule out a
+/// letter's position when another copy of it was gray there.  The clues are merged into
+/// [`Bounds`] first, so each word is only checked once for all of them.
+pub fn enumerate_answers<'a>(
+    words: &'a [Word],
+    clues: &'a [Clue],
+) -> impl Iterator<Item = Word> + 'a {
+    let length = words.first().map_or(0, Word::length);
+    let (bounds, rest) = Bounds::from_clues(clues, length);
+    words
+        .iter()
+        .filter(move |w| bounds.fits(w) && rest.iter().all(|c| c.fits(w)))
+        .copied()
+}
+
+/// Whether `guess` keeps to hard mode's rules after `clues`: every green letter of each guess's
+/// feedback in its place, and every yellow one somewhere, as many times as the feedback showed it.
+pub fn keeps_to(guess: &Word, clues: &[Clue]) -> bool {
+    clues.iter().all(|clue| match clue {
+        Clue::Feedback {
+            guess: played,
+            pattern,
+        } => {
+            let feedback = feedback(*pattern, played.length());
+            let mut letters = *guess;
+            // Greens first, so a yellow can't take a letter a green needs.
+            for (i, c) in played.letters().iter().enumerate() {
+                if feedback.as_bytes()[i] == b'g' {
+                    if letters.letters()[i] != *c {
+                        return false;
+                    }
+                    letters.letters_mut()[i] = BLANK;
+                }
+            }
+            played
+                .letters()
+                .iter()
+                .zip(feedback.bytes())
+                .filter(|(_, f)| *f == b'y')
+                .all(
+                    |(c, _)| match letters.letters_mut().iter_mut().find(|d| *d == c) {
+                        Some(d) => {
+                            *d = BLANK;
+                            true
+                        }
+                        None => false,
+                    },
+                )
+        }
+        _ => true,
+    })
+}
+
+/// Makes sure every clue that says how many letters its words have says `length`, the words'
+/// length.
+pub fn check_length(clues: &[Clue], length: usize) -> Result<(), String> {
+    match clues
+        .iter()
+        .find_map(|c| c.length().filter(|l| *l != length))
+    {
+        Some(l) => Err(format!(
+            "the clues are about words of {} letters, but the words have {}",
+            l, length
+        )),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,228 @@
+//! The columns of a results file, and how precisely each is written, along with the version of
+//! the file's layout, so tooling built on it can tell when that's changed.
+
+use std::str::FromStr;
+
+use crate::solver::Policy;
+
+/// Bumped whenever the layout of results files changes in a way that tooling reading them would
+/// notice.  Files from before there was a schema line are schema 1.
+pub const SCHEMA_VERSION: u32 = 2;
+
+const SCHEMA_PREFIX: &str = "# brutal results, schema ";
+const POLICY_PREFIX: &str = "# policy: ";
+const HARD_MODE_LINE: &str = "# hard mode";
+const WEIGHTED_ANSWERS_LINE: &str = "# weighted answers";
+const FINGERPRINT_PREFIX: &str = "# fingerprint: ";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Field {
+    Word,
+    /// How many letters the word has, which sets apart the results of lists with several lengths.
+    Length,
+    Average,
+    Success,
+    /// Percentile rank of the average, 100 being best.
+    AveragePercentile,
+    AverageZ,
+    SuccessPercentile,
+    SuccessZ,
+    /// The chance of solving in exactly two guesses.
+    InTwo,
+    /// The most answers the opener's feedback can leave.
+    Worst,
+}
+
+impl Field {
+    const ALL: [(Field, &'static str); 10] = [
+        (Field::Word, "word"),
+        (Field::Length, "length"),
+        (Field::Average, "average"),
+        (Field::Success, "success"),
+        (Field::AveragePercentile, "avg_pct"),
+        (Field::AverageZ, "avg_z"),
+        (Field::SuccessPercentile, "suc_pct"),
+        (Field::SuccessZ, "suc_z"),
+        (Field::InTwo, "in_two"),
+        (Field::Worst, "worst"),
+    ];
+
+    pub fn name(self) -> &'static str {
+        Self::ALL.iter().find(|(f, _)| *f == self).unwrap().1
+    }
+
+    fn default_precision(self) -> usize {
+        match self {
+            Field::Word | Field::Length | Field::Worst => 0,
+            Field::AveragePercentile | Field::SuccessPercentile => 2,
+            Field::Average | Field::AverageZ | Field::SuccessZ => 3,
+            Field::Success | Field::InTwo => 4,
+        }
+    }
+
+    /// Whether the field's worked out from the answers, rather than from the scores.
+    pub fn needs_answers(self) -> bool {
+        matches!(self, Field::InTwo | Field::Worst)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Column {
+    pub field: Field,
+    pub precision: usize,
+}
+
+impl Column {
+    pub fn width(&self) -> usize {
+        width(self.field.name())
+    }
+}
+
+/// Each column is as wide as its name, and at least seven characters.
+pub fn width(name: &str) -> usize {
+    name.len().max(7)
+}
+
+/// Whether `name` is one of the fields brutal writes, or `avg`, which is short for `average`.
+pub fn is_field(name: &str) -> bool {
+    name == "avg" || Field::ALL.iter().any(|(_, n)| *n == name)
+}
+
+impl From<Field> for Column {
+    fn from(field: Field) -> Self {
+        Self {
+            field,
+            precision: field.default_precision(),
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    /// Parses a field's name, like `average`, or its name and precision, like `average:4`.
+    /// `avg` is short for `average`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, precision) = match s.split_once(':') {
+            Some((name, precision)) => (
+                name,
+                Some(
+                    precision
+                        .parse()
+                        .map_err(|_| format!("invalid precision: {}", precision))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        let name = if name == "avg" { "average" } else { name };
+
+        let field = Field::ALL
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(f, _)| *f)
+            .ok_or_else(|| {
+                let names = Field::ALL.iter().map(|(_, n)| *n).collect::<Vec<_>>();
+                format!(
+                    "unknown column: {} (expected one of {})",
+                    name,
+                    names.join(", ")
+                )
+            })?;
+
+        let mut column = Column::from(field);
+        if let Some(precision) = precision {
+            column.precision = precision;
+        }
+        Ok(column)
+    }
+}
+
+/// The columns written unless others are asked for.
+pub fn default_columns(success: bool, in_two: bool) -> Vec<Column> {
+    let mut fields = vec![Field::Average];
+    if success {
+        fields.push(Field::Success);
+    }
+    fields.extend([Field::AveragePercentile, Field::AverageZ]);
+    if success {
+        fields.extend([Field::SuccessPercentile, Field::SuccessZ]);
+    }
+    if in_two {
+        fields.push(Field::InTwo);
+    }
+    fields.into_iter().map(Column::from).collect()
+}
+
+/// The line results files start with, naming their schema.
+pub fn schema_line() -> String {
+    format!("{}{}", SCHEMA_PREFIX, SCHEMA_VERSION)
+}
+
+/// The schema a line names, if it's a schema line.
+pub fn parse_schema(line: &str) -> Option<Result<u32, String>> {
+    line.strip_prefix(SCHEMA_PREFIX).map(|version| {
+        version
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid schema: {}", version))
+    })
+}
+
+/// The line naming the policy the scores were searched with.  It's left out for the random policy,
+/// so a file without one was searched with that, as every file was before there was a choice.
+pub fn policy_line(policy: Policy) -> Option<String> {
+    (policy != Policy::Random).then(|| format!("{}{}", POLICY_PREFIX, policy))
+}
+
+/// The policy a line names, if it's a policy line.
+pub fn parse_policy(line: &str) -> Option<Result<Policy, String>> {
+    line.strip_prefix(POLICY_PREFIX)
+        .map(|policy| policy.trim().parse())
+}
+
+/// The line saying the scores are for hard mode, if they are.  They're the same numbers either
+/// way, but a file without one doesn't say whether its openers were chosen with hard mode in mind.
+pub fn hard_mode_line(hard_mode: bool) -> Option<&'static str> {
+    hard_mode.then_some(HARD_MODE_LINE)
+}
+
+/// The line saying the scores are weighted by how likely each answer is, if they are, as the ones
+/// from `--answer-weights` are.
+pub fn weighted_answers_line(weighted: bool) -> Option<&'static str> {
+    weighted.then_some(WEIGHTED_ANSWERS_LINE)
+}
+
+/// The line identifying what the scores depend on besides the guesses, so a later run only reuses
+/// them if it would have scored the openers the same way.
+pub fn fingerprint_line(fingerprint: u64) -> String {
+    format!("{}{:016x}", FINGERPRINT_PREFIX, fingerprint)
+}
+
+/// The fingerprint a line gives, if it's a fingerprint line.
+pub fn parse_fingerprint(line: &str) -> Option<Result<u64, String>> {
+    line.strip_prefix(FINGERPRINT_PREFIX).map(|fingerprint| {
+        u64::from_str_radix(fingerprint.trim(), 16)
+            .map_err(|_| format!("invalid fingerprint: {}", fingerprint))
+    })
+}
+
+/// The header line for words of `word_length` letters and then `columns`.
+pub fn header(columns: &[Column], word_length: usize) -> String {
+    let names = columns
+        .iter()
+        .map(|c| format!("{:>w$}", c.field.name(), w = c.width()))
+        .collect::<Vec<_>>();
+    format!("{:w$} {}", "word,", names.join(", "), w = word_length + 1)
+}
+
+/// Fails on a schema line naming a schema newer than this version of brutal knows.
+pub fn check_schema(line: &str) -> Result<(), String> {
+    match parse_schema(line) {
+        Some(Ok(version)) if version > SCHEMA_VERSION => Err(format!(
+            "written with schema {}, but this version of brutal only reads up to schema {}",
+            version, SCHEMA_VERSION
+        )),
+        Some(Err(e)) => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,187 @@
+//! Upgrades results files written by older versions of brutal to the current schema, so tooling
+//! only has to read one layout.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::chunks;
+use crate::columns::{self, Column, Field, SCHEMA_VERSION};
+use crate::exit::{self, OrInvalidInput};
+use crate::metadata;
+use crate::solver::Policy;
+
+/// What's read from a results file: its schema version, policy, columns and lines of scores.
+struct Results {
+    version: u32,
+    policy: Option<Policy>,
+    /// Any other lines about how it was searched, like its fingerprint, kept as they are.
+    comments: Vec<String>,
+    header: Vec<Column>,
+    /// The names of any columns joined onto the end from `--metadata`.
+    joined: Vec<String>,
+    lines: Vec<String>,
+}
+
+/// Rewrites `results` with the current schema, leaving its columns, values and policy as they are, to
+/// `output`, or else to stdout.  Files from before there were headers are taken to have the
+/// columns they're read as: the word, the average and, if there's one more, the success rate.  A
+/// directory of chunks is merged into one file, in the order of its index, which its chunks'
+/// columns must all agree for.
+pub fn convert(results: &Path, output: Option<&Path>) {
+    let Results {
+        version,
+        policy,
+        comments,
+        header,
+        joined,
+        lines,
+    } = if chunks::is_chunked(results) {
+        let mut chunks = chunks::read_index(results)
+            .into_iter()
+            .map(|c| (read(&c), c));
+        let (mut merged, _) = chunks
+            .next()
+            .or_invalid_input(|| format!("{}: there are no results", results.display()));
+        for (chunk, filename) in chunks {
+            if (&chunk.header, &chunk.joined, chunk.policy)
+                != (&merged.header, &merged.joined, merged.policy)
+            {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!(
+                        "{} doesn't have the same columns and policy as the chunks before it",
+                        filename.display()
+                    ),
+                );
+            }
+            merged.version = merged.version.min(chunk.version);
+            merged.lines.extend(chunk.lines);
+        }
+        merged
+    } else {
+        read(results)
+    };
+
+    let mut out: Box<dyn Write> = match output {
+        Some(output) => {
+            Box::new(File::create(output).or_invalid_input(|| output.display().to_string()))
+        }
+        None => Box::new(io::stdout()),
+    };
+    writeln!(out, "{}", columns::schema_line()).expect("cannot write header");
+    if let Some(line) = policy.and_then(columns::policy_line) {
+        writeln!(out, "{}", line).expect("cannot write header");
+    }
+    for line in &comments {
+        writeln!(out, "{}", line).expect("cannot write header");
+    }
+    let word_length = lines
+        .first()
+        .and_then(|l| l.split(',').next())
+        .map_or(0, |w| w.trim().chars().count());
+    writeln!(
+        out,
+        "{}{}",
+        columns::header(&header, word_length),
+        metadata::header(&joined)
+    )
+    .expect("cannot write header");
+    for line in &lines {
+        writeln!(out, "{}", line).expect("cannot write line");
+    }
+
+    if let Some(output) = output {
+        println!(
+            "Converted {} from schema {} to schema {}, in {}",
+            results.display(),
+            version,
+            SCHEMA_VERSION,
+            output.display()
+        );
+    }
+}
+
+fn read(results: &Path) -> Results {
+    let contents = fs::read_to_string(results).or_invalid_input(|| results.display().to_string());
+    let location = |i: usize| format!("{}:{}", results.display(), i + 1);
+
+    let mut version = 1;
+    let mut policy = None;
+    let mut comments = Vec::new();
+    let mut lines = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        if let Some(schema) = columns::parse_schema(line) {
+            columns::check_schema(line).or_invalid_input(|| location(i));
+            version = schema.or_invalid_input(|| location(i));
+        } else if let Some(parsed) = columns::parse_policy(line) {
+            policy = Some(parsed.or_invalid_input(|| location(i)));
+        } else if line.starts_with('#') {
+            comments.push(line.to_string());
+        } else if !line.trim().is_empty() {
+            lines.push((i, line));
+        }
+    }
+
+    let (header, joined) = match lines.first() {
+        Some((i, line)) if line.starts_with("word,") => {
+            let mut names = line.split(',').map(str::trim).skip(1).peekable();
+            let mut header = Vec::new();
+            while let Some(name) =
+                names.next_if(|n| columns::is_field(n.split(':').next().unwrap()))
+            {
+                header.push(name.parse::<Column>().or_invalid_input(|| location(*i)));
+            }
+            let joined = names.map(str::to_string).collect::<Vec<_>>();
+            // Joined columns come after all of brutal's own.
+            if let Some(name) = joined.iter().find(|n| columns::is_field(n)) {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!("{}: {} comes after the joined columns", location(*i), name),
+                );
+            }
+            lines.remove(0);
+            (header, joined)
+        }
+        Some((i, line)) => match line.split(',').count() {
+            2 => (vec![Field::Average.into()], Vec::new()),
+            3 => (
+                vec![Field::Average.into(), Field::Success.into()],
+                Vec::new(),
+            ),
+            _ => exit::fail(
+                exit::INVALID_INPUT,
+                format!(
+                    "{}: there's no header to say what the columns are",
+                    location(*i)
+                ),
+            ),
+        },
+        None => exit::fail(
+            exit::INVALID_INPUT,
+            format!("{}: there are no results", results.display()),
+        ),
+    };
+
+    for (i, line) in &lines {
+        if line.split(',').count() != header.len() + joined.len() + 1 {
+            exit::fail(
+                exit::INVALID_INPUT,
+                format!(
+                    "{}: expected {} columns, as in the header",
+                    location(*i),
+                    header.len() + joined.len() + 1
+                ),
+            );
+        }
+    }
+
+    Results {
+        version,
+        policy,
+        comments,
+        header,
+        joined,
+        lines: lines.into_iter().map(|(_, l)| l.to_string()).collect(),
+    }
+}
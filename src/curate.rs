@@ -0,0 +1,161 @@
+//! Builds word lists for custom games out of a bigger dictionary, by filtering out the words that
+//! wouldn't make fair answers or guesses.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::exit::OrInvalidInput;
+use crate::frequency::WordFrequencies;
+use crate::{paths, Word};
+
+/// A rule for leaving words out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    /// Words ending in `s` or `es` whose stem is also in the dictionary.
+    Plurals,
+    /// Capitalized words.
+    ProperNouns,
+    /// Words no more frequent than this.
+    Frequency(f32),
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    /// Parses `plurals`, `proper-nouns` or `frequency>X`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plurals" => Ok(Self::Plurals),
+            "proper-nouns" => Ok(Self::ProperNouns),
+            _ => match s.strip_prefix("frequency>") {
+                Some(threshold) => threshold
+                    .parse()
+                    .map(Self::Frequency)
+                    .map_err(|_| format!("invalid frequency: {}", threshold)),
+                None => Err(format!("unknown filter: {}", s)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Plurals => write!(f, "plurals"),
+            Self::ProperNouns => write!(f, "proper-nouns"),
+            Self::Frequency(threshold) => write!(f, "frequency>{}", threshold),
+        }
+    }
+}
+
+/// Writes the words of `length` letters in `source` that pass every filter to `output`, in the
+/// order they're listed, and reports what was left out.  Why each word was left out is written to
+/// `report`, if there is one.
+pub fn curate(
+    source: &Path,
+    filters: &[Filter],
+    length: usize,
+    frequencies: Option<&WordFrequencies>,
+    output: &Path,
+    report: Option<&Path>,
+) {
+    let source = paths::find_list(source);
+    let contents = fs::read_to_string(&source).or_invalid_input(|| source.display().to_string());
+    let lines = contents
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect::<Vec<_>>();
+    // Every word in the dictionary, of any length, for finding the stems of plurals.
+    let dictionary = lines
+        .iter()
+        .map(|l| l.to_lowercase())
+        .collect::<HashSet<_>>();
+
+    let mut kept = Vec::new();
+    let mut seen = HashSet::new();
+    let mut removed = Vec::new();
+    let mut wrong_length = 0;
+
+    for line in lines {
+        if line.chars().count() != length {
+            wrong_length += 1;
+            continue;
+        }
+        let word = match line.to_lowercase().parse::<Word>() {
+            Ok(word) if line.chars().all(char::is_alphabetic) => word,
+            _ => {
+                wrong_length += 1;
+                continue;
+            }
+        };
+
+        let rejected = filters.iter().enumerate().find_map(|(i, f)| {
+            rejection(*f, line, &dictionary, &word, frequencies).map(|reason| (i, reason))
+        });
+        match rejected {
+            Some((filter, reason)) => removed.push((line.to_string(), filter, reason)),
+            None if seen.insert(word) => kept.push(word),
+            None => (),
+        }
+    }
+
+    let mut file = File::create(output).expect("cannot open output file");
+    for word in &kept {
+        writeln!(file, "{}", word).expect("cannot write word");
+    }
+
+    if let Some(report) = report {
+        let mut file = File::create(report).expect("cannot open report");
+        writeln!(file, "word, reason").expect("cannot write header");
+        for (word, _, reason) in &removed {
+            writeln!(file, "{}, {}", word, reason).expect("cannot write line");
+        }
+    }
+
+    println!(
+        "Kept {} of {} words of {} letters.",
+        kept.len(),
+        kept.len() + removed.len(),
+        length
+    );
+    for (i, filter) in filters.iter().enumerate() {
+        let count = removed.iter().filter(|(_, f, _)| *f == i).count();
+        println!("  Removed by {}: {}", filter, count);
+    }
+    println!("  Skipped for their length or characters: {}", wrong_length);
+}
+
+/// Why `filter` leaves out `line`, if it does.
+fn rejection(
+    filter: Filter,
+    line: &str,
+    dictionary: &HashSet<String>,
+    word: &Word,
+    frequencies: Option<&WordFrequencies>,
+) -> Option<String> {
+    match filter {
+        Filter::Plurals => {
+            let lower = line.to_lowercase();
+            let stem = [lower.strip_suffix("es"), lower.strip_suffix('s')]
+                .into_iter()
+                .flatten()
+                .filter(|_| !lower.ends_with("ss"))
+                .find(|stem| dictionary.contains(*stem))?;
+            Some(format!("plural of {}", stem))
+        }
+        Filter::ProperNouns => line
+            .starts_with(char::is_uppercase)
+            .then(|| "proper noun".to_string()),
+        Filter::Frequency(threshold) => {
+            let frequencies =
+                frequencies.or_invalid_input(|| "the frequency filter requires --frequency-list");
+            let frequency = frequencies.get(word);
+            (frequency <= threshold).then(|| format!("rare ({})", frequency))
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Which guess each game is solved on, so one search without a guess limit gives the score at any
+//! limit.  The follow-up guesses don't depend on the limit, so a game that's solved on its fifth
+//! guess without one is solved on its fifth with a limit of five or more, and lost with less.
+//!
+//! A distribution's shown as the first guess the answer can be found on, and the chance of finding
+//! it on that guess and each one after, leaving off the zeros at the end, like `2:0.25 0.75`.
+//! Chance left over past the tracked guesses follows as `+chance@guess`, where the guess is the
+//! average it's found on.
+
+use std::fmt;
+use std::mem;
+
+use crate::word_list::WordList;
+use crate::{
+    alloc, filter_word_list, get_constraints, most_informative, SearchState, Word, GUESS_LIMIT,
+};
+
+/// Guesses past this many are counted together, so limits can only go up to it.
+pub const TRACKED_GUESSES: usize = 2 * GUESS_LIMIT;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Distribution {
+    /// The chance of solving on each guess, from the first.
+    on_guess: [f32; TRACKED_GUESSES],
+    /// The chance of solving on a later guess, and that times the guess it's solved on.
+    later: (f32, f32),
+}
+
+impl Distribution {
+    fn solved(guess: usize) -> Self {
+        let mut distribution = Self::default();
+        match guess {
+            1..=TRACKED_GUESSES => distribution.on_guess[guess - 1] = 1.0,
+            _ => distribution.later = (1.0, guess as f32),
+        }
+        distribution
+    }
+
+    fn add(&mut self, other: &Self, weight: f32) {
+        for (a, b) in self.on_guess.iter_mut().zip(other.on_guess) {
+            *a += weight * b;
+        }
+        self.later.0 += weight * other.later.0;
+        self.later.1 += weight * other.later.1;
+    }
+
+    /// The mean of `distributions`.
+    pub fn mean(distributions: &[Self]) -> Self {
+        Self::weighted_mean(distributions, None)
+    }
+
+    /// The mean of `distributions`, each counting as much as its weight, if they're weighted.
+    pub fn weighted_mean(distributions: &[Self], weights: Option<&[f32]>) -> Self {
+        let total = weights.map_or(distributions.len() as f32, |w| w.iter().sum());
+        let mut mean = Self::default();
+        for (i, distribution) in distributions.iter().enumerate() {
+            mean.add(distribution, weights.map_or(1.0, |w| w[i]) / total);
+        }
+        mean
+    }
+
+    /// The chance of solving on each guess, from the first, up to [`TRACKED_GUESSES`].
+    pub fn on_guesses(&self) -> &[f32] {
+        &self.on_guess
+    }
+
+    /// The most guesses it can take, or `None` if that's past [`TRACKED_GUESSES`].
+    pub fn worst(&self) -> Option<usize> {
+        match self.later.0 > 0.0 {
+            true => None,
+            false => self.on_guess.iter().rposition(|c| *c > 0.0).map(|i| i + 1),
+        }
+    }
+
+    /// The average guess count and success rate with games ending after `limit` guesses, which is
+    /// either at most [`TRACKED_GUESSES`] or `usize::MAX` for no limit, as a search with that limit
+    /// would find them.
+    pub fn score(&self, limit: usize) -> (f32, f32) {
+        let mut guesses_sum = 0.0;
+        let mut success_sum = 0.0;
+        for (i, chance) in self.on_guess.iter().take(limit).enumerate() {
+            guesses_sum += (i + 1) as f32 * chance;
+            success_sum += chance;
+        }
+        if limit > TRACKED_GUESSES {
+            assert!(limit == usize::MAX, "guess limit is untracked");
+            guesses_sum += self.later.1;
+            success_sum += self.later.0;
+        }
+
+        if success_sum > 0.0 {
+            (guesses_sum / success_sum, success_sum)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+}
+
+/// Chances are written to four decimal places, without the zeros after them.
+fn write_chance(f: &mut fmt::Formatter, chance: f32) -> fmt::Result {
+    let chance = format!("{:.4}", chance);
+    write!(f, "{}", chance.trim_end_matches('0').trim_end_matches('.'))
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let first = self.on_guess.iter().position(|c| *c > 0.0);
+        let last = self.on_guess.iter().rposition(|c| *c > 0.0);
+        if let (Some(first), Some(last)) = (first, last) {
+            write!(f, "{}:", first + 1)?;
+            for (i, chance) in self.on_guess[first..=last].iter().enumerate() {
+                if i > 0 {
+                    write!(f, " ")?;
+                }
+                write_chance(f, *chance)?;
+            }
+        }
+        if self.later.0 > 0.0 {
+            if first.is_some() {
+                write!(f, " ")?;
+            }
+            write!(f, "+")?;
+            write_chance(f, self.later.0)?;
+            write!(f, "@{:.2}", self.later.1 / self.later.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the distribution of `guess` as an opener against each of the answers, calling
+/// `on_answer` before each one.  `state` must have no guess limit.
+pub fn score_word(
+    guess: &Word,
+    answers: &[Word],
+    guess_words: &WordList,
+    state: &mut SearchState,
+    mut on_answer: impl FnMut(&Word),
+) -> Vec<Distribution> {
+    answers
+        .iter()
+        .map(|answer| {
+            on_answer(answer);
+
+            let allocations = alloc::thread_allocations();
+            let distribution =
+                get_distribution(answer, guess, guess_words.indices(), 1, guess_words, state);
+            state.allocations += alloc::thread_allocations() - allocations;
+
+            distribution
+        })
+        .collect()
+}
+
+/// The same search as `get_score`'s without a guess limit, keeping which guess the answer's found
+/// on rather than the average.
+fn get_distribution(
+    answer: &Word,
+    guess: &Word,
+    words: &[u32],
+    starting_guess: usize,
+    list: &WordList,
+    state: &mut SearchState,
+) -> Distribution {
+    state.search.nodes += 1;
+
+    if answer == guess {
+        state.search.leaves += 1;
+        return Distribution::solved(starting_guess);
+    }
+
+    // The distribution's thrown away if it's cancelled, so it doesn't matter what's returned.
+    if state.is_cancelled() {
+        return Distribution::default();
+    }
+
+    let depth = starting_guess - 1;
+    if depth == state.words.len() {
+        state.grow();
+    }
+    let mut next_words = mem::take(&mut state.words[depth]);
+
+    get_constraints(answer, guess, &mut state.constraints[depth]);
+    filter_word_list(list, words, &state.constraints[depth], &mut next_words);
+
+    let distribution = if next_words.len() > state.greedy_above {
+        let guess = list.word(most_informative(&next_words, list));
+        get_distribution(answer, guess, &next_words, starting_guess + 1, list, state)
+    } else if let Some(distribution) =
+        get_pair_distribution(answer, &next_words, starting_guess, list)
+    {
+        state.search.leaves += 1;
+        distribution
+    } else {
+        let weight_sum = next_words.iter().map(|i| list.weight(*i)).sum::<f32>();
+        let mut distribution = Distribution::default();
+        for i in next_words.iter() {
+            let next = get_distribution(
+                answer,
+                list.word(*i),
+                &next_words,
+                starting_guess + 1,
+                list,
+                state,
+            );
+            distribution.add(&next, list.weight(*i) / weight_sum);
+        }
+        distribution
+    };
+
+    state.words[depth] = next_words;
+    distribution
+}
+
+/// With only the answer and one other word left, the answer's found on the next guess or the one
+/// after.
+fn get_pair_distribution(
+    answer: &Word,
+    words: &[u32],
+    starting_guess: usize,
+    list: &WordList,
+) -> Option<Distribution> {
+    let (a, b) = match words {
+        [a, b] if list.word(*a) == answer && list.word(*b) != answer => (*a, *b),
+        [b, a] if list.word(*a) == answer && list.word(*b) != answer => (*a, *b),
+        _ => return None,
+    };
+
+    let weight_sum = list.weight(a) + list.weight(b);
+    let mut distribution = Distribution::default();
+    distribution.add(
+        &Distribution::solved(starting_guess + 1),
+        list.weight(a) / weight_sum,
+    );
+    distribution.add(
+        &Distribution::solved(starting_guess + 2),
+        list.weight(b) / weight_sum,
+    );
+    Some(distribution)
+}
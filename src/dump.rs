@@ -0,0 +1,113 @@
+//! Writes out the tables the search works from, and what it found, for looking into a run's speed
+//! or results without a debugger.  The tables are written before the search starts, so they can be
+//! looked at while a long one is still going.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use crate::exit::OrInvalidInput;
+use crate::solver::{Solver, WorkerStats};
+use crate::{alphabet, get_pattern, pattern_count, Word};
+
+/// Writes the letter codes, the answers, and the guess list in the order the search takes it, with
+/// each guess's weight.
+pub fn write_tables(dir: &Path, solver: &Solver) {
+    fs::create_dir_all(dir).or_invalid_input(|| dir.display().to_string());
+    let list = solver.guess_words();
+
+    let mut codes = solver
+        .answers()
+        .iter()
+        .chain(list.indices().iter().map(|i| list.word(*i)))
+        .flat_map(|w| w.letters().to_vec())
+        .collect::<Vec<_>>();
+    codes.sort_unstable();
+    codes.dedup();
+
+    let mut file = create(dir, "letters.csv");
+    writeln!(file, "code, letter").expect("cannot write header");
+    for code in codes {
+        writeln!(file, "{:4}, {:>6}", code, alphabet::decode(code)).expect("cannot write line");
+    }
+
+    let mut file = create(dir, "answers.txt");
+    for answer in solver.answers() {
+        writeln!(file, "{}", answer).expect("cannot write line");
+    }
+
+    let mut file = create(dir, "guesses.csv");
+    writeln!(
+        file,
+        "index, {:w$} weight",
+        "word,",
+        w = list.word_length() + 1
+    )
+    .expect("cannot write header");
+    for i in list.indices() {
+        writeln!(file, "{:5}, {}, {:.6}", i, list.word(*i), list.weight(*i))
+            .expect("cannot write line");
+    }
+}
+
+/// Writes how each scored opener partitions the answers by feedback, with each pattern as a digit
+/// per position (0 gray, 1 yellow, 2 green), and what each worker searched.
+pub fn write_results(
+    dir: &Path,
+    word_scores: &[(Word, (f32, f32))],
+    answers: &[Word],
+    worker_stats: &[WorkerStats],
+) {
+    let length = answers.first().map_or(0, Word::length);
+    let mut file = create(dir, "partitions.csv");
+    writeln!(file, "{:w$} pattern, answers", "word,", w = length + 1).expect("cannot write header");
+    let mut counts = vec![0; pattern_count(length)];
+    for (word, _) in word_scores {
+        counts.fill(0);
+        for answer in answers {
+            counts[get_pattern(answer, word)] += 1;
+        }
+        for (pattern, count) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+            writeln!(
+                file,
+                "{}, {:>7}, {:7}",
+                word,
+                digits(pattern, length),
+                count
+            )
+            .expect("cannot write line");
+        }
+    }
+
+    let mut file = create(dir, "workers.csv");
+    writeln!(file, "worker, words, busy_secs, nodes, leaves, allocations")
+        .expect("cannot write header");
+    for (i, stats) in worker_stats.iter().enumerate() {
+        writeln!(
+            file,
+            "{}, {}, {:.3}, {}, {}, {}",
+            i,
+            stats.words,
+            stats.busy.as_secs_f64(),
+            stats.search.nodes,
+            stats.search.leaves,
+            stats.allocations
+        )
+        .expect("cannot write line");
+    }
+}
+
+fn create(dir: &Path, name: &str) -> File {
+    let path = dir.join(name);
+    File::create(&path).or_invalid_input(|| path.display().to_string())
+}
+
+/// A pattern from `get_pattern` for words of `length` letters, written out a digit per position.
+fn digits(mut pattern: usize, length: usize) -> String {
+    let mut digits = vec![b'0'; length];
+    for d in digits.iter_mut().rev() {
+        *d += (pattern % 3) as u8;
+        pattern /= 3;
+    }
+    String::from_utf8(digits).unwrap()
+}
@@ -0,0 +1,100 @@
+//! Exit codes that tell apart the ways a run can end, for scripts running brutal unattended.  A
+//! panic exits with Rust's usual 101, which is always a bug or an I/O failure.
+
+use std::fmt::Display;
+use std::mem;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::solver::CancellationToken;
+
+/// A word list, frequency list or results file is missing or malformed.  Bad arguments exit with
+/// this too.
+pub const INVALID_INPUT: i32 = 2;
+/// The run was interrupted, and only the words finished by then were written.
+pub const INTERRUPTED: i32 = 3;
+/// The journal given was left by a run with different answers or guesses.
+pub const JOURNAL_MISMATCH: i32 = 4;
+
+/// What's run before exiting, since exiting straight away skips the destructors that would
+/// otherwise have done it.
+type Hook = Box<dyn FnOnce() + Send>;
+
+static HOOKS: Mutex<Vec<Hook>> = Mutex::new(Vec::new());
+
+/// Runs `hook` if the run fails or is interrupted for good, such as to sync a journal that's still
+/// holding its last lines.
+pub fn on_exit(hook: impl FnOnce() + Send + 'static) {
+    lock_hooks().push(Box::new(hook));
+}
+
+fn lock_hooks() -> std::sync::MutexGuard<'static, Vec<Hook>> {
+    HOOKS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Exits with `code` after running the hooks, each only once, so a hook that fails in turn doesn't
+/// run them again.
+pub fn exit(code: i32) -> ! {
+    let hooks = mem::take(&mut *lock_hooks());
+    hooks.into_iter().for_each(|hook| hook());
+    process::exit(code)
+}
+
+pub fn fail(code: i32, message: impl Display) -> ! {
+    eprintln!("error: {}", message);
+    exit(code)
+}
+
+/// For failing with [`INVALID_INPUT`] rather than panicking.
+pub trait OrInvalidInput<T> {
+    fn or_invalid_input<M: Display>(self, message: impl FnOnce() -> M) -> T;
+}
+
+impl<T, E: Display> OrInvalidInput<T> for Result<T, E> {
+    fn or_invalid_input<M: Display>(self, message: impl FnOnce() -> M) -> T {
+        self.unwrap_or_else(|e| fail(INVALID_INPUT, format!("{}: {}", message(), e)))
+    }
+}
+
+impl<T> OrInvalidInput<T> for Option<T> {
+    fn or_invalid_input<M: Display>(self, message: impl FnOnce() -> M) -> T {
+        self.unwrap_or_else(|| fail(INVALID_INPUT, message()))
+    }
+}
+
+static INTERRUPTED_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// The search an interrupt cancels.  A run can search more than once, but only ever installs the
+/// one handler.
+static CANCELLATION: Mutex<Option<CancellationToken>> = Mutex::new(None);
+
+/// On the first interrupt, lets the words being searched finish so their scores are kept.  On the
+/// second, cancels them, and on the third, exits straight away.
+pub fn handle_interrupts(cancellation: CancellationToken) {
+    let installed = CANCELLATION
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(cancellation)
+        .is_some();
+    if installed {
+        return;
+    }
+
+    ctrlc::set_handler(move || {
+        let cancellation = CANCELLATION.lock().unwrap_or_else(PoisonError::into_inner);
+        let cancellation = cancellation.as_ref().expect("nothing to cancel");
+        if !INTERRUPTED_FLAG.swap(true, Ordering::SeqCst) {
+            eprintln!("Stopping after the words being searched.  Interrupt again to drop them and stop now.");
+        } else if !cancellation.is_cancelled() {
+            cancellation.cancel();
+        } else {
+            exit(INTERRUPTED);
+        }
+    })
+    .expect("cannot handle interrupts");
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED_FLAG.load(Ordering::SeqCst)
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::alphabet::BLANK;
+use crate::word_list::WordList;
+use crate::Word;
+
+/// Why the search ran out of guesses, judged by the words that were still possible at the time.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum FailureCause {
+    /// The remaining words only differ in one or two positions, like _ATCH.
+    Family(Word),
+    /// The remaining words use the same letters, and only differ in where and how often.
+    RepeatedLetters,
+    /// The remaining words have nothing obvious in common.
+    Scattered,
+    /// The answer isn't in the guess list, so it could never be guessed.
+    Unguessable,
+}
+
+impl fmt::Display for FailureCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FailureCause::Family(mask) => write!(f, "{} family", mask),
+            FailureCause::RepeatedLetters => write!(f, "repeated letters"),
+            FailureCause::Scattered => write!(f, "scattered"),
+            FailureCause::Unguessable => write!(f, "unguessable"),
+        }
+    }
+}
+
+impl FailureCause {
+    pub fn classify(answer: &Word, list: &WordList, indices: &[u32]) -> Self {
+        let words = || indices.iter().map(|i| list.word(*i));
+
+        if !words().any(|w| w == answer) {
+            return FailureCause::Unguessable;
+        }
+
+        let mut mask = *answer;
+        for (i, c) in mask.letters_mut().iter_mut().enumerate() {
+            if words().any(|w| w.letters()[i] != *c) {
+                *c = BLANK;
+            }
+        }
+        let blanks = mask.letters().iter().filter(|c| **c == BLANK).count();
+
+        // The distinct letters of a word, in order.
+        let letters = |word: &Word| {
+            let mut letters = *word;
+            let sorted = letters.letters_mut();
+            sorted.sort_unstable();
+            for i in (1..sorted.len()).rev() {
+                if sorted[i] == sorted[i - 1] {
+                    sorted[i..].rotate_left(1);
+                    *sorted.last_mut().unwrap() = BLANK;
+                }
+            }
+            letters
+        };
+
+        if blanks <= 1 {
+            FailureCause::Family(mask)
+        } else if words().all(|w| letters(w) == letters(answer)) {
+            FailureCause::RepeatedLetters
+        } else if blanks == 2 {
+            FailureCause::Family(mask)
+        } else {
+            FailureCause::Scattered
+        }
+    }
+}
+
+/// The total probability of failing for each cause, over every answer an opener was scored against.
+#[derive(Clone, Debug, Default)]
+pub struct FailureLog(pub HashMap<FailureCause, f32>);
+
+impl FailureLog {
+    /// Records a failure to find `answer` among the words at `indices`, reached with probability
+    /// `weight`.
+    pub fn record(&mut self, answer: &Word, list: &WordList, indices: &[u32], weight: f32) {
+        *self
+            .0
+            .entry(FailureCause::classify(answer, list, indices))
+            .or_insert(0.0) += weight;
+    }
+
+    pub fn merge(&mut self, other: &FailureLog) {
+        for (cause, weight) in other.0.iter() {
+            *self.0.entry(cause.clone()).or_insert(0.0) += weight;
+        }
+    }
+
+    /// The causes, most likely first.
+    pub fn sorted(&self) -> Vec<(&FailureCause, f32)> {
+        let mut causes = self.0.iter().map(|(c, w)| (c, *w)).collect::<Vec<_>>();
+        causes.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(b.0)));
+        causes
+    }
+}
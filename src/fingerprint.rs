@@ -0,0 +1,36 @@
+//! Fingerprints of what something kept between runs was made from, like a journal's answers and
+//! guesses, so it's only used by a run it applies to.  They're kept on disk and compared across
+//! machines and builds, so they're a SHA-256 of the words and parameters as they're written out,
+//! rather than of how they happen to be held in memory: the same lists give the same fingerprint
+//! from any build, whatever order their letters were first seen in.
+
+use std::fmt::Display;
+
+use sha2::{Digest, Sha256};
+
+pub struct Fingerprint(Sha256);
+
+impl Fingerprint {
+    /// A fingerprint of something of `kind`, so different kinds never share one.
+    pub fn new(kind: &str) -> Self {
+        let mut fingerprint = Self(Sha256::new());
+        fingerprint.add(kind);
+        fingerprint
+    }
+
+    /// Adds `value`, as it's displayed, on a line of its own so values can't run together.
+    pub fn add(&mut self, value: impl Display) -> &mut Self {
+        self.0.update(format!("{}\n", value));
+        self
+    }
+
+    /// Adds a weight, exactly rather than as it's displayed.
+    pub fn add_weight(&mut self, weight: f32) -> &mut Self {
+        self.add(format!("{:08x}", weight.to_bits()))
+    }
+
+    pub fn finish(self) -> u64 {
+        let digest = self.0.finalize();
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+}
@@ -0,0 +1,593 @@
+//! Finds the best way to finish a game once only a few answers are left: the tree of guesses, one
+//! for each feedback so far, that finds the answer in the fewest guesses on average.  It's exact,
+//! not a heuristic, and quick for up to a hundred or so answers, since a guess is only searched
+//! when the fewest guesses it could possibly need beat the best found so far.
+//!
+//! Each guess in the tree is also labelled by what it risks with the guesses left: safe if every
+//! answer it leaves can still be found in time whatever the feedback, risky if some feedback
+//! leaves answers that no guesses could be sure of finding in time, and informational if it's
+//! safe but can't be the answer itself.
+//!
+//! For more answers than can be searched in time, the search can be given a budget, and suggests
+//! the best first guess it's found by then, or failing that, the one that splits the answers up
+//! most evenly.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::clue;
+use crate::tablebase::{self, Tablebase};
+use crate::{get_pattern, pattern_count, solved_pattern, Word};
+
+/// The fewest guesses `count` answers could take in total when none of them have been guessed:
+/// one to find one of them, and two for each of the rest.
+fn lower_bound(count: usize) -> usize {
+    2 * count - 1
+}
+
+/// What a guess risks with the guesses left.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Safety {
+    /// It can't lose.
+    Safe,
+    /// Some feedback for it leaves answers that can't all be found in time.
+    Risky,
+    /// It can't lose, and it can't win with this guess either.
+    Informational,
+    /// There are no guesses left to make it with.
+    OutOfGuesses,
+}
+
+impl fmt::Display for Safety {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Safe => write!(f, "safe"),
+            Self::Risky => write!(f, "risky"),
+            Self::Informational => write!(f, "informational only"),
+            Self::OutOfGuesses => write!(f, "out of guesses"),
+        }
+    }
+}
+
+struct Search {
+    answers: Vec<Word>,
+    /// The letters every word has.
+    word_length: usize,
+    /// The answers come first, so they're the first guesses tried, and the rest of the guesses
+    /// after them.
+    candidates: Vec<Word>,
+    /// The candidates in the order they're tried, which is the order equally good ones are
+    /// preferred in.
+    order: Vec<usize>,
+    /// The pattern of each candidate against each answer.
+    patterns: Vec<Vec<u16>>,
+    /// The fewest guesses each set of answers takes in total, and the candidate to guess first.
+    best: HashMap<Vec<u16>, (usize, usize)>,
+    /// Whether each set of answers can always be found within some number of guesses.
+    winnable: HashMap<(Vec<u16>, usize), bool>,
+    /// When to give up searching, if ever.  Nothing's remembered from a search that's given up.
+    deadline: Option<Instant>,
+    out_of_time: bool,
+}
+
+/// The guess to make first, and how it was chosen.
+#[derive(Clone, Copy, Debug)]
+pub struct Suggestion {
+    pub guess: Word,
+    pub answers: usize,
+    /// The guesses the answers take in total with it, if it was searched, or else the fewest they
+    /// could.
+    pub total: usize,
+    pub searched: bool,
+    /// Whether it's certainly the best, rather than the best found in time.
+    pub exact: bool,
+}
+
+impl Search {
+    fn new(answers: &[Word], guesses: &[Word]) -> Self {
+        let mut answers = answers.to_vec();
+        answers.sort();
+        answers.dedup();
+        let mut candidates = answers.clone();
+        candidates.extend(guesses.iter().filter(|g| !answers.contains(g)));
+        let patterns = candidates
+            .iter()
+            .map(|c| answers.iter().map(|a| get_pattern(a, c) as u16).collect())
+            .collect();
+
+        Self {
+            word_length: answers.first().map_or(0, Word::length),
+            answers,
+            order: (0..candidates.len()).collect(),
+            candidates,
+            patterns,
+            best: HashMap::new(),
+            winnable: HashMap::new(),
+            deadline: None,
+            out_of_time: false,
+        }
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        if !self.out_of_time {
+            self.out_of_time = self.deadline.is_some_and(|d| Instant::now() >= d);
+        }
+        self.out_of_time
+    }
+
+    /// Takes the best play for each of `tablebase`'s positions that's made of these answers, so
+    /// they're looked up rather than searched.
+    fn load(&mut self, tablebase: &Tablebase) {
+        let answers = self
+            .answers
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (*w, i as u16))
+            .collect::<HashMap<_, _>>();
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (*w, i))
+            .collect::<HashMap<_, _>>();
+        for (words, total, guess) in tablebase.positions() {
+            let position = words
+                .iter()
+                .map(|w| answers.get(w).copied())
+                .collect::<Option<Vec<_>>>();
+            if let (Some(position), Some(guess)) = (position, candidates.get(&guess)) {
+                self.best.insert(position, (total, *guess));
+            }
+        }
+    }
+
+    fn fingerprint(&self) -> u64 {
+        tablebase::fingerprint(&self.candidates)
+    }
+
+    /// Splits `answers` by their feedback for `candidate`, leaving out the answer it finds, biggest
+    /// first.
+    fn partition(&self, candidate: usize, answers: &[u16]) -> Vec<Vec<u16>> {
+        let solved = solved_pattern(self.word_length);
+        let mut groups = HashMap::<u16, Vec<u16>>::new();
+        for a in answers {
+            let pattern = self.patterns[candidate][*a as usize];
+            if pattern as usize != solved {
+                groups.entry(pattern).or_default().push(*a);
+            }
+        }
+        let mut groups = groups.into_values().collect::<Vec<_>>();
+        groups.sort_unstable_by_key(|g| Reverse(g.len()));
+        groups
+    }
+
+    /// The fewest guesses `answers` take in total, and the candidate to guess first.
+    fn solve(&mut self, answers: &[u16]) -> (usize, usize) {
+        match answers {
+            [a] => return (1, *a as usize),
+            [a, _] => return (3, *a as usize),
+            _ => (),
+        }
+        if let Some(best) = self.best.get(answers) {
+            return *best;
+        }
+        if self.out_of_time() {
+            return (usize::MAX, 0);
+        }
+
+        let mut best = (usize::MAX, 0);
+        for (bound, candidate) in self.bounds(answers, false) {
+            if bound >= best.0 {
+                break;
+            }
+            if let Some(total) = self.try_candidate(candidate, bound, answers, best.0) {
+                best = (total, candidate);
+            }
+        }
+
+        if !self.out_of_time {
+            self.best.insert(answers.to_vec(), best);
+        }
+        best
+    }
+
+    /// The fewest guesses each useful candidate could take for `answers`, going by how evenly it
+    /// splits them, most promising first.  Unless it's for `every` candidate, it stops at the first
+    /// that nothing could beat.
+    fn bounds(&self, answers: &[u16], every: bool) -> Vec<(usize, usize)> {
+        let count = answers.len();
+        let solved = solved_pattern(self.word_length);
+        let mut counts = vec![0u16; pattern_count(self.word_length)];
+        // The fewest guesses each candidate could take, going by how evenly it splits the answers,
+        // for searching the most promising first.
+        let mut bounds = Vec::new();
+        for &candidate in &self.order {
+            let patterns = &self.patterns[candidate];
+            answers
+                .iter()
+                .for_each(|a| counts[patterns[*a as usize] as usize] += 1);
+            let mut bound = count;
+            let mut useful = true;
+            for a in answers {
+                let pattern = patterns[*a as usize] as usize;
+                match counts[pattern] {
+                    0 => continue,
+                    n if n as usize == count && pattern != solved => useful = false,
+                    n if pattern != solved => bound += lower_bound(n as usize),
+                    _ => (),
+                }
+                counts[pattern] = 0;
+            }
+            if useful {
+                bounds.push((bound, candidate));
+                if !every && bound == lower_bound(count) {
+                    // Nothing can do better than splitting the rest up entirely.
+                    break;
+                }
+            }
+        }
+        bounds.sort_by_key(|(bound, _)| *bound);
+        bounds
+    }
+
+    /// The guesses `answers` take in total with `candidate`, whose bound is `bound`, guessed first,
+    /// unless that's no better than `best`.
+    fn try_candidate(
+        &mut self,
+        candidate: usize,
+        bound: usize,
+        answers: &[u16],
+        best: usize,
+    ) -> Option<usize> {
+        let groups = self.partition(candidate, answers);
+        let mut total = answers.len();
+        let mut remaining = bound - answers.len();
+        let beaten = groups.iter().any(|group| {
+            remaining -= lower_bound(group.len());
+            total = total.saturating_add(self.solve(group).0);
+            total.saturating_add(remaining) >= best
+        });
+        (!beaten).then_some(total)
+    }
+
+    /// The best first guess for `answers` that can be found before the deadline, telling `refined`
+    /// of each better one found on the way, starting with the most promising before it's searched.
+    fn suggest(&mut self, answers: &[u16], mut refined: impl FnMut(&Suggestion)) -> Suggestion {
+        let bounds = match answers.len() {
+            // These are solved without searching.
+            1 | 2 => {
+                let (total, candidate) = self.solve(answers);
+                vec![(total, candidate)]
+            }
+            _ => self.bounds(answers, false),
+        };
+        let suggestion =
+            |search: &Self, (total, candidate): (usize, usize), searched, exact| Suggestion {
+                guess: search.candidates[candidate],
+                answers: answers.len(),
+                total,
+                searched,
+                exact,
+            };
+        if answers.len() > 2 {
+            refined(&suggestion(self, bounds[0], false, false));
+        }
+        let mut best = None;
+        let mut exact = true;
+        for (bound, candidate) in &bounds {
+            let best_total = best.map_or(usize::MAX, |(total, _)| total);
+            if *bound >= best_total {
+                break;
+            }
+            let total = match answers.len() {
+                1 | 2 => Some(*bound),
+                _ => self.try_candidate(*candidate, *bound, answers, best_total),
+            };
+            // What was found as time ran out can't be counted on.
+            if answers.len() > 2 && self.out_of_time() {
+                exact = false;
+                break;
+            }
+            if let Some(total) = total {
+                best = Some((total, *candidate));
+                if answers.len() > 2 {
+                    refined(&suggestion(self, (total, *candidate), true, false));
+                }
+            }
+        }
+
+        suggestion(self, best.unwrap_or(bounds[0]), best.is_some(), exact)
+    }
+
+    /// Whether some guesses can find every one of `answers` within `limit`, whatever the feedback.
+    fn can_win(&mut self, answers: &[u16], limit: usize) -> bool {
+        match (answers.len(), limit) {
+            (_, 0) => return false,
+            (1, _) => return true,
+            (_, 1) => return false,
+            (2, _) => return true,
+            _ => (),
+        }
+        if let Some(winnable) = self.winnable.get(&(answers.to_vec(), limit)) {
+            return *winnable;
+        }
+
+        // Candidates leaving the smallest biggest group are tried first, that being the one least
+        // likely to be winnable.
+        let solved = solved_pattern(self.word_length);
+        let mut counts = vec![0u16; pattern_count(self.word_length)];
+        let mut biggest = Vec::new();
+        for candidate in 0..self.candidates.len() {
+            let patterns = &self.patterns[candidate];
+            let mut most = 0;
+            for a in answers {
+                let pattern = patterns[*a as usize] as usize;
+                counts[pattern] += 1;
+                if pattern != solved {
+                    most = most.max(counts[pattern]);
+                }
+            }
+            answers
+                .iter()
+                .for_each(|a| counts[patterns[*a as usize] as usize] = 0);
+            if (most as usize) < answers.len() && (limit > 2 || most <= 1) {
+                biggest.push((most, candidate));
+            }
+        }
+        biggest.sort();
+
+        let winnable = biggest.into_iter().any(|(_, candidate)| {
+            self.partition(candidate, answers)
+                .iter()
+                .all(|group| self.can_win(group, limit - 1))
+        });
+        self.winnable.insert((answers.to_vec(), limit), winnable);
+        winnable
+    }
+
+    /// What guessing `candidate` risks for `answers` with `left` guesses to go.
+    fn safety(&mut self, candidate: usize, answers: &[u16], left: usize) -> Safety {
+        if left == 0 {
+            return Safety::OutOfGuesses;
+        }
+        let groups = self.partition(candidate, answers);
+        if !groups.iter().all(|group| self.can_win(group, left - 1)) {
+            Safety::Risky
+        } else if u16::try_from(candidate).is_ok_and(|c| answers.contains(&c)) {
+            Safety::Safe
+        } else {
+            Safety::Informational
+        }
+    }
+
+    /// Writes the guess for `answers` after `feedback`, indented by `depth`, and what it risks with
+    /// `left` guesses to go, and then what to guess after each feedback for that, returning the
+    /// most guesses any of them take.
+    fn write_tree(
+        &mut self,
+        out: &mut impl Write,
+        answers: &[u16],
+        feedback: Option<usize>,
+        depth: usize,
+        left: usize,
+    ) -> usize {
+        let (_, candidate) = self.solve(answers);
+        let indent = "  ".repeat(depth);
+        let feedback = feedback.map_or(String::new(), |f| {
+            format!("{} ", clue::feedback(f, self.word_length))
+        });
+        if answers.len() == 1 {
+            let safety = match left {
+                0 => format!(" ({})", Safety::OutOfGuesses),
+                _ => String::new(),
+            };
+            writeln!(
+                out,
+                "{}{}{}{}",
+                indent, feedback, self.candidates[candidate], safety
+            )
+            .expect("cannot write line");
+            return 1;
+        }
+        let safety = self.safety(candidate, answers, left);
+        writeln!(
+            out,
+            "{}{}{} ({} left, {})",
+            indent,
+            feedback,
+            self.candidates[candidate],
+            answers.len(),
+            safety
+        )
+        .expect("cannot write line");
+
+        let mut groups = self.partition(candidate, answers);
+        groups.sort_by_key(|g| self.patterns[candidate][g[0] as usize]);
+        groups
+            .iter()
+            .map(|group| {
+                let pattern = self.patterns[candidate][group[0] as usize] as usize;
+                1 + self.write_tree(out, group, Some(pattern), depth + 1, left.saturating_sub(1))
+            })
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+/// Writes the best tree of guesses for finding which of `answers` it is, choosing from them and
+/// `guesses`, with what each guess risks given `guesses_left`, and how many guesses it takes on
+/// average and at most.  Positions in `tablebase` are looked up rather than searched, and it must
+/// have been built with the same guesses.
+pub fn finish(
+    out: &mut impl Write,
+    answers: &[Word],
+    guesses: &[Word],
+    guesses_left: usize,
+    tablebase: Option<&Tablebase>,
+) -> Result<(), String> {
+    let mut search = Search::new(answers, guesses);
+    if let Some(tablebase) = tablebase {
+        if tablebase.fingerprint() != search.fingerprint() {
+            return Err("the tablebase was built with different guesses".to_string());
+        }
+        search.load(tablebase);
+    }
+    let count = search.answers.len();
+    let all = (0..count as u16).collect::<Vec<_>>();
+    if all.is_empty() {
+        writeln!(out, "There are no answers left.").expect("cannot write line");
+        return Ok(());
+    }
+
+    let (total, _) = search.solve(&all);
+    let most = search.write_tree(out, &all, None, 0, guesses_left);
+    writeln!(out).expect("cannot write line");
+    writeln!(
+        out,
+        "Answers: {}, average guesses: {:.3}, at most: {}",
+        count,
+        total as f32 / count as f32,
+        most
+    )
+    .expect("cannot write line");
+    Ok(())
+}
+
+/// The best first guess for finding which of `answers` it is, choosing from them and `guesses`, that
+/// can be found within `budget`, if there's one; working out how each guess splits the answers
+/// comes before the budget starts.  Of equally good guesses, the first in `guesses` is suggested,
+/// and the answers that aren't in them come after them.  `refined` hears of each better guess
+/// found along the way, the first being the most promising before any are searched.  `None` if
+/// there are no answers.
+pub fn suggest(
+    answers: &[Word],
+    guesses: &[Word],
+    budget: Option<Duration>,
+    refined: impl FnMut(&Suggestion),
+) -> Option<Suggestion> {
+    let mut search = Search::new(answers, guesses);
+    let all = (0..search.answers.len() as u16).collect::<Vec<_>>();
+    if all.is_empty() {
+        return None;
+    }
+    let place = guesses
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, w)| (*w, i))
+        .collect::<HashMap<_, _>>();
+    search.order.sort_by_key(|c| {
+        place
+            .get(&search.candidates[*c])
+            .copied()
+            .unwrap_or(usize::MAX)
+    });
+    search.deadline = budget.map(|b| Instant::now() + b);
+    Some(search.suggest(&all, refined))
+}
+
+/// What guessing `guess` risks for finding which of `answers` it is with `guesses_left` guesses to
+/// go, choosing the rest from them and `guesses`.  It's a search of its own for whether the answers
+/// each feedback leaves can all be found in time, so it's only quick for a hundred or so answers.
+pub fn safety(answers: &[Word], guesses: &[Word], guess: &Word, guesses_left: usize) -> Safety {
+    let mut search = Search::new(answers, guesses);
+    let all = (0..search.answers.len() as u16).collect::<Vec<_>>();
+    let candidate = match search.candidates.iter().position(|c| c == guess) {
+        Some(candidate) => candidate,
+        None => {
+            // A guess that's in neither list still gets feedback like any other.
+            search.patterns.push(
+                search
+                    .answers
+                    .iter()
+                    .map(|a| get_pattern(a, guess) as u16)
+                    .collect(),
+            );
+            search.candidates.push(*guess);
+            search.candidates.len() - 1
+        }
+    };
+    search.safety(candidate, &all, guesses_left)
+}
+
+/// Every guess that tells any of `answers` apart, choosing from them and `guesses`, with the guesses
+/// the answers take in total when it's guessed first and then played as well as can be, best first.
+pub fn rank(answers: &[Word], guesses: &[Word]) -> Vec<(Word, usize)> {
+    let mut search = Search::new(answers, guesses);
+    let all = (0..search.answers.len() as u16).collect::<Vec<_>>();
+    let mut ranked = search
+        .bounds(&all, true)
+        .into_iter()
+        .map(|(bound, candidate)| {
+            let total = search
+                .try_candidate(candidate, bound, &all, usize::MAX)
+                .unwrap();
+            (search.candidates[candidate], total)
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by_key(|(guess, total)| (*total, *guess));
+    ranked
+}
+
+/// Works out the best play for every position of at most `max_answers` answers that can be reached
+/// from `openers`' feedback, or from the start if there are none, with up to `depth` more guesses
+/// of any of the answers or `guesses`, as a tablebase.
+pub fn build_tablebase(
+    answers: &[Word],
+    guesses: &[Word],
+    openers: &[Word],
+    max_answers: usize,
+    depth: usize,
+) -> Result<Tablebase, String> {
+    if max_answers < 3 {
+        return Err("positions of fewer than three answers are never looked up".to_string());
+    }
+    let mut search = Search::new(answers, guesses);
+    let all = (0..search.answers.len() as u16).collect::<Vec<_>>();
+    let mut positions = Vec::new();
+    for opener in openers {
+        let candidate = search
+            .candidates
+            .iter()
+            .position(|c| c == opener)
+            .ok_or_else(|| format!("{} isn't in the lists", opener))?;
+        positions.extend(search.partition(candidate, &all));
+    }
+    if openers.is_empty() {
+        positions.push(all);
+    }
+
+    let mut seen = HashSet::new();
+    for level in 0..=depth {
+        let mut next = Vec::new();
+        for position in positions {
+            if !seen.insert(position.clone()) {
+                continue;
+            }
+            if position.len() <= max_answers {
+                search.solve(&position);
+            } else if level < depth {
+                for candidate in 0..search.candidates.len() {
+                    next.extend(search.partition(candidate, &position));
+                }
+            }
+        }
+        positions = next;
+    }
+
+    // Everything the search passed through is solved exactly, so it all goes in.
+    let mut tablebase = Tablebase::new(search.fingerprint());
+    for (position, (total, candidate)) in &search.best {
+        if position.len() <= max_answers {
+            let words = position
+                .iter()
+                .map(|a| search.answers[*a as usize])
+                .collect();
+            tablebase.insert(words, *total, search.candidates[*candidate]);
+        }
+    }
+    Ok(tablebase)
+}
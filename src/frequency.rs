@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use crate::Word;
+
+/// How common (or familiar) each word is.
+#[derive(Clone, Debug)]
+pub struct WordFrequencies {
+    frequencies: HashMap<Word, f32>,
+    /// Words that aren't listed are treated as the least common listed word.
+    default: f32,
+}
+
+impl WordFrequencies {
+    /// `frequencies` by word, with any word that isn't in them as uncommon as the least common
+    /// that is.
+    pub fn new(frequencies: HashMap<Word, f32>) -> Self {
+        let default = frequencies.values().copied().fold(f32::INFINITY, f32::min);
+        Self {
+            frequencies,
+            default: if default.is_finite() { default } else { 0.0 },
+        }
+    }
+
+    pub fn get(&self, word: &Word) -> f32 {
+        self.frequencies.get(word).copied().unwrap_or(self.default)
+    }
+
+    /// The listed words and their frequencies, in alphabetical order.
+    pub fn listed(&self) -> Vec<(Word, f32)> {
+        let mut listed = self
+            .frequencies
+            .iter()
+            .map(|(w, f)| (*w, *f))
+            .collect::<Vec<_>>();
+        listed.sort_by_key(|(w, _)| *w);
+        listed
+    }
+
+    /// Raises every frequency to `exponent`, for weighting guesses by how familiar they are.
+    pub fn powf(&self, exponent: f32) -> Self {
+        Self {
+            frequencies: self
+                .frequencies
+                .iter()
+                .map(|(w, f)| (*w, f.powf(exponent)))
+                .collect(),
+            default: self.default.powf(exponent),
+        }
+    }
+}
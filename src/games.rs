@@ -0,0 +1,864 @@
+//! The player's own daily games, kept between runs so their streaks and averages can be followed,
+//! and set against what the search expects of the openers they play.  They're kept in a SQLite
+//! database, a game to a date, and exported as lines of `date, answer, guesses`, after a line of
+//! `# brutal games`, with the guesses separated by spaces, which can be imported again.  Games
+//! imported from what was shared of them only have each guess's feedback, like `..y.g`, in place
+//! of its word.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::{Subcommand, ValueHint};
+
+use crate::distribution::Distribution;
+use crate::exit::{self, OrInvalidInput};
+use crate::solver::{ProgressSink, SolverConfig, WordResult};
+use crate::sqlite::Games;
+use crate::{
+    check_length, check_list_roles, clue, get_pattern, paths, read_guesses, read_lines,
+    read_scores, share, solved_pattern, Word, GUESS_LIMIT, MAX_WORD_LENGTH,
+};
+
+const HEADER: &str = "# brutal games";
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Operation {
+    /// Show your streaks, averages and guess distribution, with how the search expects your openers to do
+    Me {
+        #[clap(flatten)]
+        lists: Lists,
+
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Look your openers up in a results file from a previous run, rather than scoring them"
+        )]
+        previous_results: Option<PathBuf>,
+
+        #[clap(flatten)]
+        store: Store,
+    },
+    /// Show how switching openers should change your games: your own guess distribution, moved by as much as the search expects the new opener to move its own
+    Switch {
+        #[clap(parse(try_from_str), help = "The opener to switch to")]
+        to: Word,
+
+        #[clap(
+            long,
+            parse(try_from_str),
+            help = "The opener to switch from [defaults to the one you've opened the most games with]"
+        )]
+        from: Option<Word>,
+
+        #[clap(flatten)]
+        lists: Lists,
+
+        #[clap(flatten)]
+        store: Store,
+    },
+    /// Import the games in a file of grids shared from Wordle, with their answers looked up by puzzle number in an archive, or in a file written by `export`, leaving out any on days already recorded
+    Import {
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The shared grids, one after another, each after the line with its date if it has one, or the exported games"
+        )]
+        shares: PathBuf,
+
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The answer to each puzzle, as lines of number, answer, for importing shared grids"
+        )]
+        archive: Option<PathBuf>,
+
+        #[clap(flatten)]
+        store: Store,
+    },
+    /// Write out every game recorded, as lines of date, answer, guesses, which `import` reads back
+    Export {
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Write them to this file rather than to stdout"
+        )]
+        output: Option<PathBuf>,
+
+        #[clap(flatten)]
+        store: Store,
+    },
+    /// Record a game you've played: its answer, then your guesses in order, ending with the answer if you solved it
+    Record {
+        #[clap(parse(try_from_str))]
+        answer: Word,
+
+        #[clap(required = true, parse(try_from_str))]
+        guesses: Vec<Word>,
+
+        #[clap(
+            long,
+            parse(try_from_str),
+            help = "The day it was played, like 2022-01-31 [defaults to today]"
+        )]
+        date: Option<Date>,
+
+        #[clap(flatten)]
+        store: Store,
+    },
+}
+
+/// The lists the search scores openers against, for what it expects of them.
+#[derive(Clone, Debug, clap::Args)]
+pub struct Lists {
+    #[clap(
+        short,
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Override the default answer list, for what's expected of your openers",
+        default_value = "wordle_answer_list.txt"
+    )]
+    answer_list: PathBuf,
+
+    #[clap(
+        short,
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Override the default guess list [defaults to the answer list]"
+    )]
+    guess_list: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Add any answers missing from the guess list to it, so every answer can be guessed"
+    )]
+    guesses_include_answers: bool,
+
+    #[clap(short, long, default_value = "1")]
+    threads: usize,
+}
+
+impl Lists {
+    /// Scores `openers`, keeping their distributions if `keep_distributions`, in whatever order
+    /// they finish.
+    fn score(&self, openers: Vec<Word>, keep_distributions: bool) -> Vec<WordResult> {
+        if openers.is_empty() {
+            return Vec::new();
+        }
+        let answers = read_lines(&self.answer_list);
+        let guess_list = self.guess_list.as_ref().unwrap_or(&self.answer_list);
+        let mut guesses = read_guesses(guess_list, &answers);
+        check_list_roles(&answers, &mut guesses, self.guesses_include_answers);
+        check_length("the games' openers", &openers, &answers);
+        let solver = SolverConfig::new(answers, guesses)
+            .threads(self.threads)
+            .keep_distributions(keep_distributions)
+            .build();
+        let collected = Collected::default();
+        solver.score_all(openers.into_iter(), &collected);
+        collected.0.into_inner().unwrap()
+    }
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct Store {
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Keep the games in this SQLite database [defaults to games.db in the data directory]"
+    )]
+    games: Option<PathBuf>,
+}
+
+impl Store {
+    /// Opens the games, making the database if it's new.  A new database in the data directory
+    /// starts with the games in the file they were kept in before there was one, if it's there.
+    fn open(&self) -> Games {
+        let path = self
+            .games
+            .clone()
+            .or_else(paths::games_file)
+            .or_invalid_input(|| "there's no data directory to keep the games in");
+        let new = !path.exists();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).or_invalid_input(|| dir.display().to_string());
+        }
+        let mut games = Games::open(&path).or_invalid_input(|| path.display().to_string());
+
+        let old = paths::old_games_file().filter(|f| new && self.games.is_none() && f.exists());
+        if let Some(old) = old {
+            let moved = read_csv(&old);
+            add(&mut games, &moved);
+            println!(
+                "Moved the {} games in {} into {}",
+                moved.len(),
+                old.display(),
+                path.display()
+            );
+        }
+        games
+    }
+}
+
+/// A day, as the days since the Unix epoch.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Date(i64);
+
+impl Date {
+    pub fn today() -> Self {
+        let since = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO);
+        Self((since.as_secs() / 86400) as i64)
+    }
+
+    /// The day `days` after this one.
+    pub fn plus(self, days: i64) -> Self {
+        Self(self.0 + days)
+    }
+
+    pub fn from_ymd(year: i64, month: i64, day: i64) -> Self {
+        // Counting years from March, so the leap day comes last.
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Self(era * 146097 + day_of_era - 719468)
+    }
+
+    pub fn ymd(self) -> (i64, i64, i64) {
+        let days = self.0 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month + 2) / 5 + 1;
+        let month = if month < 10 { month + 3 } else { month - 9 };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        (year, month, day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date: {} (expected like 2022-01-31)", s);
+        let parts = s
+            .split('-')
+            .map(|p| p.parse::<i64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        match parts[..] {
+            [year, month @ 1..=12, day @ 1..=31] => {
+                let date = Self::from_ymd(year, month, day);
+                // Catches days past the end of the month.
+                match date.ymd() == (year, month, day) {
+                    true => Ok(date),
+                    false => Err(invalid()),
+                }
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// A guess in a game: its word, or only its feedback.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Guess {
+    Word(Word),
+    /// The feedback's pattern, as `get_pattern` would give it, and the letters it's for.
+    Feedback {
+        pattern: usize,
+        length: usize,
+    },
+}
+
+impl Guess {
+    pub fn pattern(&self, answer: &Word) -> usize {
+        match self {
+            Self::Word(word) => get_pattern(answer, word),
+            Self::Feedback { pattern, .. } => *pattern,
+        }
+    }
+
+    /// The letters the word, or the word the feedback was for, has.
+    pub fn length(&self) -> usize {
+        match self {
+            Self::Word(word) => word.length(),
+            Self::Feedback { length, .. } => *length,
+        }
+    }
+}
+
+impl FromStr for Guess {
+    type Err = String;
+
+    /// Parses a word, or feedback written only in `g`, `y` and `.`, which no word is.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let length = s.chars().count();
+        if (1..=MAX_WORD_LENGTH).contains(&length) && s.chars().all(|c| "gy.".contains(c)) {
+            let pattern = s.chars().fold(0, |pattern, c| {
+                pattern * 3 + "gy.".find(c).map_or(0, |i| 2 - i)
+            });
+            return Ok(Self::Feedback { pattern, length });
+        }
+        s.parse()
+            .map(Self::Word)
+            .map_err(|e| format!("{}: {}", e, s))
+    }
+}
+
+impl fmt::Display for Guess {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "{}", word),
+            Self::Feedback { pattern, length } => {
+                write!(f, "{}", clue::feedback(*pattern, *length))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Game {
+    pub date: Date,
+    pub answer: Word,
+    pub guesses: Vec<Guess>,
+}
+
+impl Game {
+    /// A finished game, which is either solved or out of guesses.
+    pub fn new(date: Date, answer: Word, guesses: Vec<Guess>) -> Result<Self, String> {
+        if let Some(guess) = guesses.iter().find(|g| g.length() != answer.length()) {
+            return Err(format!(
+                "{} has {} letters, but the answer, {}, has {}",
+                guess,
+                guess.length(),
+                answer,
+                answer.length()
+            ));
+        }
+        let solved_at = guesses
+            .iter()
+            .position(|g| g.pattern(&answer) == solved_pattern(answer.length()));
+        match solved_at {
+            _ if guesses.len() > GUESS_LIMIT => {
+                Err(format!("a game has at most {} guesses", GUESS_LIMIT))
+            }
+            Some(i) if i + 1 < guesses.len() => {
+                Err(format!("{} was solved before the guesses after it", answer))
+            }
+            None if guesses.len() < GUESS_LIMIT => Err(format!(
+                "the game isn't over: {} isn't guessed, and there are guesses left",
+                answer
+            )),
+            _ => Ok(Self {
+                date,
+                answer,
+                guesses,
+            }),
+        }
+    }
+
+    /// The guesses it was solved in, if it was.
+    pub fn solved_in(&self) -> Option<usize> {
+        let last = self.guesses.last()?;
+        let solved = solved_pattern(self.answer.length());
+        (last.pattern(&self.answer) == solved).then_some(self.guesses.len())
+    }
+
+    /// The word it was opened with, if that's known.
+    pub fn opener(&self) -> Option<Word> {
+        match self.guesses.first()? {
+            Guess::Word(word) => Some(*word),
+            Guess::Feedback { .. } => None,
+        }
+    }
+}
+
+/// The games recorded, by date.
+fn read(games: &Games) -> Vec<Game> {
+    let rows = games
+        .read()
+        .or_invalid_input(|| "cannot read the games recorded");
+    rows.iter()
+        .map(|[date, answer, guesses]| {
+            parse_game(date, answer, guesses)
+                .or_invalid_input(|| format!("the game recorded for {} can't be read", date))
+        })
+        .collect()
+}
+
+/// Adds `games` to the ones recorded.  There can only be one game a day, so these can't be on the
+/// days of any already there.
+fn add(store: &mut Games, games: &[Game]) {
+    let rows = games
+        .iter()
+        .map(|g| [g.date.to_string(), g.answer.to_string(), guesses(g)])
+        .collect::<Vec<_>>();
+    store
+        .add(&rows)
+        .or_invalid_input(|| "cannot record the games");
+}
+
+fn guesses(game: &Game) -> String {
+    game.guesses
+        .iter()
+        .map(Guess::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_game(date: &str, answer: &str, guesses: &str) -> Result<Game, String> {
+    let guesses = guesses
+        .split_whitespace()
+        .map(str::parse::<Guess>)
+        .collect::<Result<_, _>>()?;
+    let answer = answer.parse::<Word>().map_err(|e| e.to_string())?;
+    Game::new(date.parse()?, answer, guesses)
+}
+
+/// Whether `filename` holds exported games, rather than shared grids.
+fn is_csv(filename: &Path) -> bool {
+    fs::read_to_string(filename).is_ok_and(|c| c.lines().next() == Some(HEADER))
+}
+
+/// The games exported to `filename`, by date.
+fn read_csv(filename: &Path) -> Vec<Game> {
+    let contents = fs::read_to_string(filename).or_invalid_input(|| filename.display().to_string());
+    let mut lines = contents.lines().enumerate();
+    if lines.next().map(|(_, l)| l) != Some(HEADER) {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!("{} isn't a file of games", filename.display()),
+        );
+    }
+    let mut games = lines
+        .filter(|(_, l)| !l.trim().is_empty() && !l.starts_with("date,"))
+        .map(|(i, l)| {
+            let line = || format!("{}:{}", filename.display(), i + 1);
+            let columns = l.split(',').map(str::trim).collect::<Vec<_>>();
+            if columns.len() != 3 {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!("{}: expected 3 columns", line()),
+                );
+            }
+            parse_game(columns[0], columns[1], columns[2]).or_invalid_input(line)
+        })
+        .collect::<Vec<_>>();
+    games.sort_by_key(|g| g.date);
+    games
+}
+
+fn write_csv(out: &mut impl Write, games: &[Game]) -> io::Result<()> {
+    writeln!(out, "{}", HEADER)?;
+    writeln!(out, "date,       answer, guesses")?;
+    for game in games {
+        writeln!(out, "{}, {},  {}", game.date, game.answer, guesses(game))?;
+    }
+    Ok(())
+}
+
+/// The wins in a row up to `today`, which only count if the last of them was today or the day
+/// before, and the most in a row there have ever been.  A day without a game breaks a streak too.
+fn streaks(games: &[Game], today: Date) -> (usize, usize) {
+    let mut run = 0;
+    let mut longest = 0;
+    let mut last = None;
+    for game in games {
+        if game.solved_in().is_none() {
+            run = 0;
+        } else if last == Some(game.date.plus(-1)) {
+            run += 1;
+        } else {
+            run = 1;
+        }
+        longest = longest.max(run);
+        last = Some(game.date);
+    }
+    let current = match last {
+        Some(last) if last >= today.plus(-1) => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+#[derive(Default)]
+struct Collected(Mutex<Vec<WordResult>>);
+
+impl ProgressSink for Collected {
+    fn word_finished(&self, _worker: usize, result: WordResult, _elapsed: Duration) {
+        self.0.lock().unwrap().push(result);
+    }
+}
+
+/// Writes the player's record from `games`, with the average and success rate `expected` of each
+/// opener they've played.
+fn write_report(
+    out: &mut impl Write,
+    games: &[Game],
+    expected: &HashMap<Word, (f32, f32)>,
+    today: Date,
+) -> io::Result<()> {
+    if games.is_empty() {
+        return writeln!(out, "There are no games recorded yet.");
+    }
+    let solved = games.iter().filter_map(Game::solved_in).collect::<Vec<_>>();
+    let (current, longest) = streaks(games, today);
+    writeln!(
+        out,
+        "Games: {}, from {} to {}, won: {} ({:.1}%)",
+        games.len(),
+        games[0].date,
+        games[games.len() - 1].date,
+        solved.len(),
+        100.0 * solved.len() as f32 / games.len() as f32
+    )?;
+    writeln!(out, "Streak: {}, longest: {}", current, longest)?;
+    if !solved.is_empty() {
+        writeln!(
+            out,
+            "Average guesses in the games won: {:.3}",
+            solved.iter().sum::<usize>() as f32 / solved.len() as f32
+        )?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Guesses:")?;
+    let mut counts = [0usize; GUESS_LIMIT + 1];
+    for game in games {
+        counts[game.solved_in().map_or(GUESS_LIMIT, |g| g - 1)] += 1;
+    }
+    let most = counts.iter().max().copied().unwrap_or(1);
+    for (i, count) in counts.iter().enumerate() {
+        let label = match i {
+            GUESS_LIMIT => "x".to_string(),
+            _ => (i + 1).to_string(),
+        };
+        // Bars are up to 40 wide.
+        let bar = "#".repeat((40 * count).div_ceil(most));
+        let line = format!("  {}: {:5} {}", label, count, bar);
+        writeln!(out, "{}", line.trim_end())?;
+    }
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Openers, against what the search expects of them, with ????? for the ones not known:"
+    )?;
+    writeln!(out, "opener, games,   yours, success,  search, success")?;
+    let mut openers = HashMap::<Option<Word>, Vec<&Game>>::new();
+    for game in games {
+        openers.entry(game.opener()).or_default().push(game);
+    }
+    let mut openers = openers.into_iter().collect::<Vec<_>>();
+    openers.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+    for (opener, games) in openers {
+        let solved = games
+            .iter()
+            .filter_map(|g| g.solved_in())
+            .collect::<Vec<_>>();
+        let average = match solved.len() {
+            0 => "-".to_string(),
+            n => format!("{:.3}", solved.iter().sum::<usize>() as f32 / n as f32),
+        };
+        let (search_average, search_success) = match opener.and_then(|o| expected.get(&o)) {
+            Some((average, success)) => (
+                format!("{:.3}", average),
+                format!("{:.2}%", 100.0 * success),
+            ),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        writeln!(
+            out,
+            "{}, {:5}, {:>7}, {:>7}, {:>7}, {:>7}",
+            opener.map_or("?????".to_string(), |o| o.to_string()),
+            games.len(),
+            average,
+            format!("{:.2}%", 100.0 * solved.len() as f32 / games.len() as f32),
+            search_average,
+            search_success
+        )?;
+    }
+    Ok(())
+}
+
+/// The share of `games` solved in each number of guesses, with the share lost last.
+fn shares(games: &[&Game]) -> [f32; GUESS_LIMIT + 1] {
+    let mut shares = [0.0; GUESS_LIMIT + 1];
+    for game in games {
+        shares[game.solved_in().map_or(GUESS_LIMIT, |g| g - 1)] += 1.0 / games.len() as f32;
+    }
+    shares
+}
+
+/// The same for a distribution the search found.
+fn expected_shares(distribution: &Distribution) -> [f32; GUESS_LIMIT + 1] {
+    let mut shares = [0.0; GUESS_LIMIT + 1];
+    shares[..GUESS_LIMIT].copy_from_slice(&distribution.on_guesses()[..GUESS_LIMIT]);
+    shares[GUESS_LIMIT] = (1.0 - shares[..GUESS_LIMIT].iter().sum::<f32>()).max(0.0);
+    shares
+}
+
+/// The guesses a game takes on average with `shares`, with a lost game taking one more than there
+/// are, and the share won.
+fn score(shares: &[f32; GUESS_LIMIT + 1]) -> (f32, f32) {
+    let guesses = shares
+        .iter()
+        .enumerate()
+        .map(|(i, share)| (i + 1) as f32 * share)
+        .sum::<f32>();
+    (guesses, shares[..GUESS_LIMIT].iter().sum())
+}
+
+/// Writes how switching from the opener of `games` to `to` should change them, modeled as the
+/// player's own distribution moved by as much as the search's moves between `from` and `to`.
+fn write_switch(
+    out: &mut impl Write,
+    games: &[&Game],
+    (from, from_distribution): (Word, &Distribution),
+    (to, to_distribution): (Word, &Distribution),
+) -> io::Result<()> {
+    let yours = shares(games);
+    let search_from = expected_shares(from_distribution);
+    let search_to = expected_shares(to_distribution);
+    let mut modeled = [0.0; GUESS_LIMIT + 1];
+    for (i, share) in modeled.iter_mut().enumerate() {
+        *share = (yours[i] + search_to[i] - search_from[i]).max(0.0);
+    }
+    let total = modeled.iter().sum::<f32>();
+    modeled.iter_mut().for_each(|s| *s /= total);
+
+    writeln!(
+        out,
+        "Guesses a game on average, counting a lost game as {}:",
+        GUESS_LIMIT + 1
+    )?;
+    let (yours_average, yours_success) = score(&yours);
+    let (modeled_average, modeled_success) = score(&modeled);
+    for (name, (average, success)) in [
+        (
+            format!("your {} games opened with {}", games.len(), from),
+            (yours_average, yours_success),
+        ),
+        (format!("the search with {}", from), score(&search_from)),
+        (format!("the search with {}", to), score(&search_to)),
+        (
+            format!("you with {}, modeled", to),
+            (modeled_average, modeled_success),
+        ),
+    ] {
+        writeln!(
+            out,
+            "  {:.3}, with {:.2}% solved, for {}",
+            average,
+            100.0 * success,
+            name
+        )?;
+    }
+    let saved = yours_average - modeled_average;
+    writeln!(
+        out,
+        "Switching from {} to {} should {} you about {:.3} guesses a game.",
+        from,
+        to,
+        if saved >= 0.0 { "save" } else { "cost" },
+        saved.abs()
+    )?;
+
+    writeln!(out)?;
+    writeln!(out, "guesses,   yours,   {},   {}, modeled", from, to)?;
+    for i in 0..=GUESS_LIMIT {
+        let label = match i {
+            GUESS_LIMIT => "x".to_string(),
+            _ => (i + 1).to_string(),
+        };
+        writeln!(
+            out,
+            "{:>7}, {:6.2}%, {:6.2}%, {:6.2}%, {:6.2}%",
+            label,
+            100.0 * yours[i],
+            100.0 * search_from[i],
+            100.0 * search_to[i],
+            100.0 * modeled[i]
+        )?;
+    }
+    Ok(())
+}
+
+pub fn run(operation: &Operation) {
+    match operation {
+        Operation::Record {
+            answer,
+            guesses,
+            date,
+            store,
+        } => {
+            let guesses = guesses.iter().map(|g| Guess::Word(*g)).collect();
+            let game = Game::new(date.unwrap_or_else(Date::today), *answer, guesses)
+                .or_invalid_input(|| "can't record the game");
+            let mut games = store.open();
+            if read(&games).iter().any(|g| g.date == game.date) {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!("there's already a game recorded for {}", game.date),
+                );
+            }
+            add(&mut games, std::slice::from_ref(&game));
+            match game.solved_in() {
+                Some(n) => println!(
+                    "Recorded {} for {}, solved in {}",
+                    game.answer, game.date, n
+                ),
+                None => println!("Recorded {} for {}, lost", game.answer, game.date),
+            }
+        }
+        Operation::Import {
+            shares,
+            archive,
+            store,
+        } => {
+            let mut store = store.open();
+            let mut recorded = read(&store).iter().map(|g| g.date).collect::<HashSet<_>>();
+            let imported = match archive {
+                _ if is_csv(shares) => read_csv(shares),
+                Some(archive) => share::read(shares, &share::read_archive(archive)),
+                None => exit::fail(
+                    exit::INVALID_INPUT,
+                    "importing shared grids needs an --archive to look their answers up in",
+                ),
+            };
+            let count = imported.len();
+            let games = imported
+                .into_iter()
+                .filter(|g| recorded.insert(g.date))
+                .collect::<Vec<_>>();
+            add(&mut store, &games);
+            println!(
+                "Imported {} of the {} games in {}, leaving out {} on days already recorded",
+                games.len(),
+                count,
+                shares.display(),
+                count - games.len()
+            );
+        }
+        Operation::Export { output, store } => {
+            let games = read(&store.open());
+            let written = match output {
+                Some(output) => File::create(output).and_then(|mut f| write_csv(&mut f, &games)),
+                None => write_csv(&mut io::stdout(), &games),
+            };
+            written.or_invalid_input(|| {
+                output
+                    .as_ref()
+                    .map_or("stdout".to_string(), |o| o.display().to_string())
+            });
+        }
+        Operation::Switch {
+            to,
+            from,
+            lists,
+            store,
+        } => {
+            let games = read(&store.open());
+            let from = match from {
+                Some(from) => *from,
+                None => {
+                    let mut counts = HashMap::<Word, usize>::new();
+                    for opener in games.iter().filter_map(Game::opener) {
+                        *counts.entry(opener).or_default() += 1;
+                    }
+                    counts
+                        .into_iter()
+                        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+                        .map(|(opener, _)| opener)
+                        .or_invalid_input(|| "none of your games' openers are known")
+                }
+            };
+            let opened = games
+                .iter()
+                .filter(|g| g.opener() == Some(from))
+                .collect::<Vec<_>>();
+            if opened.is_empty() {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!("none of your games were opened with {}", from),
+                );
+            }
+
+            let mut openers = vec![from, *to];
+            openers.dedup();
+            let results = lists.score(openers, true);
+            let distribution = |opener: Word| {
+                results
+                    .iter()
+                    .find(|r| r.word == opener)
+                    .and_then(|r| r.distribution.as_ref())
+                    .expect("distributions aren't being kept")
+            };
+            write_switch(
+                &mut io::stdout(),
+                &opened,
+                (from, distribution(from)),
+                (*to, distribution(*to)),
+            )
+            .expect("cannot write line");
+        }
+        Operation::Me {
+            lists,
+            previous_results,
+            store,
+        } => {
+            let games = read(&store.open());
+            let mut openers = games.iter().filter_map(Game::opener).collect::<Vec<_>>();
+            openers.sort();
+            openers.dedup();
+
+            let mut expected = HashMap::new();
+            if let Some(results) = previous_results {
+                expected.extend(
+                    read_scores(results)
+                        .into_iter()
+                        .filter(|(w, _)| openers.binary_search(w).is_ok()),
+                );
+            }
+            let unscored = openers
+                .iter()
+                .filter(|w| !expected.contains_key(*w))
+                .copied()
+                .collect::<Vec<_>>();
+            expected.extend(
+                lists
+                    .score(unscored, false)
+                    .into_iter()
+                    .map(|r| (r.word, r.score)),
+            );
+
+            write_report(&mut io::stdout(), &games, &expected, Date::today())
+                .expect("cannot write line");
+        }
+    }
+}
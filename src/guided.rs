@@ -0,0 +1,133 @@
+//! Searches the openers most likely to be the best first, rather than all of them in order, and
+//! stops once the best have stopped changing.  The first few are the ones whose feedback splits the
+//! answers most evenly, and after them each is the one most like the best openers scored so far,
+//! going by the feedback it would get if one of them were the answer: the more greens and yellows,
+//! the more alike they are.
+
+use std::sync::Mutex;
+
+use crate::{get_pattern, pattern_count, Results, Word};
+
+/// How many of the best openers so far the next is picked to be like.
+pub const LEADERS: usize = 10;
+/// How many openers are searched by how evenly they split the answers, before any are picked by
+/// what's best so far.
+const SEEDS: usize = 20;
+/// The search has converged once this many openers in a row haven't made the leaders.
+pub const PATIENCE: usize = 50;
+
+/// Gives out the openers to search, as an iterator that a solver can draw from.
+pub struct Guide<'a> {
+    /// The openers not given out yet, the most even split first.
+    left: Vec<Word>,
+    /// The most that will be given out.
+    budget: usize,
+    given: usize,
+    converged: bool,
+    results: &'a Mutex<Results>,
+}
+
+/// The number of answers `opener` can expect to leave, in bits: the lower, the more evenly its
+/// feedback splits them.
+fn expected_bits(opener: &Word, answers: &[Word]) -> f32 {
+    let mut counts = vec![0u32; pattern_count(opener.length())];
+    for answer in answers {
+        counts[get_pattern(answer, opener)] += 1;
+    }
+    counts
+        .iter()
+        .filter(|c| **c > 0)
+        .map(|c| *c as f32 * (*c as f32).log2())
+        .sum::<f32>()
+        / answers.len().max(1) as f32
+}
+
+/// How alike `a` and `b` are, from the feedback `b` would get if `a` were the answer.
+fn likeness(a: &Word, b: &Word) -> usize {
+    let mut pattern = get_pattern(a, b);
+    let mut sum = 0;
+    for _ in 0..b.length() {
+        sum += pattern % 3;
+        pattern /= 3;
+    }
+    sum
+}
+
+impl<'a> Guide<'a> {
+    /// Gives out at most `budget` of `words`, going by how they split `answers` and by the scores
+    /// in `results` as they come in.
+    pub fn new(
+        words: Vec<Word>,
+        answers: &[Word],
+        budget: usize,
+        results: &'a Mutex<Results>,
+    ) -> Self {
+        let mut left = words
+            .into_iter()
+            .map(|w| (expected_bits(&w, answers), w))
+            .collect::<Vec<_>>();
+        left.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Self {
+            left: left.into_iter().map(|(_, w)| w).collect(),
+            budget,
+            given: 0,
+            converged: false,
+            results,
+        }
+    }
+
+    /// How many openers have been given out.
+    pub fn given(&self) -> usize {
+        self.given
+    }
+
+    /// Whether it stopped because the leaders stopped changing, rather than for running out.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+}
+
+impl Iterator for Guide<'_> {
+    type Item = Word;
+
+    fn next(&mut self) -> Option<Word> {
+        if self.given >= self.budget || self.left.is_empty() {
+            return None;
+        }
+
+        let scored = self.results.lock().unwrap().word_scores.clone();
+        let mut ranked = scored.clone();
+        ranked.sort_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap().then(a.0.cmp(&b.0)));
+        let leaders = ranked
+            .iter()
+            .take(LEADERS)
+            .map(|(w, _)| *w)
+            .collect::<Vec<_>>();
+
+        if scored.len() >= SEEDS + PATIENCE
+            && scored[scored.len() - PATIENCE..]
+                .iter()
+                .all(|(w, _)| !leaders.contains(w))
+        {
+            self.converged = true;
+            return None;
+        }
+
+        // Of the ones most like a leader, the one that splits the answers most evenly.
+        let pick = match self.given < SEEDS || leaders.is_empty() {
+            true => 0,
+            false => {
+                let mut best = (0, 0);
+                for (i, word) in self.left.iter().enumerate() {
+                    let like = leaders.iter().map(|l| likeness(l, word)).max().unwrap();
+                    if like > best.1 {
+                        best = (i, like);
+                    }
+                }
+                best.0
+            }
+        };
+        self.given += 1;
+        Some(self.left.remove(pick))
+    }
+}
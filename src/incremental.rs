@@ -0,0 +1,80 @@
+//! Works out which openers a change to the guess list could have changed the score of, so a run
+//! after adding or removing a few guesses only searches those again.
+
+use std::mem;
+
+use std::collections::HashMap;
+
+use crate::fingerprint::Fingerprint;
+use crate::solver::{Policy, Solver};
+use crate::{
+    get_constraints, get_pattern, passes_constraints, pattern_count, Word, MAX_WORD_LENGTH,
+};
+
+/// Identifies everything an opener's score depends on but the guesses, which [`unaffected`] sorts
+/// out for itself: the answers and their weights, the guess limit, hard mode and anything else
+/// the search was told.  A results file's scores are only reused by a run with the same one.
+pub fn fingerprint(solver: &Solver) -> u64 {
+    let mut fingerprint = Fingerprint::new("results");
+    fingerprint
+        .add(solver.word_length())
+        .add(solver.guess_limit());
+    if solver.hard_mode() {
+        fingerprint.add("hard mode");
+    }
+    fingerprint.add("answers");
+    for (i, answer) in solver.answers().iter().enumerate() {
+        fingerprint
+            .add(answer)
+            .add_weight(solver.answer_weights().map_or(1.0, |w| w[i]));
+    }
+    // The guess list changes between the runs, but not the weights the guesses that are answers
+    // get, unless the weighting does.
+    fingerprint.add("guess weights");
+    let guess_words = solver.guess_words();
+    let guess_weights = guess_words
+        .indices()
+        .iter()
+        .map(|i| (guess_words.word(*i), guess_words.weight(*i)))
+        .collect::<HashMap<_, _>>();
+    for answer in solver.answers() {
+        fingerprint.add_weight(guess_weights.get(answer).copied().unwrap_or(1.0));
+    }
+    if solver.policy() != Policy::Random {
+        fingerprint.add("policy").add(solver.policy());
+    }
+    if let Some(sample) = solver.sample() {
+        fingerprint
+            .add("sample")
+            .add(sample.count())
+            .add(sample.seed());
+    }
+    if let Some(first_guesses) = solver.first_guesses() {
+        fingerprint.add("first guesses");
+        for (word, weight) in first_guesses {
+            fingerprint.add(word).add_weight(*weight);
+        }
+    }
+    fingerprint.finish()
+}
+
+/// Whether `opener`'s score is the same with or without each of the `changed` guesses.  The search
+/// only ever follows up with guesses that fit the feedback so far, and any that doesn't fit the
+/// opener's feedback never fits what comes after it, so a guess can only matter to an opener if it
+/// fits the feedback the opener gets from one of the answers.
+pub fn unaffected(opener: &Word, answers: &[Word], changed: &[Word]) -> bool {
+    if changed.is_empty() {
+        return true;
+    }
+
+    let mut seen = vec![false; pattern_count(opener.length())];
+    let mut constraints = Vec::with_capacity(MAX_WORD_LENGTH);
+    answers
+        .iter()
+        .filter(|a| *a != opener)
+        .filter(|a| !mem::replace(&mut seen[get_pattern(a, opener)], true))
+        .all(|answer| {
+            get_constraints(answer, opener, &mut constraints);
+            !changed.iter().any(|w| passes_constraints(w, &constraints))
+        })
+}
@@ -0,0 +1,198 @@
+//! An append-only log of every scored word, so a run that dies partway through can pick up where it
+//! left off.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::fingerprint::Fingerprint;
+use crate::solver::{Policy, Solver};
+use crate::storage::{Files, Log, Storage};
+use crate::{paths, Word};
+
+/// How often the journal is synced.  A journal kept in files writes each line as soon as it's
+/// scored, so only a crash of the whole machine loses anything there.  One kept in a bucket or
+/// a database holds its lines in memory until it's synced, so a crash of the run loses up to
+/// this much of it.  A run that fails or is interrupted syncs it first; see
+/// [`crate::exit::on_exit`].
+const SYNC_INTERVAL: Duration = Duration::from_secs(10);
+
+const HEADER: &str = "# brutal journal";
+const CACHE_DIR: &str = "scores";
+
+/// The scores in a journal, by word.
+pub type Scores = HashMap<Word, (f32, f32)>;
+
+/// Identifies everything a score depends on, so a journal is only recovered into a run that would
+/// have scored its words the same way.
+pub fn fingerprint(solver: &Solver) -> u64 {
+    let mut fingerprint = Fingerprint::new("journal");
+    fingerprint
+        .add(solver.word_length())
+        .add(solver.guess_limit());
+    fingerprint.add("answers");
+    solver.answers().iter().for_each(|w| {
+        fingerprint.add(w);
+    });
+    if let Some(weights) = solver.answer_weights() {
+        fingerprint.add("answer weights");
+        weights.iter().for_each(|w| {
+            fingerprint.add_weight(*w);
+        });
+    }
+    fingerprint.add("guesses");
+    let guess_words = solver.guess_words();
+    for i in guess_words.indices() {
+        fingerprint
+            .add(guess_words.word(*i))
+            .add_weight(guess_words.weight(*i));
+    }
+    // Left out when they aren't used, so they don't tell apart runs that score the same.
+    if solver.policy() != Policy::Random {
+        fingerprint.add("policy").add(solver.policy());
+    }
+    if let Some(sample) = solver.sample() {
+        fingerprint
+            .add("sample")
+            .add(sample.count())
+            .add(sample.seed());
+    }
+    if let Some(first_guesses) = solver.first_guesses() {
+        fingerprint.add("first guesses");
+        for (word, weight) in first_guesses {
+            fingerprint.add(word).add_weight(*weight);
+        }
+    }
+    fingerprint.finish()
+}
+
+/// Where scores are cached unless another store's given, so that any later run with the same
+/// answers and guesses can reuse them, whatever it's searching and wherever it's writing.
+pub fn default_cache() -> Option<Files> {
+    let dir = paths::cache_dir()?;
+    fs::create_dir_all(dir.join(CACHE_DIR)).ok()?;
+    Some(Files::new(dir))
+}
+
+/// The key the scores for runs with `fingerprint` are cached under.
+pub fn cache_key(fingerprint: u64) -> PathBuf {
+    Path::new(CACHE_DIR).join(format!("{:016x}", fingerprint))
+}
+
+pub struct Journal {
+    /// Where it's kept, for warnings.
+    location: String,
+    /// `None` once it can't be kept, after which the run carries on without it.
+    log: Option<Box<dyn Log>>,
+    synced: Instant,
+}
+
+impl Journal {
+    /// Opens the journal kept in `storage` under `key` for a run with `fingerprint`, along with the
+    /// scores already in it.  A journal left by a different configuration is started over if
+    /// `start_over` is set, and otherwise isn't opened at all.  One that can't be read or written
+    /// is warned about and left alone, and the run carries on without it.
+    pub fn open(
+        storage: &dyn Storage,
+        key: &Path,
+        fingerprint: u64,
+        start_over: bool,
+    ) -> Option<(Self, Scores)> {
+        let header = format!("{} {:016x}", HEADER, fingerprint);
+        let mut journal = Self {
+            location: storage.locate(key),
+            log: None,
+            synced: Instant::now(),
+        };
+
+        let contents = match storage.read(key) {
+            Ok(contents) => contents.unwrap_or_default(),
+            Err(e) => {
+                journal.check(Err(e));
+                return Some((journal, HashMap::new()));
+            }
+        };
+        let contents = String::from_utf8_lossy(&contents);
+        // Anything after the last newline was cut off partway through being written.
+        let complete = &contents[..contents.rfind('\n').map_or(0, |i| i + 1)];
+
+        let mut scores = HashMap::new();
+        let mut lines = complete.lines();
+        let resuming = match lines.next() {
+            Some(h) if h == header => true,
+            Some(h) if h.starts_with(HEADER) && !start_over => return None,
+            _ => false,
+        };
+        if resuming {
+            scores.extend(lines.filter_map(parse_line));
+        }
+
+        let opened = (|| {
+            // Only a journal that isn't this run's is started over, so one that other machines
+            // are adding to at the same time is only ever added to.
+            match resuming {
+                true => {
+                    if let Err(e) = storage.compact(key) {
+                        eprintln!("warning: cannot compact {}: {}", journal.location, e);
+                    }
+                }
+                false => storage.write(key, format!("{}\n", header).as_bytes())?,
+            }
+            let mut log = storage.append(key)?;
+            if resuming && complete.len() < contents.len() {
+                // Start after whatever was cut off, rather than on the end of it.
+                writeln!(log)?;
+            }
+            Ok(log)
+        })();
+        match opened {
+            Ok(log) => journal.log = Some(log),
+            Err(e) => journal.check(Err(e)),
+        }
+        Some((journal, scores))
+    }
+
+    pub fn record(&mut self, word: &Word, (guess_count, success_rate): (f32, f32)) {
+        if let Some(log) = &mut self.log {
+            let written = writeln!(log, "{}, {}, {}", word, guess_count, success_rate);
+            self.check(written);
+        }
+
+        if self.synced.elapsed() >= SYNC_INTERVAL {
+            self.sync();
+        }
+    }
+
+    pub fn sync(&mut self) {
+        if let Some(log) = &mut self.log {
+            let synced = log.sync();
+            self.check(synced);
+        }
+        self.synced = Instant::now();
+    }
+
+    /// Gives up on the journal if `result` is an error, so a store that's gone away costs the run
+    /// its journal rather than the run itself.
+    fn check(&mut self, result: io::Result<()>) {
+        if let Err(e) = result {
+            eprintln!(
+                "warning: cannot keep {}: {}; carrying on without it",
+                self.location, e
+            );
+            self.log = None;
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<(Word, (f32, f32))> {
+    let mut columns = line.split(',').map(str::trim);
+    let word = columns.next()?.parse().ok()?;
+    let guess_count = columns.next()?.parse().ok()?;
+    let success_rate = columns.next()?.parse().ok()?;
+    columns
+        .next()
+        .is_none()
+        .then_some((word, (guess_count, success_rate)))
+}
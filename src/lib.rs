@@ -0,0 +1,755 @@
+//! The search at the heart of brutal, for scoring Wordle openers by brute force, and the solver
+//! that spreads it over threads.  A [`Word`] is parsed from its letters, the feedback one guess
+//! gets against an answer comes as [`get_constraints`] or [`get_pattern`], and [`get_score`] plays
+//! out every game from there.  Scoring whole openers is best left to
+//! [`solver::SolverConfig`], which keeps the search's state and can keep much more than the scores.
+//! Partway through a game, [`solver::Solver::suggest`] suggests the next guess from the game's
+//! [`clue`]s, searching what's left exactly with [`finish`].
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::alphabet::{BLANK, PAD};
+use crate::failures::FailureLog;
+use crate::memo::Memo;
+use crate::solver::{CancellationToken, SearchStats};
+use crate::word_list::WordList;
+
+pub mod alloc;
+pub mod alphabet;
+pub mod clue;
+pub mod distribution;
+pub mod failures;
+pub mod fingerprint;
+pub mod finish;
+pub mod frequency;
+pub mod memo;
+pub mod race;
+pub mod sample;
+pub mod seed;
+pub mod solver;
+pub mod tablebase;
+pub mod word_list;
+
+/// The most letters a word can have.
+pub const MAX_WORD_LENGTH: usize = 8;
+/// The guesses a game allows.
+pub const GUESS_LIMIT: usize = 6;
+
+/// A word, as the codes of its letters from [`alphabet`], so the search only ever compares bytes.
+/// Parse one from its letters, like `"crane".parse::<Word>()`.  It can have up to
+/// [`MAX_WORD_LENGTH`] letters, five for Wordle, followed by [`alphabet::PAD`] the rest of the
+/// way.  Words of different lengths never belong to the same game, so whatever brings words
+/// together, like a [`WordList`], makes sure they all have as many letters as each other.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Word {
+    letters: [u8; MAX_WORD_LENGTH],
+    length: u8,
+}
+
+impl Word {
+    /// A word of `length` blank letters, for filling in.
+    pub fn blank(length: usize) -> Self {
+        let mut word = Word {
+            letters: [PAD; MAX_WORD_LENGTH],
+            length: length as u8,
+        };
+        word.letters_mut().fill(BLANK);
+        word
+    }
+
+    /// How many letters the word has.
+    pub fn length(&self) -> usize {
+        self.length as usize
+    }
+
+    pub fn letters(&self) -> &[u8] {
+        &self.letters[..self.length()]
+    }
+
+    pub fn letters_mut(&mut self) -> &mut [u8] {
+        let length = self.length();
+        &mut self.letters[..length]
+    }
+}
+
+/// Alphabetical, going by the letters themselves rather than their codes.
+impl Ord for Word {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let letters = |w: &Self| {
+            let mut letters = ['\0'; MAX_WORD_LENGTH];
+            for (l, c) in letters.iter_mut().zip(w.letters()) {
+                *l = alphabet::decode(*c);
+            }
+            letters
+        };
+        letters(self).cmp(&letters(other))
+    }
+}
+
+impl PartialOrd for Word {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.letters()
+            .iter()
+            .try_for_each(|c| write!(f, "{}", alphabet::decode(*c)))
+    }
+}
+
+impl FromStr for Word {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let length = value.chars().count();
+        if length == 0 || length > MAX_WORD_LENGTH {
+            return Err("word has incorrect length");
+        }
+        let mut word = Word::blank(length);
+        for (d, c) in word.letters_mut().iter_mut().zip(value.chars()) {
+            *d = alphabet::encode(c)?;
+        }
+        Ok(word)
+    }
+}
+
+impl TryFrom<&str> for Word {
+    type Error = &'static str;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// From the word's letters as ASCII bytes, like `*b"crane"`.
+impl<const N: usize> TryFrom<[u8; N]> for Word {
+    type Error = &'static str;
+
+    fn try_from(value: [u8; N]) -> Result<Self, Self::Error> {
+        if N == 0 || N > MAX_WORD_LENGTH {
+            return Err("word has incorrect length");
+        }
+        let mut word = Word::blank(N);
+        for (d, c) in word.letters_mut().iter_mut().zip(value) {
+            if !c.is_ascii() {
+                return Err("word isn't ASCII");
+            }
+            *d = alphabet::encode(c as char)?;
+        }
+        Ok(word)
+    }
+}
+
+impl From<Word> for String {
+    fn from(word: Word) -> Self {
+        word.to_string()
+    }
+}
+
+/// Words are serialized as their letters, like `"crane"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Word {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Word {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let letters = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+        letters.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// What a guess's feedback says about one of the answer's letters.  Letters are serialized as
+/// themselves, like `{"green": ["a", 0]}`, since their codes can differ between runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Constraint {
+    /// Present, and in the correct place.
+    Green(
+        #[cfg_attr(feature = "serde", serde(with = "alphabet::serde_letter"))] u8,
+        usize,
+    ),
+    /// Present, but not in the correct place.
+    Yellow(
+        #[cfg_attr(feature = "serde", serde(with = "alphabet::serde_letter"))] u8,
+        usize,
+    ),
+    /// Not present.
+    Gray(#[cfg_attr(feature = "serde", serde(with = "alphabet::serde_letter"))] u8),
+}
+
+/// Fills `buffer` with what the feedback for `guess` says about `answer`: the greens, then the
+/// yellows, then a gray for each of the guess's other letters.
+pub fn get_constraints(answer: &Word, guess: &Word, buffer: &mut Vec<Constraint>) {
+    buffer.clear();
+
+    let mut answer = *answer;
+    let mut guess = *guess;
+
+    for (i, c) in guess.letters_mut().iter_mut().enumerate() {
+        if answer.letters[i] == *c {
+            buffer.push(Constraint::Green(*c, i));
+            answer.letters[i] = BLANK;
+            *c = BLANK;
+        }
+    }
+
+    for (i, c) in guess
+        .letters_mut()
+        .iter_mut()
+        .enumerate()
+        .filter(|(_, c)| **c != BLANK)
+    {
+        if let Some(j) = answer.letters.iter().position(|d| d == c) {
+            buffer.push(Constraint::Yellow(*c, i));
+            answer.letters[j] = BLANK;
+            *c = BLANK;
+        }
+    }
+
+    for c in guess.letters().iter().filter(|c| **c != BLANK) {
+        if !buffer.contains(&Constraint::Gray(*c)) {
+            buffer.push(Constraint::Gray(*c));
+        }
+    }
+}
+
+/// The feedback for `guess` as a base-3 number, with a digit per position (0 gray, 1 yellow,
+/// 2 green), so that equal feedback always produces equal patterns.
+pub fn get_pattern(answer: &Word, guess: &Word) -> usize {
+    let mut answer = *answer;
+    let mut digits = [0; MAX_WORD_LENGTH];
+    let digits = &mut digits[..guess.length()];
+
+    for (i, c) in guess.letters().iter().enumerate() {
+        if answer.letters[i] == *c {
+            digits[i] = 2;
+            answer.letters[i] = BLANK;
+        }
+    }
+
+    for (i, c) in guess.letters().iter().enumerate() {
+        if digits[i] > 0 {
+            continue;
+        }
+
+        if let Some(j) = answer.letters.iter().position(|d| d == c) {
+            digits[i] = 1;
+            answer.letters[j] = BLANK;
+        }
+    }
+
+    digits.iter().fold(0, |pattern, d| pattern * 3 + d)
+}
+
+/// The number of distinct patterns `get_pattern` can produce for words of `length` letters.
+pub fn pattern_count(length: usize) -> usize {
+    3usize.pow(length as u32)
+}
+
+/// The most distinct patterns `get_pattern` can produce, with words of the most letters.
+pub const MAX_PATTERN_COUNT: usize = 3usize.pow(MAX_WORD_LENGTH as u32);
+
+/// The pattern of a guess of `length` letters that's the answer.
+pub fn solved_pattern(length: usize) -> usize {
+    pattern_count(length) - 1
+}
+
+fn passes_constraint(word: &Word, constraint: &Constraint) -> bool {
+    // No letter is ever padding, so the padding never gets in the way.
+    match constraint {
+        Constraint::Green(c, i) => word.letters[*i] == *c,
+        Constraint::Yellow(c, i) => word.letters.contains(c) && word.letters[*i] != *c,
+        Constraint::Gray(c) => !word.letters.contains(c),
+    }
+}
+
+/// Whether `word` could be the answer, given `constraints` from [`get_constraints`].
+pub fn passes_constraints(word: &Word, constraints: &[Constraint]) -> bool {
+    let mut characters = *word;
+
+    for constraint in constraints {
+        if !passes_constraint(&characters, constraint) {
+            return false;
+        }
+
+        match constraint {
+            Constraint::Green(_, i) => characters.letters[*i] = BLANK,
+            Constraint::Yellow(c, _) => {
+                *characters.letters.iter_mut().find(|d| *d == c).unwrap() = BLANK
+            }
+            Constraint::Gray(_) => (),
+        }
+    }
+
+    true
+}
+
+/// Fills `buffer` with those of `indices` into `list` whose words pass `constraints`, in order.
+pub fn filter_word_list(
+    list: &WordList,
+    indices: &[u32],
+    constraints: &[Constraint],
+    buffer: &mut Vec<u32>,
+) {
+    buffer.clear();
+
+    indices
+        .iter()
+        .filter(|i| passes_constraints(list.word(**i), constraints))
+        .for_each(|i| buffer.push(*i));
+}
+
+/// Scores `guess` as guess number `starting_guess` against `answer`, with `words` the indices into
+/// `list` of the guesses still possible before it, and every guess after it any of the words still
+/// possible then, at random.  Returns the average guess the answer's found on, when it's found, and
+/// the chance it's found within `state`'s guess limit.  `weight` is the chance of the game getting
+/// this far, for recording failures.
+pub fn get_score(
+    answer: &Word,
+    guess: &Word,
+    words: &[u32],
+    starting_guess: usize,
+    weight: f32,
+    list: &WordList,
+    state: &mut SearchState,
+) -> (f32, f32) {
+    state.search.nodes += 1;
+
+    if answer == guess {
+        state.search.leaves += 1;
+        return (starting_guess as f32, 1.0);
+    }
+
+    if starting_guess >= state.guess_limit {
+        state.search.leaves += 1;
+        if let Some(failures) = &mut state.failures {
+            failures.record(answer, list, words, weight);
+        }
+        return (0.0, 0.0);
+    }
+
+    // The score's thrown away if it's cancelled, so it doesn't matter what's returned.
+    if state.is_cancelled() {
+        return (0.0, 0.0);
+    }
+
+    let depth = starting_guess - 1;
+    if depth == state.words.len() {
+        // Only without a guess limit, the first time the search gets this deep.
+        state.grow();
+    }
+    let mut next_words = mem::take(&mut state.words[depth]);
+
+    get_constraints(answer, guess, &mut state.constraints[depth]);
+    filter_word_list(list, words, &state.constraints[depth], &mut next_words);
+
+    let score = if next_words.len() > state.greedy_above {
+        let guess = list.word(most_informative(&next_words, list));
+        get_score(
+            answer,
+            guess,
+            &next_words,
+            starting_guess + 1,
+            weight,
+            list,
+            state,
+        )
+    } else if starting_guess + 1 >= state.guess_limit {
+        state.search.leaves += 1;
+        get_last_guess_score(answer, &next_words, weight, list, state)
+    } else if let Some(score) = get_pair_score(answer, &next_words, starting_guess, list) {
+        state.search.leaves += 1;
+        score
+    } else {
+        get_deep_score(answer, &next_words, starting_guess, weight, list, state)
+    };
+
+    state.words[depth] = next_words;
+    score
+}
+
+/// How close two guesses' entropies have to be to count as a tie, since summing the same split in a
+/// different order can round differently.
+const TIE_TOLERANCE: f32 = 1e-5;
+
+/// The one of `words` whose feedback would split them up the most evenly, going by their weights,
+/// which is the first of them if there's a tie.  The order of the guess list decides which that
+/// is.
+fn most_informative(words: &[u32], list: &WordList) -> u32 {
+    let mut pattern_weights = [0.0f32; MAX_PATTERN_COUNT];
+    let pattern_weights = &mut pattern_weights[..pattern_count(list.word_length())];
+    let mut best = (words[0], f32::NEG_INFINITY);
+
+    for guess in words {
+        pattern_weights.iter_mut().for_each(|w| *w = 0.0);
+        for answer in words {
+            pattern_weights[get_pattern(list.word(*answer), list.word(*guess))] +=
+                list.weight(*answer);
+        }
+
+        let weight_sum = pattern_weights.iter().sum::<f32>();
+        let entropy = pattern_weights
+            .iter()
+            .filter(|w| **w > 0.0)
+            .map(|w| -(w / weight_sum) * (w / weight_sum).log2())
+            .sum::<f32>();
+        if entropy > best.1 + TIE_TOLERANCE {
+            best = (*guess, entropy);
+        }
+    }
+
+    best.0
+}
+
+/// Guessing each of `words` as the last guess only succeeds if it's the answer, so there's nothing
+/// left to search.
+fn get_last_guess_score(
+    answer: &Word,
+    words: &[u32],
+    weight: f32,
+    list: &WordList,
+    state: &mut SearchState,
+) -> (f32, f32) {
+    let weight_sum = words.iter().map(|i| list.weight(*i)).sum::<f32>();
+    let answer_weight = words
+        .iter()
+        .filter(|i| list.word(**i) == answer)
+        .map(|i| list.weight(*i))
+        .sum::<f32>();
+
+    if let Some(failures) = &mut state.failures {
+        if answer_weight < weight_sum {
+            failures.record(
+                answer,
+                list,
+                words,
+                weight * (weight_sum - answer_weight) / weight_sum,
+            );
+        }
+    }
+
+    if answer_weight > 0.0 {
+        (state.guess_limit as f32, answer_weight / weight_sum)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// With only the answer and one other word left, either the answer is guessed next, or the other
+/// word is and rules itself out, so the answer is guessed after that.  Only valid with at least two
+/// guesses to go.
+fn get_pair_score(
+    answer: &Word,
+    words: &[u32],
+    starting_guess: usize,
+    list: &WordList,
+) -> Option<(f32, f32)> {
+    let (a, b) = match words {
+        [a, b] if list.word(*a) == answer && list.word(*b) != answer => (*a, *b),
+        [b, a] if list.word(*a) == answer && list.word(*b) != answer => (*a, *b),
+        _ => return None,
+    };
+
+    let (answer_weight, other_weight) = (list.weight(a), list.weight(b));
+    let guesses_sum =
+        answer_weight * (starting_guess + 1) as f32 + other_weight * (starting_guess + 2) as f32;
+
+    Some((guesses_sum / (answer_weight + other_weight), 1.0))
+}
+
+/// Averages the scores of guessing each of `words` next.
+fn get_deep_score(
+    answer: &Word,
+    words: &[u32],
+    starting_guess: usize,
+    weight: f32,
+    list: &WordList,
+    state: &mut SearchState,
+) -> (f32, f32) {
+    // Not while failures are being recorded, since an endgame that's looked up records none.
+    let remembered =
+        state.failures.is_none() && (memo::MIN_WORDS..=memo::MAX_WORDS).contains(&words.len());
+    if let Some(memo) = state.memo.as_ref().filter(|_| remembered) {
+        if let Some(score) = memo.get(answer, starting_guess, words) {
+            return score;
+        }
+    }
+
+    let score = search_deep_score(answer, words, starting_guess, weight, list, state);
+    if let Some(memo) = state
+        .memo
+        .as_ref()
+        .filter(|_| remembered && !state.cancelled)
+    {
+        memo.insert(answer, starting_guess, words, score);
+    }
+    score
+}
+
+fn search_deep_score(
+    answer: &Word,
+    words: &[u32],
+    starting_guess: usize,
+    weight: f32,
+    list: &WordList,
+    state: &mut SearchState,
+) -> (f32, f32) {
+    let weight_sum = words.iter().map(|i| list.weight(*i)).sum::<f32>();
+
+    let mut guesses_sum = 0.0;
+    let mut success_sum = 0.0;
+
+    for i in words.iter() {
+        let word_weight = list.weight(*i);
+
+        let (guess_count, success_rate) = get_score(
+            answer,
+            list.word(*i),
+            words,
+            starting_guess + 1,
+            weight * word_weight / weight_sum,
+            list,
+            state,
+        );
+
+        guesses_sum += word_weight * guess_count * success_rate;
+        success_sum += word_weight * success_rate;
+    }
+
+    if success_sum > 0.0 {
+        (guesses_sum / success_sum, success_sum / weight_sum)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// How many searches go by between checks of the cancellation token.
+const CANCELLATION_INTERVAL: usize = 1024;
+
+/// Preallocated space for each level of the search, so we don't slap the shit out of the heap, and
+/// anything we're recording along the way.
+pub struct SearchState {
+    constraints: Vec<Vec<Constraint>>,
+    words: Vec<Vec<u32>>,
+    /// The guess the search stops at, which is `usize::MAX` if there's no limit.
+    guess_limit: usize,
+    /// While more words than this are still possible, only the most informative is guessed next.
+    greedy_above: usize,
+    failures: Option<FailureLog>,
+    /// The endgames already scored, if they're being remembered.
+    memo: Option<Arc<Memo>>,
+    /// Everything the state has searched, so far.
+    search: SearchStats,
+    /// Heap allocations made inside the search, if they're being counted.
+    allocations: usize,
+    cancellation: Option<CancellationToken>,
+    /// Searches since the cancellation token was last checked.
+    unchecked: usize,
+    cancelled: bool,
+}
+
+impl SearchState {
+    /// Without a guess limit, there's only space for as many levels as there would be with one, and
+    /// the rest are added as they're needed.
+    pub fn new(word_count: usize, guess_limit: usize) -> Self {
+        let levels = guess_limit.min(GUESS_LIMIT);
+        Self {
+            constraints: (0..levels)
+                .map(|_| Vec::with_capacity(MAX_WORD_LENGTH))
+                .collect(),
+            words: (0..levels)
+                .map(|_| Vec::with_capacity(word_count))
+                .collect(),
+            guess_limit,
+            greedy_above: usize::MAX,
+            failures: None,
+            memo: None,
+            search: SearchStats::default(),
+            allocations: 0,
+            cancellation: None,
+            unchecked: 0,
+            cancelled: false,
+        }
+    }
+
+    fn grow(&mut self) {
+        self.constraints.push(Vec::with_capacity(MAX_WORD_LENGTH));
+        self.words.push(Vec::new());
+    }
+
+    /// Checks the cancellation token every so often, rather than at every step of the search.
+    fn is_cancelled(&mut self) -> bool {
+        self.unchecked += 1;
+        if self.unchecked >= CANCELLATION_INTERVAL {
+            self.unchecked = 0;
+            self.cancelled = self
+                .cancellation
+                .as_ref()
+                .is_some_and(CancellationToken::is_cancelled);
+        }
+        self.cancelled
+    }
+}
+
+/// Scores `guess` as an opener against each of the answers, calling `on_answer` before each one.
+fn score_word(
+    guess: &Word,
+    answers: &[Word],
+    guess_words: &WordList,
+    state: &mut SearchState,
+    mut on_answer: impl FnMut(&Word),
+) -> Vec<(f32, f32)> {
+    answers
+        .iter()
+        .map(|answer| {
+            on_answer(answer);
+
+            let allocations = alloc::thread_allocations();
+            let score = get_score(
+                answer,
+                guess,
+                guess_words.indices(),
+                1,
+                1.0,
+                guess_words,
+                state,
+            );
+            state.allocations += alloc::thread_allocations() - allocations;
+
+            score
+        })
+        .collect()
+}
+
+/// Scores `guess` as a second guess against each of the answers, with the first drawn from
+/// `first_guesses` by weight, calling `on_answer` before each answer.  A first guess that's the
+/// answer finishes the game before `guess` is played.
+fn score_second_guess(
+    guess: &Word,
+    first_guesses: &[(Word, f32)],
+    answers: &[Word],
+    guess_words: &WordList,
+    state: &mut SearchState,
+    mut on_answer: impl FnMut(&Word),
+) -> Vec<(f32, f32)> {
+    let weight_sum = first_guesses.iter().map(|(_, w)| w).sum::<f32>();
+
+    answers
+        .iter()
+        .map(|answer| {
+            on_answer(answer);
+
+            let allocations = alloc::thread_allocations();
+            let mut first_words = mem::take(&mut state.words[0]);
+
+            let mut guesses_sum = 0.0;
+            let mut success_sum = 0.0;
+
+            for (first_guess, first_weight) in first_guesses {
+                let weight = first_weight / weight_sum;
+
+                let (guess_count, success_rate) = if first_guess == answer {
+                    (1.0, 1.0)
+                } else {
+                    get_constraints(answer, first_guess, &mut state.constraints[0]);
+                    let indices = guess_words.indices();
+                    filter_word_list(
+                        guess_words,
+                        indices,
+                        &state.constraints[0],
+                        &mut first_words,
+                    );
+                    get_score(answer, guess, &first_words, 2, weight, guess_words, state)
+                };
+
+                guesses_sum += weight * guess_count * success_rate;
+                success_sum += weight * success_rate;
+            }
+
+            state.words[0] = first_words;
+            state.allocations += alloc::thread_allocations() - allocations;
+
+            if success_sum > 0.0 {
+                (guesses_sum / success_sum, success_sum)
+            } else {
+                (0.0, 0.0)
+            }
+        })
+        .collect()
+}
+
+/// Combines the scores against each answer into an average guess count and success rate, with the
+/// answers optionally weighted.
+pub fn combine_scores(answer_scores: &[(f32, f32)], weights: Option<&[f32]>) -> (f32, f32) {
+    let mut guesses_sum = 0.0;
+    let mut success_sum = 0.0;
+    let mut weight_sum = 0.0;
+
+    for (i, (guess_count, success_rate)) in answer_scores.iter().enumerate() {
+        let weight = weights.map_or(1.0, |w| w[i]);
+
+        guesses_sum += weight * guess_count * success_rate;
+        success_sum += weight * success_rate;
+        weight_sum += weight;
+    }
+
+    let guess_count = if success_sum > 0.0 {
+        guesses_sum / success_sum
+    } else {
+        0.0
+    };
+
+    (guess_count, success_sum / weight_sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The pattern for `digits`, a digit per position (0 gray, 1 yellow, 2 green).
+    fn pattern(digits: &[usize]) -> usize {
+        digits.iter().fold(0, |pattern, d| pattern * 3 + d)
+    }
+
+    fn feedback(answer: &str, guess: &str) -> usize {
+        get_pattern(&answer.parse().unwrap(), &guess.parse().unwrap())
+    }
+
+    #[test]
+    fn scores_letters_once_each() {
+        // Each b and a in the answer marks only one of the guess's.
+        assert_eq!(feedback("abbey", "kebab"), pattern(&[0, 1, 2, 1, 1]));
+        assert_eq!(feedback("abbey", "babes"), pattern(&[1, 1, 2, 2, 0]));
+    }
+
+    #[test]
+    fn greens_come_before_yellows() {
+        // The answer's one e is green at the end, which leaves none for the guess's first two.
+        assert_eq!(feedback("crane", "eerie"), pattern(&[0, 0, 1, 0, 2]));
+        assert_eq!(feedback("those", "geese"), pattern(&[0, 0, 0, 2, 2]));
+    }
+
+    #[test]
+    fn yellows_go_left_to_right() {
+        assert_eq!(feedback("cheer", "eerie"), pattern(&[1, 1, 1, 0, 0]));
+        assert_eq!(feedback("mason", "nanny"), pattern(&[1, 2, 0, 0, 0]));
+    }
+
+    #[test]
+    fn solves_only_on_the_answer() {
+        assert_eq!(feedback("crane", "crane"), solved_pattern(5));
+        assert_eq!(feedback("ab", "ba"), pattern(&[1, 1]));
+    }
+}
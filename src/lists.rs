@@ -0,0 +1,299 @@
+//! Combines and samples word lists, reading them the same careful way whatever they came from, so
+//! preparing the answer and guess lists doesn't take pipelines that let a capitalized or
+//! wrong-length word through.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Subcommand, ValueHint};
+use rand::seq::SliceRandom;
+
+use crate::exit::{self, OrInvalidInput};
+use crate::{paths, seed, Word};
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Operation {
+    /// List the words in any of the lists, in the order they first appear
+    Union {
+        #[clap(
+            required = true,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The lists to combine"
+        )]
+        lists: Vec<PathBuf>,
+
+        #[clap(flatten)]
+        output: Output,
+    },
+    /// List the words in every one of the lists, in the order of the first
+    Intersect {
+        #[clap(
+            required = true,
+            min_values = 2,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The lists to intersect"
+        )]
+        lists: Vec<PathBuf>,
+
+        #[clap(flatten)]
+        output: Output,
+    },
+    /// List the words in the first list that aren't in any of the others
+    Subtract {
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The list to take words from"
+        )]
+        list: PathBuf,
+
+        #[clap(
+            required = true,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The lists of words to leave out"
+        )]
+        remove: Vec<PathBuf>,
+
+        #[clap(flatten)]
+        output: Output,
+    },
+    /// List some of a list's words, chosen at random, in the order they're listed
+    Sample {
+        #[clap(help = "How many words to choose")]
+        count: usize,
+
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The list to choose from"
+        )]
+        list: PathBuf,
+
+        #[clap(
+            long,
+            help = "Seed the choice, so the same seed chooses the same words",
+            default_value = "0"
+        )]
+        seed: u64,
+
+        #[clap(flatten)]
+        output: Output,
+    },
+}
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct Output {
+    #[clap(
+        short,
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Write the list to this file rather than to stdout"
+    )]
+    output: Option<PathBuf>,
+}
+
+/// The words of a list, each line trimmed and lowercased, without blank lines, `#` comments or
+/// repeats.
+struct List {
+    filename: PathBuf,
+    words: Vec<Word>,
+    /// How many lines had to be trimmed or lowercased.
+    normalized: usize,
+    repeated: usize,
+}
+
+impl List {
+    /// Reads the list at `filename`, which must have only words, in letters, all as long as its
+    /// first.
+    fn read(filename: &Path) -> Self {
+        let filename = paths::find_list(filename);
+        let contents =
+            fs::read_to_string(&filename).or_invalid_input(|| filename.display().to_string());
+
+        let mut list = Self {
+            filename: filename.clone(),
+            words: Vec::new(),
+            normalized: 0,
+            repeated: 0,
+        };
+        let mut seen = HashSet::new();
+        for (i, line) in contents.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let lower = trimmed.to_lowercase();
+            let word = match lower.parse::<Word>() {
+                Ok(word) if lower.chars().all(char::is_alphabetic) => word,
+                Ok(_) => exit::fail(
+                    exit::INVALID_INPUT,
+                    format!(
+                        "{}:{}: {:?} isn't all letters",
+                        filename.display(),
+                        i + 1,
+                        line
+                    ),
+                ),
+                Err(e) => exit::fail(
+                    exit::INVALID_INPUT,
+                    format!("{}:{}: {:?}: {}", filename.display(), i + 1, line, e),
+                ),
+            };
+            if let Some(first) = list.words.first().filter(|f| f.length() != word.length()) {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!(
+                        "{}:{}: {:?} has {} letters, but the list's first word, {}, has {}",
+                        filename.display(),
+                        i + 1,
+                        line,
+                        word.length(),
+                        first,
+                        first.length()
+                    ),
+                );
+            }
+            list.normalized += (lower != line) as usize;
+            if seen.insert(word) {
+                list.words.push(word);
+            } else {
+                list.repeated += 1;
+            }
+        }
+        list
+    }
+}
+
+/// Runs `operation`, writing the list it makes one word per line.  Written to a file, what was
+/// read and written is reported too.
+pub fn run(operation: &Operation) {
+    let (lists, output): (Vec<_>, _) = match operation {
+        Operation::Union { lists, output } | Operation::Intersect { lists, output } => {
+            (lists.iter().map(|l| List::read(l)).collect(), output)
+        }
+        Operation::Subtract {
+            list,
+            remove,
+            output,
+        } => (
+            [list]
+                .into_iter()
+                .chain(remove)
+                .map(|l| List::read(l))
+                .collect(),
+            output,
+        ),
+        Operation::Sample { list, output, .. } => (vec![List::read(list)], output),
+    };
+    // Words of different lengths can't be in one list, or be compared with each other.
+    let nonempty = lists
+        .iter()
+        .filter(|l| !l.words.is_empty())
+        .collect::<Vec<_>>();
+    if let Some([a, b]) = nonempty
+        .windows(2)
+        .find(|pair| pair[0].words[0].length() != pair[1].words[0].length())
+    {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!(
+                "{} has words of {} letters, but {} has words of {}",
+                a.filename.display(),
+                a.words[0].length(),
+                b.filename.display(),
+                b.words[0].length()
+            ),
+        );
+    }
+
+    let words = match operation {
+        Operation::Union { .. } => {
+            let mut seen = HashSet::new();
+            lists
+                .iter()
+                .flat_map(|l| &l.words)
+                .filter(|w| seen.insert(**w))
+                .copied()
+                .collect::<Vec<_>>()
+        }
+        Operation::Intersect { .. } => {
+            let rest = lists[1..]
+                .iter()
+                .map(|l| l.words.iter().collect::<HashSet<_>>())
+                .collect::<Vec<_>>();
+            lists[0]
+                .words
+                .iter()
+                .filter(|w| rest.iter().all(|r| r.contains(w)))
+                .copied()
+                .collect()
+        }
+        Operation::Subtract { .. } => {
+            let remove = lists[1..]
+                .iter()
+                .flat_map(|l| &l.words)
+                .collect::<HashSet<_>>();
+            lists[0]
+                .words
+                .iter()
+                .filter(|w| !remove.contains(w))
+                .copied()
+                .collect()
+        }
+        Operation::Sample { count, seed, .. } => {
+            let words = &lists[0].words;
+            if *count > words.len() {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!(
+                        "can't choose {} words from a list of {}",
+                        count,
+                        words.len()
+                    ),
+                );
+            }
+            let mut rng = seed::stream(*seed, "sample", 0);
+            let chosen = words
+                .choose_multiple(&mut rng, *count)
+                .collect::<HashSet<_>>();
+            words
+                .iter()
+                .filter(|w| chosen.contains(w))
+                .copied()
+                .collect()
+        }
+    };
+
+    let mut out: Box<dyn Write> = match &output.output {
+        Some(output) => Box::new(BufWriter::new(
+            File::create(output).or_invalid_input(|| output.display().to_string()),
+        )),
+        None => Box::new(io::stdout()),
+    };
+    for word in &words {
+        writeln!(out, "{}", word).expect("cannot write word");
+    }
+    out.flush().expect("cannot write word");
+
+    if let Some(output) = &output.output {
+        println!("Wrote {} words to {}", words.len(), output.display());
+        println!(
+            "  Read: {}",
+            lists.iter().map(|l| l.words.len()).sum::<usize>()
+        );
+        println!(
+            "  Trimmed or lowercased: {}",
+            lists.iter().map(|l| l.normalized).sum::<usize>()
+        );
+        println!(
+            "  Repeats dropped: {}",
+            lists.iter().map(|l| l.repeated).sum::<usize>()
+        );
+    }
+}
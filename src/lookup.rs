@@ -0,0 +1,64 @@
+//! Finds the listed words nearest to one that might have been mistyped, which needn't even be the
+//! right length.
+
+use std::cmp::Reverse;
+
+use crate::Word;
+
+/// The number of letters that have to be changed, added, removed or swapped with a neighbour to
+/// turn one word into the other.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, d) in distances[0].iter_mut().enumerate() {
+        *d = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let changed = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + changed);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+/// The number of letters the words have in common, wherever they are in each.
+pub fn shared_letters(a: &str, b: &str) -> usize {
+    let mut b = b.chars().map(Some).collect::<Vec<_>>();
+    a.chars()
+        .filter(|c| match b.iter().position(|d| *d == Some(*c)) {
+            Some(i) => {
+                b[i] = None;
+                true
+            }
+            None => false,
+        })
+        .count()
+}
+
+/// Up to `count` of `words`, nearest to `word` first: by edit distance, then by letters in common,
+/// then alphabetically.
+pub fn nearest(word: &str, words: &[Word], count: usize) -> Vec<Word> {
+    let mut words = words.to_vec();
+    words.sort_by_cached_key(|w| {
+        let listed = w.to_string();
+        (
+            edit_distance(word, &listed),
+            Reverse(shared_letters(word, &listed)),
+            *w,
+        )
+    });
+    words.truncate(count);
+    words
+}
@@ -1,199 +1,1006 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use std::cell::Cell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::{mpsc, Arc, Mutex};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::thread;
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand, ValueHint};
+use clap_complete::Shell;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rand::seq::SliceRandom;
+
+use brutal::{
+    alloc, alphabet, clue, combine_scores, distribution, failures, filter_word_list, fingerprint,
+    finish, frequency, get_constraints, get_pattern, memo, passes_constraints, pattern_count, seed,
+    solved_pattern, solver, tablebase, word_list, Word, GUESS_LIMIT, MAX_WORD_LENGTH,
+};
+
+use crate::columns::{Column, Field};
+use crate::distribution::Distribution;
+use crate::exit::OrInvalidInput;
+use crate::failures::{FailureCause, FailureLog};
+use crate::frequency::WordFrequencies;
+use crate::guided::Guide;
+use crate::journal::Journal;
+use crate::memo::Memo;
+use crate::metadata::Metadata;
+use crate::outcomes::OutcomeFile;
+use crate::player::PlayerStats;
+use crate::solver::{
+    CancellationToken, Policy, ProgressSink, SearchStats, Solver, SolverConfig, WordResult,
+    WorkerStats,
+};
+use crate::storage::{Files, Storage};
+use crate::tablebase::Tablebase;
+use crate::variant::Variant;
+use crate::versus::{Strategy, Turns};
+use crate::word_list::WordList;
+
+mod anagram;
+mod analysis;
+mod bee;
+mod book;
+mod chunks;
+mod columns;
+mod convert;
+mod curate;
+mod dump;
+mod exit;
+mod games;
+mod guided;
+mod incremental;
+mod journal;
+mod lists;
+mod lookup;
+mod metadata;
+mod morphology;
+mod outcomes;
+mod partition;
+mod paths;
+mod player;
+mod query;
+mod repl;
+mod s3;
+mod script;
+#[cfg(unix)]
+mod serve;
+mod setup;
+mod share;
+mod shootout;
+mod solve;
+mod sqlite;
+mod stats;
+mod storage;
+mod train;
+mod variant;
+mod versus;
+
+/// Warns if the answer list is so much bigger than the guess list that they look the wrong way
+/// around, or if the guess list leaves out some of the answers, which can then only be found by
+/// elimination and so bring success rates down.  With `include_answers`, the missing answers are
+/// added to the end of the guesses instead.
+fn check_list_roles(answers: &[Word], guesses: &mut Vec<Word>, include_answers: bool) {
+    let mut listed = guesses.iter().copied().collect::<HashSet<_>>();
+    let missing = answers
+        .iter()
+        .filter(|a| listed.insert(**a))
+        .copied()
+        .collect::<Vec<_>>();
+    if include_answers {
+        guesses.extend(missing);
+        return;
+    }
+
+    if answers.len() > 2 * guesses.len() {
+        eprintln!(
+            "warning: the answer list has {} words, more than twice the guess list's {}; are they the wrong way around?",
+            answers.len(),
+            guesses.len()
+        );
+    }
+    if let Some(example) = missing.first() {
+        eprintln!(
+            "warning: {} of the answers, like {}, aren't in the guess list, so they can never be guessed and success rates will be too low; --guesses-include-answers adds them",
+            missing.len(),
+            example
+        );
+    }
+}
+
+fn read_lines(filename: impl AsRef<Path>) -> Vec<Word> {
+    stream_lines(filename).collect()
+}
+
+/// Every word has to have as many letters as the list's first.
+fn stream_lines(filename: impl AsRef<Path>) -> impl Iterator<Item = Word> {
+    stream_group(filename, None)
+}
+
+/// The words with `length` letters, skipping the rest, or without a length, every word, which then
+/// has to have as many letters as the list's first.
+fn stream_group(filename: impl AsRef<Path>, length: Option<usize>) -> impl Iterator<Item = Word> {
+    let mut first_length = None;
+    list_words(filename).filter_map(move |(line, l, word)| {
+        if let Some(length) = length {
+            return (word.length() == length).then_some(word);
+        }
+        let first_length = *first_length.get_or_insert(word.length());
+        if word.length() != first_length {
+            exit::fail(
+                exit::INVALID_INPUT,
+                format!(
+                    "{}: {:?} has {} letters, but the list's first word has {}",
+                    line,
+                    l,
+                    word.length(),
+                    first_length
+                ),
+            );
+        }
+        Some(word)
+    })
+}
 
-const WORD_LENGTH: usize = 5;
-const GUESS_LIMIT: usize = 6;
+/// How many letters the words in the list have, which is more than one length for lists gathered
+/// from several games.
+fn list_lengths(filename: impl AsRef<Path>) -> BTreeSet<usize> {
+    list_words(filename).map(|(_, _, w)| w.length()).collect()
+}
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-struct Word([char; WORD_LENGTH]);
+/// Each word in the list, with its line's place in the file and its text, for errors about it.
+fn list_words(filename: impl AsRef<Path>) -> impl Iterator<Item = (String, String, Word)> {
+    let filename = paths::find_list(filename.as_ref());
+    let file = File::open(&filename).or_invalid_input(|| filename.display().to_string());
+    let buf = BufReader::new(file);
+    buf.lines().enumerate().map(move |(i, l)| {
+        let line = format!("{}:{}", filename.display(), i + 1);
+        let l = l.or_invalid_input(|| line.clone());
+        let word = Word::from_str(&l).or_invalid_input(|| format!("{}: {:?}", line, l));
+        (line, l, word)
+    })
+}
 
-impl ToString for Word {
-    fn to_string(&self) -> String {
-        self.0.iter().collect()
+/// Exits unless every one of `words`, from `source`, has as many letters as the `answers`.
+fn check_length<'a>(
+    source: impl Display,
+    words: impl IntoIterator<Item = &'a Word>,
+    answers: &[Word],
+) {
+    let length = match answers.first() {
+        Some(answer) => answer.length(),
+        None => return,
+    };
+    if let Some(word) = words.into_iter().find(|w| w.length() != length) {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!(
+                "{}: {} has {} letters, but the answers have {}",
+                source,
+                word,
+                word.length(),
+                length
+            ),
+        );
     }
 }
 
-impl FromStr for Word {
-    type Err = &'static str;
+/// The words in `guess_list`, which have to have as many letters as `answers`.
+fn read_guesses(guess_list: impl AsRef<Path>, answers: &[Word]) -> Vec<Word> {
+    let guesses = read_lines(&guess_list);
+    check_length(guess_list.as_ref().display(), &guesses, answers);
+    guesses
+}
 
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        if value.len() == WORD_LENGTH {
-            let mut word = Word(['_'; WORD_LENGTH]);
-            word.0
-                .iter_mut()
-                .zip(value.chars())
-                .for_each(|(d, c)| *d = c);
-            Ok(word)
-        } else {
-            Err("word has incorrect length")
-        }
+/// Adds the endgames in the table kept in `storage` under `key` to `memo`, unless it was kept for
+/// a different search, or it isn't there and isn't `required` to be.
+fn read_memo(storage: &dyn Storage, key: &Path, required: bool, memo: &Memo, list: &WordList) {
+    let location = || storage.locate(key);
+    let contents = match storage.read(key).or_invalid_input(location) {
+        Some(contents) => String::from_utf8(contents).or_invalid_input(location),
+        None if !required => return,
+        None => exit::fail(exit::INVALID_INPUT, format!("{}: not found", location())),
+    };
+    match memo.read(&contents, list) {
+        Ok(Some(count)) => println!("Read {} endgames from {}.", count, location()),
+        Ok(None) => eprintln!(
+            "warning: {} was kept by a search with different guesses, guess limit or policy, so none of its endgames are used",
+            location()
+        ),
+        Err((line, e)) => exit::fail(
+            exit::INVALID_INPUT,
+            format!("{}:{}: {}", location(), line, e),
+        ),
     }
 }
 
-fn read_lines(filename: impl AsRef<Path>) -> Vec<Word> {
-    let file = File::open(filename).expect("no such file");
+/// How common each word is, from lines of `word score`.
+fn read_frequencies(filename: impl AsRef<Path>) -> WordFrequencies {
+    let filename = paths::find_list(filename.as_ref());
+    let file = File::open(&filename).or_invalid_input(|| filename.display().to_string());
     let buf = BufReader::new(file);
-    buf.lines()
-        .map(|l| l.expect("could not parse line"))
-        .map(|s| Word::from_str(&s).expect("could not parse word"))
-        .collect()
+
+    let frequencies = buf
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i, l.or_invalid_input(|| filename.display().to_string())))
+        .filter(|(_, l)| !l.trim().is_empty())
+        .filter_map(|(i, l)| {
+            let line = || format!("{}:{}", filename.display(), i + 1);
+            let mut columns = l.split(|c: char| c == ',' || c.is_whitespace());
+            let word = columns.next().or_invalid_input(line);
+            let frequency = columns
+                .find(|c| !c.is_empty())
+                .or_invalid_input(|| format!("{}: missing frequency", line()))
+                .parse::<f32>()
+                .or_invalid_input(line);
+            word.parse::<Word>().ok().map(|w| (w, frequency))
+        })
+        .collect();
+
+    WordFrequencies::new(frequencies)
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Constraint {
-    /// Present, and in the correct place.
-    Green(char, usize),
-    /// Present, but not in the correct place.
-    Yellow(char, usize),
-    /// Not present.
-    Gray(char),
+/// How often the scores are rewritten while the search is running, unless told otherwise.
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Everything the workers have scored so far, shared between them.
+#[derive(Default)]
+struct Results {
+    word_scores: Vec<(Word, (f32, f32))>,
+    word_answer_scores: Vec<(Word, Vec<(f32, f32)>)>,
+    word_failures: Vec<(Word, FailureLog)>,
+    word_distributions: Vec<(Word, Distribution)>,
+    word_search: Vec<(Word, SearchStats)>,
+}
+
+impl Results {
+    fn add(&mut self, result: WordResult) {
+        self.word_scores.push((result.word, result.score));
+        if let Some(answer_scores) = result.answer_scores {
+            self.word_answer_scores.push((result.word, answer_scores));
+        }
+        if let Some(failures) = result.failures {
+            self.word_failures.push((result.word, failures));
+        }
+        if let Some(distribution) = result.distribution {
+            self.word_distributions.push((result.word, distribution));
+        }
+        if let Some(search) = result.search {
+            self.word_search.push((result.word, search));
+        }
+    }
+}
+
+/// What's written about an opener from how its feedback partitions the answers.
+#[derive(Clone, Copy)]
+struct Partition {
+    in_two: f32,
+    worst: usize,
 }
 
-fn get_constraints(answer: &Word, guess: &Word, buffer: &mut Vec<Constraint>) {
-    buffer.clear();
+/// Where the scores are written.
+enum Output {
+    File(PathBuf),
+    /// A directory of chunks, split by the split.
+    Chunks(PathBuf, chunks::Split),
+}
 
-    let mut answer = *answer;
-    let mut guess = *guess;
+/// Rewrites the scores file as the search goes.  It's kept apart from the results so that a slow
+/// disk only ever holds up the worker doing the writing.
+struct ScoreWriter {
+    output: Output,
+    /// Every column but the word, which always comes first.
+    columns: Vec<Column>,
+    sort_by: SortBy,
+    /// What the scores were searched with, for saying so in the file.
+    provenance: Provenance,
+    /// The letters the words have, for lining the header up with them.
+    word_length: usize,
+    /// Joined onto the end of each line.
+    metadata: Option<Metadata>,
+    /// The answers, if anything's written or sorted by that's worked out from them, and each
+    /// opener's chance of solving in two guesses and largest group of answers left, so far.
+    partitions: Option<(Vec<Word>, HashMap<Word, Partition>)>,
+    /// Rewrite after this many new results.
+    every: Option<usize>,
+    /// Rewrite once this long has passed.
+    interval: Option<Duration>,
+    /// When the scores were last written, and how many there were.
+    written: Option<Instant>,
+    written_count: usize,
+}
 
-    for (i, c) in guess.0.iter_mut().enumerate() {
-        if answer.0[i] == *c {
-            buffer.push(Constraint::Green(*c, i));
-            answer.0[i] = '_';
-            *c = '_';
+impl ScoreWriter {
+    fn new(
+        output: Output,
+        columns: Vec<Column>,
+        sort_by: SortBy,
+        solver: &Solver,
+        metadata: Option<Metadata>,
+        every: Option<usize>,
+        interval: Option<Duration>,
+    ) -> Self {
+        let needs_answers =
+            sort_by == SortBy::TwoGuess || columns.iter().any(|c| c.field.needs_answers());
+        Self {
+            output,
+            columns,
+            sort_by,
+            provenance: Provenance::of(solver),
+            word_length: solver.word_length(),
+            metadata,
+            partitions: needs_answers.then(|| (solver.answers().to_vec(), HashMap::new())),
+            every,
+            interval,
+            written: None,
+            written_count: 0,
         }
     }
 
-    for (i, c) in guess.0.iter_mut().enumerate().filter(|(_, c)| **c != '_') {
-        if let Some(j) = answer.0.iter().position(|d| d == c) {
-            buffer.push(Constraint::Yellow(*c, i));
-            answer.0[j] = '_';
-            *c = '_';
-        }
+    /// Whether the scores are due to be rewritten, now that there are `count` of them.
+    fn is_due(&self, count: usize) -> bool {
+        let by_count = self.every.is_some_and(|n| count - self.written_count >= n);
+        let by_time = self
+            .interval
+            .is_some_and(|i| self.written.is_none_or(|w| w.elapsed() >= i));
+        by_count || by_time
     }
 
-    for c in guess.0.iter().filter(|c| **c != '_') {
-        if !buffer.contains(&Constraint::Gray(*c)) {
-            buffer.push(Constraint::Gray(*c));
+    fn write(&mut self, word_scores: &mut [(Word, (f32, f32))]) {
+        if let Some((answers, partitions)) = &mut self.partitions {
+            for (word, _) in word_scores.iter() {
+                partitions.entry(*word).or_insert_with(|| Partition {
+                    in_two: analysis::two_guess_chance(word, answers),
+                    worst: analysis::largest_group(word, answers),
+                });
+            }
+        }
+        let partitions = self.partitions.as_ref().map(|(_, p)| p);
+
+        match self.sort_by {
+            SortBy::Average => {
+                word_scores.sort_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap().then(a.0.cmp(&b.0)))
+            }
+            SortBy::Success => word_scores.sort_by(|a, b| {
+                (b.1 .1, a.1 .0)
+                    .partial_cmp(&(a.1 .1, b.1 .0))
+                    .unwrap()
+                    .then(a.0.cmp(&b.0))
+            }),
+            SortBy::TwoGuess => {
+                let partitions = partitions.expect("partitions aren't being kept");
+                word_scores.sort_by(|a, b| {
+                    (partitions[&b.0].in_two, a.1 .0)
+                        .partial_cmp(&(partitions[&a.0].in_two, b.1 .0))
+                        .unwrap()
+                        .then(a.0.cmp(&b.0))
+                });
+            }
+        }
+        let mut header = columns::header(&self.columns, self.word_length);
+        let mut rows = score_rows(word_scores, &self.columns, partitions);
+        if let Some(metadata) = &self.metadata {
+            header += &metadata.header();
+            for (row, (word, _)) in rows.iter_mut().zip(word_scores.iter()) {
+                *row += &metadata.cells(word);
+            }
+        }
+        match &self.output {
+            Output::File(filename) => write_scores(filename, &header, &rows, &self.provenance),
+            Output::Chunks(dir, split) => {
+                write_chunks(dir, *split, word_scores, &header, &rows, &self.provenance)
+            }
         }
+        self.written = Some(Instant::now());
+        self.written_count = word_scores.len();
     }
 }
 
-fn passes_constraint(word: &Word, constraint: &Constraint) -> bool {
-    match constraint {
-        Constraint::Green(c, i) => word.0[*i] == *c,
-        Constraint::Yellow(c, i) => word.0.contains(c) && word.0[*i] != *c,
-        Constraint::Gray(c) => !word.0.contains(c),
+/// Shows the search's progress, and records and writes its results as they come in.
+struct SearchProgress {
+    worker_progress: Vec<ProgressBar>,
+    total_progress: ProgressBar,
+    results: Mutex<Results>,
+    writer: Mutex<ScoreWriter>,
+    /// The run's journal, and the cache's if it's being kept.
+    journals: Arc<Mutex<Vec<Journal>>>,
+    outcomes: Mutex<Option<OutcomeFile>>,
+    /// The openers that score the same as each searched one, and are finished along with it.
+    equivalents: HashMap<Word, Vec<Word>>,
+}
+
+impl SearchProgress {
+    fn finish(&self, result: WordResult) {
+        let (guess_count, success_rate) = result.score;
+        self.total_progress.inc(1);
+        self.total_progress.set_message(format!(
+            "{}, average: {:.3}, success: {:5.2}%",
+            result.word,
+            guess_count,
+            success_rate * 100.0,
+        ));
+
+        let count = {
+            let mut results = self.results.lock().unwrap();
+            for word in self.equivalents.get(&result.word).into_iter().flatten() {
+                self.total_progress.inc(1);
+                results.add(WordResult {
+                    word: *word,
+                    score: result.score,
+                    answer_scores: None,
+                    failures: None,
+                    distribution: None,
+                    answer_distributions: None,
+                    search: None,
+                });
+            }
+            results.add(result);
+            results.word_scores.len()
+        };
+
+        // Whoever gets to the writer writes from a copy of the scores, and everyone else gets back
+        // to searching rather than waiting on the disk.
+        if let Ok(mut writer) = self.writer.try_lock() {
+            if writer.is_due(count) {
+                let mut word_scores = self.results.lock().unwrap().word_scores.clone();
+                writer.write(&mut word_scores);
+            }
+        }
     }
 }
 
-fn passes_constraints(word: &Word, constraints: &[Constraint]) -> bool {
-    let mut characters = *word;
+impl ProgressSink for SearchProgress {
+    fn word_started(&self, worker: usize, word: &Word, answer_count: usize) {
+        let progress = &self.worker_progress[worker];
+        progress.reset();
+        progress.set_length(answer_count as u64);
+        progress.set_message(word.to_string());
+    }
 
-    for constraint in constraints {
-        if !passes_constraint(&characters, constraint) {
-            return false;
-        }
+    fn answer_started(&self, worker: usize, word: &Word, answer: &Word) {
+        let progress = &self.worker_progress[worker];
+        progress.set_message(format!("{} -> {}", word, answer));
+        progress.inc(1);
+    }
+
+    fn word_dropped(&self, worker: usize, word: &Word) {
+        self.worker_progress[worker].set_message(format!("{} dropped", word));
+        let equivalents = self.equivalents.get(word).map_or(0, Vec::len);
+        self.total_progress.inc(1 + equivalents as u64);
+    }
 
-        match constraint {
-            Constraint::Green(_, i) => characters.0[*i] = '_',
-            Constraint::Yellow(c, _) => *characters.0.iter_mut().find(|d| *d == c).unwrap() = '_',
-            Constraint::Gray(_) => (),
+    fn word_finished(&self, worker: usize, mut result: WordResult, _elapsed: Duration) {
+        self.worker_progress[worker].set_message(format!("{} done", result.word));
+        for journal in self.journals.lock().unwrap().iter_mut() {
+            journal.record(&result.word, result.score);
+        }
+        if let Some(distributions) = result.answer_distributions.take() {
+            if let Some(outcomes) = &mut *self.outcomes.lock().unwrap() {
+                outcomes.record(&result.word, &distributions);
+            }
         }
+        self.finish(result);
     }
+}
 
-    true
+/// Percentile rank (100 is best) and z-score of each value, relative to all of the values.
+fn normalize(values: &[f32], lower_is_better: bool) -> Vec<(f32, f32)> {
+    let count = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / count;
+    let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / count).sqrt();
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    values
+        .iter()
+        .map(|v| {
+            let less = sorted.partition_point(|u| u < v);
+            let less_or_equal = sorted.partition_point(|u| u <= v);
+            let worse = if lower_is_better {
+                sorted.len() - less_or_equal
+            } else {
+                less
+            };
+            let ties = less_or_equal - less - 1;
+
+            let percentile = if sorted.len() > 1 {
+                100.0 * (worse as f32 + 0.5 * ties as f32) / (count - 1.0)
+            } else {
+                100.0
+            };
+            let z_score = if std_dev > 0.0 {
+                (v - mean) / std_dev
+            } else {
+                0.0
+            };
+
+            (percentile, z_score)
+        })
+        .collect()
 }
 
-fn filter_word_list(words: &[Word], constraints: &[Constraint], buffer: &mut Vec<Word>) {
-    buffer.clear();
+/// Writes each chunk of `split` to a file of its own in `dir`, with `rows`, the lines for
+/// `word_scores`, in the same order, and an index of them.
+fn write_chunks(
+    dir: &Path,
+    split: chunks::Split,
+    word_scores: &[(Word, (f32, f32))],
+    header: &str,
+    rows: &[String],
+    provenance: &Provenance,
+) {
+    let mut chunks = HashMap::<String, (Vec<&str>, chunks::Chunk)>::new();
+    for ((word, (guess_count, _)), row) in word_scores.iter().zip(rows) {
+        let name = split.chunk(word, *guess_count);
+        let (lines, chunk) = chunks.entry(name.clone()).or_insert_with(|| {
+            let chunk = chunks::Chunk {
+                name,
+                words: 0,
+                min_average: f32::INFINITY,
+                max_average: f32::NEG_INFINITY,
+            };
+            (Vec::new(), chunk)
+        });
+        lines.push(row);
+        chunk.words += 1;
+        chunk.min_average = chunk.min_average.min(*guess_count);
+        chunk.max_average = chunk.max_average.max(*guess_count);
+    }
+    let mut chunks = chunks.into_values().collect::<Vec<_>>();
+    chunks.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+    for (lines, chunk) in &chunks {
+        let filename = dir.join(chunk.file_name());
+        write_scores(&filename, header, lines, provenance);
+    }
+    let chunks = chunks.into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+    chunks::write_index(dir, &chunks);
+}
 
-    words
+/// Each opener's line of a results file, with `columns` after its word.  Percentiles and z-scores
+/// are among all of `word_scores`.  Columns worked out from the answers need their `partitions`.
+fn score_rows(
+    word_scores: &[(Word, (f32, f32))],
+    columns: &[Column],
+    partitions: Option<&HashMap<Word, Partition>>,
+) -> Vec<String> {
+    let averages = normalize(
+        &word_scores.iter().map(|(_, s)| s.0).collect::<Vec<_>>(),
+        true,
+    );
+    let successes = normalize(
+        &word_scores.iter().map(|(_, s)| s.1).collect::<Vec<_>>(),
+        false,
+    );
+
+    word_scores
         .iter()
-        .filter(|w| passes_constraints(w, constraints))
-        .for_each(|w| buffer.push(*w));
+        .enumerate()
+        .map(|(i, (word, (guess_count, success_rate)))| {
+            let partition = || partitions.expect("partitions aren't being kept")[word];
+            let cells = columns
+                .iter()
+                .map(|c| {
+                    let value = match c.field {
+                        Field::Word => return format!("{:>w$}", word, w = c.width()),
+                        Field::Length => return format!("{:w$}", word.length(), w = c.width()),
+                        Field::Worst => return format!("{:w$}", partition().worst, w = c.width()),
+                        Field::Average => *guess_count,
+                        Field::Success => *success_rate,
+                        Field::AveragePercentile => averages[i].0,
+                        Field::AverageZ => averages[i].1,
+                        Field::SuccessPercentile => successes[i].0,
+                        Field::SuccessZ => successes[i].1,
+                        Field::InTwo => partition().in_two,
+                    };
+                    format!("{:w$.p$}", value, w = c.width(), p = c.precision)
+                })
+                .collect::<Vec<_>>();
+            format!("{}, {}", word, cells.join(", "))
+        })
+        .collect()
 }
 
-fn get_score(
-    answer: &Word,
-    guess: &Word,
-    words: &[Word],
-    starting_guess: usize,
-    constraint_buffers: &mut [Vec<Constraint>],
-    word_buffers: &mut [Vec<Word>],
-) -> (f32, f32) {
-    if answer == guess {
-        return (starting_guess as f32, 1.0);
+/// What a results file's scores were searched with, which the lines before its header say.
+struct Provenance {
+    policy: Policy,
+    hard_mode: bool,
+    weighted_answers: bool,
+    /// See [`incremental::fingerprint`].
+    fingerprint: u64,
+}
+
+impl Provenance {
+    fn of(solver: &Solver) -> Self {
+        Self {
+            policy: solver.policy(),
+            hard_mode: solver.hard_mode(),
+            weighted_answers: solver.answer_weights().is_some(),
+            fingerprint: incremental::fingerprint(solver),
+        }
+    }
+}
+
+fn write_scores(
+    filename: impl AsRef<Path>,
+    header: &str,
+    rows: &[impl AsRef<str>],
+    provenance: &Provenance,
+) {
+    let mut file = BufWriter::new(File::create(filename).expect("cannot open output file"));
+
+    writeln!(file, "{}", columns::schema_line()).expect("cannot write header");
+    if let Some(line) = columns::policy_line(provenance.policy) {
+        writeln!(file, "{}", line).expect("cannot write header");
+    }
+    if let Some(line) = columns::hard_mode_line(provenance.hard_mode) {
+        writeln!(file, "{}", line).expect("cannot write header");
+    }
+    if let Some(line) = columns::weighted_answers_line(provenance.weighted_answers) {
+        writeln!(file, "{}", line).expect("cannot write header");
     }
+    writeln!(
+        file,
+        "{}",
+        columns::fingerprint_line(provenance.fingerprint)
+    )
+    .expect("cannot write header");
+    writeln!(file, "{}", header).expect("cannot write header");
+    for row in rows {
+        writeln!(file, "{}", row.as_ref()).expect("cannot write line");
+    }
+    file.flush().expect("cannot write line");
+}
+
+/// A results file without a success column, from a run without a guess limit, always succeeds.
+/// The average and success rate are found by the header's names for them, since `--columns`
+/// can put them anywhere; without a header, they're the second and third columns.  Files with a
+/// newer schema than this version of brutal knows are refused.  A directory of chunks is read as
+/// all of them, ranked by average.
+fn read_scores(filename: impl AsRef<Path>) -> Vec<(Word, (f32, f32))> {
+    let filename = filename.as_ref();
+    if chunks::is_chunked(filename) {
+        let mut word_scores = chunks::read_index(filename)
+            .iter()
+            .flat_map(read_scores)
+            .collect::<Vec<_>>();
+        word_scores.sort_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap().then(a.0.cmp(&b.0)));
+        return word_scores;
+    }
+    let file = File::open(filename).or_invalid_input(|| filename.display().to_string());
+    let buf = BufReader::new(file);
+    let positions = Cell::new((1, Some(2)));
+    buf.lines()
+        .enumerate()
+        .map(|(i, l)| (i, l.or_invalid_input(|| filename.display().to_string())))
+        .filter(|(i, l)| {
+            if l.starts_with('#') {
+                columns::check_schema(l)
+                    .or_invalid_input(|| format!("{}:{}", filename.display(), i + 1));
+                return false;
+            }
+            if !l.starts_with("word,") {
+                return true;
+            }
+            let names = l.split(',').map(str::trim).collect::<Vec<_>>();
+            let position = |name| names.iter().position(|n| *n == name);
+            let average = position(Field::Average.name()).or_invalid_input(|| {
+                format!("{}:{}: no average column", filename.display(), i + 1)
+            });
+            positions.set((average, position(Field::Success.name())));
+            false
+        })
+        .map(|(i, l)| {
+            let line = || format!("{}:{}", filename.display(), i + 1);
+            let columns = l.split(',').map(str::trim).collect::<Vec<_>>();
+            let column = |i: usize| {
+                columns
+                    .get(i)
+                    .copied()
+                    .or_invalid_input(|| format!("{}: missing column", line()))
+            };
+            let (average, success) = positions.get();
+            let word = Word::from_str(column(0)).or_invalid_input(line);
+            let guess_count = column(average).parse().or_invalid_input(line);
+            let success_rate = match success {
+                Some(success) => column(success).parse().or_invalid_input(line),
+                None => 1.0,
+            };
+            (word, (guess_count, success_rate))
+        })
+        .collect()
+}
+
+/// The policy the scores in `filename` were searched with.
+fn read_policy(filename: impl AsRef<Path>) -> Policy {
+    read_header_line(filename.as_ref(), columns::parse_policy).unwrap_or(Policy::Random)
+}
+
+/// The fingerprint a results file gives, if it was written by a version of brutal that gave one.
+fn read_fingerprint(filename: impl AsRef<Path>) -> Option<u64> {
+    read_header_line(filename.as_ref(), columns::parse_fingerprint)
+}
 
-    if starting_guess >= GUESS_LIMIT {
-        return (0.0, 0.0);
+/// The value of the first of the comment lines at the start of a results file, or of its first
+/// chunk, that `parse` recognizes.
+fn read_header_line<T>(filename: &Path, parse: fn(&str) -> Option<Result<T, String>>) -> Option<T> {
+    if chunks::is_chunked(filename) {
+        return chunks::read_index(filename)
+            .first()
+            .and_then(|chunk| read_header_line(chunk, parse));
     }
+    let file = File::open(filename).or_invalid_input(|| filename.display().to_string());
+    for (i, l) in BufReader::new(file).lines().enumerate() {
+        let l = l.or_invalid_input(|| filename.display().to_string());
+        if let Some(value) = parse(&l) {
+            return Some(value.or_invalid_input(|| format!("{}:{}", filename.display(), i + 1)));
+        }
+        if !l.starts_with('#') {
+            break;
+        }
+    }
+    None
+}
 
-    get_constraints(answer, guess, &mut constraint_buffers[0]);
-    filter_word_list(words, &constraint_buffers[0], &mut word_buffers[0]);
+/// How wide the word column's header is, to line up with the words of `word_scores` under it.
+fn word_width(word_scores: &[(Word, (f32, f32))]) -> usize {
+    word_scores.first().map_or(0, |(w, _)| w.length()) + 1
+}
 
-    let (_, next_constraint_buffers) = constraint_buffers.split_at_mut(1);
-    let (next_words, next_word_buffers) = word_buffers.split_at_mut(1);
+fn write_bootstrap_intervals(
+    filename: impl AsRef<Path>,
+    intervals: &[analysis::BootstrapInterval],
+) {
+    let mut file = File::create(filename).expect("cannot open bootstrap file");
+
+    writeln!(
+        file,
+        "{:w$} average, avg_low, avg_high, rank, rank_low, rank_high",
+        "word,",
+        w = intervals.first().map_or(0, |i| i.word.length()) + 1
+    )
+    .expect("cannot write header");
+
+    for interval in intervals {
+        writeln!(
+            file,
+            "{}, {:7.3}, {:7.3}, {:8.3}, {:4}, {:8}, {:9}",
+            interval.word,
+            interval.average,
+            interval.average_range.0,
+            interval.average_range.1,
+            interval.rank,
+            interval.rank_range.0,
+            interval.rank_range.1,
+        )
+        .expect("cannot write line");
+    }
+}
 
-    let mut guesses_sum = 0.0;
-    let mut success_sum = 0.0;
+/// Writes how big each searched opener's search was, in the same order as `word_scores`.  Openers
+/// recovered from a journal weren't searched, so they're left out.
+fn write_search_stats(
+    filename: impl AsRef<Path>,
+    word_scores: &[(Word, (f32, f32))],
+    word_search: &[(Word, SearchStats)],
+) {
+    let mut file = File::create(filename).expect("cannot open stats file");
+
+    writeln!(
+        file,
+        "{:w$}         nodes,        leaves",
+        "word,",
+        w = word_width(word_scores)
+    )
+    .expect("cannot write header");
+
+    for (word, _) in word_scores {
+        if let Some((_, search)) = word_search.iter().find(|(w, _)| w == word) {
+            writeln!(file, "{}, {:13}, {:13}", word, search.nodes, search.leaves)
+                .expect("cannot write line");
+        }
+    }
+}
 
-    for word in next_words[0].iter() {
-        let (guess_count, success_rate) = get_score(
-            answer,
-            word,
-            &next_words[0],
-            starting_guess + 1,
-            next_constraint_buffers,
-            next_word_buffers,
-        );
+/// Writes each opener's failure rate by cause, with openers in the same order as `word_scores`,
+/// followed by the failure rates for all openers together under `*`.
+fn write_failure_report(
+    filename: impl AsRef<Path>,
+    word_scores: &[(Word, (f32, f32))],
+    word_failures: &[(Word, FailureLog)],
+    answer_count: usize,
+) {
+    let mut file = File::create(filename).expect("cannot open failure report");
+
+    writeln!(
+        file,
+        "{:w$}  failure, cause",
+        "word,",
+        w = word_width(word_scores)
+    )
+    .expect("cannot write header");
+
+    let width = word_width(word_scores);
+    let mut write_causes = |word: &str, failures: &FailureLog, count: usize| {
+        for (cause, weight) in failures.sorted() {
+            writeln!(
+                file,
+                "{:w$} {:8.6}, {}",
+                format!("{},", word),
+                weight / count as f32,
+                cause,
+                w = width
+            )
+            .expect("cannot write line");
+        }
+    };
 
-        guesses_sum += guess_count * success_rate;
-        success_sum += success_rate;
+    let mut all_failures = FailureLog::default();
+    for (word, _) in word_scores {
+        if let Some((_, failures)) = word_failures.iter().find(|(w, _)| w == word) {
+            write_causes(&word.to_string(), failures, answer_count);
+            all_failures.merge(failures);
+        }
     }
 
-    if success_sum > 0.0 {
-        (
-            guesses_sum / success_sum,
-            success_sum / next_words[0].len() as f32,
+    write_causes("*", &all_failures, answer_count * word_failures.len());
+}
+
+/// An opener's chance of failing is the chance of breaking a streak with it, and the answers it can
+/// fail on at all are the ones a streak is at risk from.  Its worst trap is the family of words, like
+/// _ATCH, that it's most likely to run out of guesses in.
+fn write_streak_risk(
+    filename: impl AsRef<Path>,
+    word_scores: &[(Word, (f32, f32))],
+    word_answer_scores: &[(Word, Vec<(f32, f32)>)],
+    word_failures: &[(Word, FailureLog)],
+    answer_count: usize,
+) {
+    let mut file = File::create(filename).expect("cannot open streak risk report");
+
+    writeln!(
+        file,
+        "{:w$}  failure, at_risk, worst_trap, trap_failure",
+        "word,",
+        w = word_width(word_scores)
+    )
+    .expect("cannot write header");
+
+    for (word, (_, success_rate)) in word_scores {
+        let at_risk = word_answer_scores
+            .iter()
+            .find(|(w, _)| w == word)
+            .map_or(0, |(_, s)| s.iter().filter(|(_, s)| *s < 1.0).count());
+        let worst_trap = word_failures
+            .iter()
+            .find(|(w, _)| w == word)
+            .and_then(|(_, failures)| {
+                failures
+                    .sorted()
+                    .into_iter()
+                    .find_map(|(c, weight)| match c {
+                        FailureCause::Family(mask) => Some((mask.to_string(), weight)),
+                        _ => None,
+                    })
+            });
+
+        let (trap, trap_failure) = match worst_trap {
+            Some((mask, weight)) => (mask, weight / answer_count as f32),
+            None => ("-".to_string(), 0.0),
+        };
+        writeln!(
+            file,
+            "{}, {:8.6}, {:7.4}, {:>10}, {:12.6}",
+            word,
+            1.0 - success_rate,
+            at_risk as f32 / answer_count as f32,
+            trap,
+            trap_failure,
         )
-    } else {
-        (0.0, 0.0)
+        .expect("cannot write line");
+    }
+}
+
+/// Writes each opener's average and success rate at each of `limits`, and its average without a
+/// limit, with openers in the same order as `word_scores`.
+fn write_limit_report(
+    filename: impl AsRef<Path>,
+    word_scores: &[(Word, (f32, f32))],
+    word_distributions: &[(Word, Distribution)],
+    limits: &[usize],
+) {
+    let mut file = File::create(filename).expect("cannot open limit report");
+
+    let mut header = format!("{:w$}", "word,", w = word_width(word_scores));
+    for limit in limits {
+        header += &format!(
+            " {:>7}, {:>7},",
+            format!("avg_{}", limit),
+            format!("suc_{}", limit)
+        );
+    }
+    writeln!(file, "{} average", header).expect("cannot write header");
+
+    for (word, _) in word_scores {
+        if let Some((_, distribution)) = word_distributions.iter().find(|(w, _)| w == word) {
+            let mut line = format!("{},", word);
+            for limit in limits {
+                let (guess_count, success_rate) = distribution.score(*limit);
+                line += &format!(" {:7.3}, {:7.4},", guess_count, success_rate);
+            }
+            let (guess_count, _) = distribution.score(usize::MAX);
+            writeln!(file, "{} {:7.3}", line, guess_count).expect("cannot write line");
+        }
     }
 }
 
-#[derive(Debug, Parser)]
-#[clap(author, version, about, long_about = None)]
+#[derive(Clone, Debug, Parser)]
+#[clap(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    after_help = "Word lists that aren't found where they're given are looked for in the data directory (see `brutal paths`).\n\nExit codes: 0 success, 2 invalid input, 3 interrupted with partial results, 4 journal mismatch, 101 internal error."
+)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     #[clap(
         short,
         long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
         help = "Override the default answer list",
         default_value = "wordle_answer_list.txt"
     )]
-    answer_list: String,
+    answer_list: PathBuf,
 
     #[clap(
         short,
         long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
         help = "Override the default guess list [defaults to the answer list]"
     )]
-    guess_list: Option<String>,
+    guess_list: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Add any answers missing from the guess list to it, so every answer can be guessed"
+    )]
+    guesses_include_answers: bool,
+
+    #[clap(
+        long,
+        help = "Add plausible forms of the answers to the guess list, like plurals and -ed and -er forms, for games whose guess lists aren't known; they're marked yes in a generated column of the results"
+    )]
+    augment_guesses: bool,
 
     #[clap(
         short,
         long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
         help = "Override the default search list [defaults to the guess list]"
     )]
-    search_list: Option<String>,
+    search_list: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(try_from_str),
+        conflicts_with_all = &["answer-list", "guess-list"],
+        help = "Play `wordle`, with the answer and guess lists, `hardmode-nyt`, which is wordle in hard mode, or `primel`, where the answers and guesses are the five-digit primes",
+        default_value = "wordle"
+    )]
+    variant: Variant,
 
     #[clap(
         short,
@@ -206,165 +1013,2603 @@ struct Args {
     #[clap(
         short,
         long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
         help = "Override the default output path",
         default_value = "word_scores.csv"
     )]
-    output_file: String,
+    output_file: PathBuf,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::DirPath,
+        conflicts_with = "output-file",
+        help = "Split the results across files in this directory, with an index of them, rather than writing one file; anything that reads results reads the directory as all of them"
+    )]
+    output_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(try_from_str),
+        requires = "output-dir",
+        help = "Split the results by `letter`, a file for each first letter, or `average`, a file for each tenth of a guess",
+        default_value = "letter"
+    )]
+    split_by: chunks::Split,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Override the default journal path, where scores are logged as they're found and recovered from after a crash, and which must be from a run with the same answers and guesses [default: <OUTPUT_FILE>.journal, or journal in the --output-dir, started over if it isn't]"
+    )]
+    journal: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Don't reuse or keep scores in the cache directory, where any run with the same answers and guesses can reuse them"
+    )]
+    no_cache: bool,
+
+    #[clap(
+        long,
+        parse(try_from_str),
+        conflicts_with = "no-cache",
+        help = "Keep the cache in this store rather than the cache directory, like a directory shared between machines, as file:<DIR> or just <DIR>, a SQLite database, as sqlite:<FILE>, or an S3 bucket, as s3://<BUCKET>/<PREFIX> with the endpoint and credentials from AWS_ENDPOINT_URL, AWS_REGION, AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY"
+    )]
+    cache: Option<storage::Store>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        conflicts_with = "limit-report",
+        help = "Remember the scores of the endgames the search reaches, so none is searched twice, starting from the ones in this file if it's there and writing them all back to it at the end, for later runs with the same guesses, guess limit and policy; it's kept in the --cache store if one's given"
+    )]
+    memo: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        conflicts_with = "limit-report",
+        help = "Start from the endgames in this file as well, like one written by another shard of the run with --memo, remembering them as --memo does"
+    )]
+    memo_import: Vec<PathBuf>,
 
     #[clap(short, long, default_value = "1")]
     threads: usize,
-}
 
-fn main() {
-    let args = Args::parse();
+    #[clap(
+        long,
+        help = "Rewrite the output file after every <FLUSH_EVERY> scored words, instead of every second"
+    )]
+    flush_every: Option<usize>,
 
-    let answer_words = read_lines(&args.answer_list);
-    let mut guess_words = read_lines(&args.guess_list.unwrap_or(args.answer_list));
-    let mut search_words = if let Some(search_list) = &args.search_list {
-        read_lines(search_list)
-    } else {
-        guess_words.clone()
-    };
+    #[clap(
+        long,
+        parse(try_from_str = parse_duration),
+        help = "Rewrite the output file this often, like 500ms, 30s, 5m or 1h [default: 1s, unless --flush-every is given]"
+    )]
+    flush_interval: Option<Duration>,
 
-    if !args.word.is_empty() {
-        guess_words.extend_from_slice(&args.word);
-        if args.search_list.is_some() {
-            search_words.extend_from_slice(&args.word);
-        } else {
-            search_words = args.word;
-        }
-    }
+    #[clap(
+        long,
+        parse(try_from_str),
+        help = "How follow-up guesses are chosen: `random`, from the words still possible, `hybrid:<N>`, which takes the most informative of them while more than N remain, and searches far less, `greedy`, the same as hybrid:0, or `baseline`, from the answers still possible, all equally likely, whatever the guess list",
+        default_value = "random"
+    )]
+    policy: Policy,
 
-    let answer_words = Arc::new(answer_words);
-    let guess_words = Arc::new(guess_words);
+    #[clap(
+        long,
+        parse(try_from_str),
+        help = "How ties between equally informative guesses are broken under the hybrid policy: `list`, `alphabetical`, `answers-first`, `common` (which needs --frequency-list) or `fewer-repeats`",
+        default_value = "list"
+    )]
+    tie_break: TieBreak,
 
-    println!("Word counts:");
-    println!("  Possible answers:  {:5}", answer_words.len());
-    println!("  Available guesses: {:5}", guess_words.len());
-    println!("  Words to search:   {:5}", search_words.len());
-    println!();
+    #[clap(
+        long,
+        help = "Score with no guess limit, as in games that go on until the answer's found, so there's only an average and no success rate",
+        conflicts_with = "failure-report"
+    )]
+    unlimited: bool,
 
-    let search_queue = Arc::new(Mutex::new(
-        search_words.iter().rev().copied().collect::<Vec<_>>(),
-    ));
+    #[clap(
+        long,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new()
+            .range(1..=distribution::TRACKED_GUESSES as u64),
+        help = "Rank openers by their chance of solving within <WITHIN> guesses, rather than by their average",
+        conflicts_with = "unlimited"
+    )]
+    within: Option<usize>,
 
-    let progress_bars = MultiProgress::new();
-    let progress_bar_style =
-        ProgressStyle::default_bar().template("{elapsed_precise} {bar:50} {pos:>5}/{len:>5} {msg}");
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Rank openers by their chance of solving within the guesses your games usually take, given as lines of `guesses count`, with `x` for lost games",
+        conflicts_with_all = &["unlimited", "within"]
+    )]
+    player_stats: Option<PathBuf>,
 
-    let (completed, completed_receiver) = mpsc::channel();
-
-    // Clippy's a little high, I guess.
-    #[allow(clippy::needless_collect)]
-    let worker_threads = (0..args.threads)
-        .map(|_| {
-            let answer_words = answer_words.clone();
-            let guess_words = guess_words.clone();
-            let search_queue = search_queue.clone();
-
-            let progress = progress_bars.add(ProgressBar::new(answer_words.len() as u64));
-            progress.set_style(progress_bar_style.clone());
-            progress.enable_steady_tick(500);
-
-            let completed = completed.clone();
-
-            thread::spawn(move || {
-                // So we don't slap the shit out of the heap with our search.
-                let mut constraint_buffers = vec![Vec::with_capacity(WORD_LENGTH); GUESS_LIMIT];
-                let mut word_buffers = vec![Vec::with_capacity(guess_words.len()); GUESS_LIMIT];
-
-                while let Some(guess) = {
-                    let mut search_queue_guard = search_queue.lock().unwrap();
-                    search_queue_guard.pop()
-                } {
-                    progress.reset();
-
-                    let mut guesses_sum = 0.0;
-                    let mut success_sum = 0.0;
-
-                    for answer in answer_words.iter() {
-                        progress.set_message(format!(
-                            "{} -> {}",
-                            guess.to_string(),
-                            answer.to_string()
-                        ));
-
-                        let (guess_count, success_rate) = get_score(
-                            answer,
-                            &guess,
-                            &guess_words,
-                            1,
-                            &mut constraint_buffers,
-                            &mut word_buffers,
-                        );
-
-                        progress.inc(1);
-
-                        guesses_sum += guess_count * success_rate;
-                        success_sum += success_rate;
-                    }
+    #[clap(
+        long,
+        help = "Also write each opener's chance of solving in exactly two guesses, with the second guess played as well as it can be"
+    )]
+    two_guess_chance: bool,
 
-                    let guess_count = if success_sum > 0.0 {
-                        guesses_sum / success_sum
-                    } else {
-                        0.0
-                    };
+    #[clap(
+        long,
+        parse(try_from_str),
+        help = "Rank the results by `average`, `success` or `two-guess` [default: success with --within or --player-stats, otherwise average]"
+    )]
+    sort_by: Option<SortBy>,
 
-                    let success_rate = success_sum / answer_words.len() as f32;
+    #[clap(
+        long,
+        parse(try_from_str),
+        value_delimiter = ',',
+        help = "The results file's columns, each with its decimal places if they're not the usual, like word,avg:4,success:2,worst; from word, length, average (or avg), success, avg_pct, avg_z, suc_pct, suc_z, in_two and worst",
+        conflicts_with = "two-guess-chance"
+    )]
+    columns: Vec<columns::Column>,
 
-                    completed
-                        .send((guess, (guess_count, success_rate)))
-                        .expect("could not send update");
-                }
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Join the columns of this CSV onto the results by word, after brutal's own, for facts like part of speech or frequency to filter openers by; its first line names them, starting with word"
+    )]
+    metadata: Option<PathBuf>,
 
-                progress.finish_with_message("done");
-            })
-        })
-        .collect::<Vec<_>>();
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Score the search words as second guesses, after a first guess drawn from these openers, as lines of `word weight`"
+    )]
+    first_guesses: Option<PathBuf>,
 
-    mem::drop(completed);
+    #[clap(
+        long,
+        help = "Score for hard mode, where every guess after the opener keeps to the greens and yellows revealed so far, and say so in the results; the follow-up guesses already do, so the scores are the same, but an opener scored as a second guess may not be allowed",
+        conflicts_with = "first-guesses"
+    )]
+    hard_mode: bool,
 
-    let total_progress = progress_bars.add(ProgressBar::new(search_words.len() as u64));
-    total_progress.set_style(progress_bar_style);
-    total_progress.enable_steady_tick(500);
+    #[clap(
+        long,
+        parse(try_from_str = parse_fraction),
+        help = "Hold out this fraction of the answers, between 0 and 1, and evaluate the top openers against them"
+    )]
+    holdout: Option<f32>,
 
-    let progress_thread = thread::spawn(move || progress_bars.join().unwrap());
+    #[clap(
+        long,
+        help = "The number of top openers to evaluate in held-out and frequency comparisons",
+        default_value = "10"
+    )]
+    top_k: usize,
 
-    let collection_thread = thread::spawn(move || {
-        let mut word_scores = Vec::with_capacity(search_words.len());
-
-        while let Ok((word, (guess_count, success_rate))) = completed_receiver.recv() {
-            total_progress.inc(1);
-            total_progress.set_message(format!(
-                "{}, average: {:.3}, success: {:5.2}%",
-                word.to_string(),
-                guess_count,
-                success_rate * 100.0,
-            ));
-
-            word_scores.push((word, (guess_count, success_rate)));
-            word_scores.sort_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap());
-
-            let mut file = File::create(&args.output_file).expect("cannot open output file");
-
-            writeln!(file, "{:w$} average, success", "word,", w = WORD_LENGTH + 1)
-                .expect("cannot write header");
-
-            for (word, (guess_count, success_rate)) in word_scores.iter() {
-                writeln!(
-                    file,
-                    "{}, {:7.3}, {:7.4}",
-                    word.to_string(),
-                    guess_count,
-                    success_rate
-                )
-                .expect("cannot write line");
-            }
-        }
-        total_progress.finish_with_message("done");
-    });
+    #[clap(
+        long,
+        value_name = "COUNT",
+        help = "Search at most <COUNT> of the search words, starting with the ones whose feedback splits the answers most evenly and then always the one most like the best so far, and stopping sooner once the best stop changing",
+        conflicts_with = "stream"
+    )]
+    guided: Option<usize>,
 
-    worker_threads.into_iter().for_each(|t| t.join().unwrap());
-    collection_thread.join().unwrap();
+    #[clap(
+        long,
+        help = "Before searching, look for openers bound to score the same as an anagram of theirs, and only search the first of them, giving the rest its score",
+        conflicts_with_all = &["stream", "first-guesses", "sample", "bootstrap", "failure-report", "streak-risk", "limit-report", "outcome-file"]
+    )]
+    anagram_hints: bool,
+
+    #[clap(
+        long,
+        value_name = "COUNT",
+        help = "Score each opener against only <COUNT> of the answers, for quick approximate scores, taken from each group of answers its feedback splits them into in proportion to the group's size, and otherwise the same answers for every opener, so they compare fairly",
+        conflicts_with_all = &["race", "bootstrap", "failure-report", "streak-risk", "limit-report", "outcome-file"]
+    )]
+    sample: Option<usize>,
+
+    #[clap(
+        long,
+        value_name = "TOP",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "Only rank the <TOP> best openers by average, dropping any others partway through once enough answers show they can't make it, so they're left out of the results",
+        conflicts_with_all = &["limit-report", "outcome-file"]
+    )]
+    race: Option<usize>,
+
+    #[clap(
+        long,
+        help = "How many standard errors behind the top openers one has to be for --race to drop it",
+        default_value = "3"
+    )]
+    race_confidence: f32,
+
+    #[clap(
+        long,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "Resample the answer list <BOOTSTRAP> times to find confidence intervals on the scores"
+    )]
+    bootstrap: Option<usize>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Override the default bootstrap output path",
+        default_value = "word_bootstrap.csv"
+    )]
+    bootstrap_file: PathBuf,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Write a report of why each opener fails to find the answer in time"
+    )]
+    failure_report: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Write a report of each opener's risk of breaking a streak: its chance of failing, the share of answers it can fail on, and the family of words it's most likely to fail in",
+        conflicts_with = "unlimited"
+    )]
+    streak_risk: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Write each opener's average and success rate at each of --limits, all from one search without a guess limit",
+        conflicts_with_all = &["first-guesses", "failure-report", "streak-risk"]
+    )]
+    limit_report: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Write which guess each opener finds each answer on, all from one search without a guess limit, for working out other measures later",
+        conflicts_with_all = &["first-guesses", "failure-report", "streak-risk"]
+    )]
+    outcome_file: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(try_from_str = parse_limit),
+        value_delimiter = ',',
+        help = "The guess limits in the limit report",
+        default_value = "4,5,6"
+    )]
+    limits: Vec<usize>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Write how many positions each opener's search visited, and how many of them were leaves"
+    )]
+    stats: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::DirPath,
+        help = "Write the letter codes, answers and guess list the search works from to this directory before it starts, and each opener's partition of the answers and each worker's statistics once it's done"
+    )]
+    dump_state: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Reuse the scores in a results file from a run with the same answers and options, but this guess list instead, searching again only the openers the changes to the guess list could affect",
+        requires = "previous-results",
+        conflicts_with_all = &["first-guesses", "bootstrap", "failure-report", "streak-risk"]
+    )]
+    previous_guess_list: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "The results file to reuse scores from, with --previous-guess-list, if it was searched with the same answers, weights, guess limit and mode",
+        requires = "previous-guess-list"
+    )]
+    previous_results: Option<PathBuf>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Word frequencies or familiarity scores, as lines of `word score`, with unlisted words getting the lowest score"
+    )]
+    frequency_list: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Favor familiar follow-up guesses by weighting them by their frequency to this power [requires --frequency-list]",
+        requires = "frequency-list"
+    )]
+    obscurity_penalty: Option<f32>,
+
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Weight the answers by how likely they are to come up, as lines of `word weight`, with unlisted answers getting the lowest weight, so the scores are over the likelier answers; in hard mode too",
+        conflicts_with_all = &["race", "bootstrap"]
+    )]
+    answer_weights: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Drop guess and search words less frequent than this [requires --frequency-list]",
+        requires = "frequency-list"
+    )]
+    min_frequency: Option<f32>,
+
+    #[clap(
+        long,
+        help = "Report what --min-frequency costs the top openers, by rescoring them with the full guess list",
+        requires = "min-frequency"
+    )]
+    compare_frequency: bool,
+
+    #[clap(
+        long,
+        help = "Report how the top openers' averages degrade as the guess list narrows, from the full list to the answers alone, and to the common words with --min-frequency"
+    )]
+    guess_sensitivity: bool,
+
+    #[clap(
+        long,
+        help = "Report how much entropy the top openers leave in the answers after each of the first three guesses",
+        conflicts_with = "first-guesses"
+    )]
+    entropy_curve: bool,
+
+    #[clap(
+        long,
+        help = "Read the search list as it's searched, rather than loading it all up front",
+        requires = "search-list"
+    )]
+    stream: bool,
+
+    #[clap(
+        long,
+        help = "Seed anything random: the held-out answers, the bootstrap's resamples and the order --race plays the answers in and the answers --sample takes, each from its own stream, so they're the same however many threads there are",
+        default_value = "0"
+    )]
+    seed: u64,
+
+    /// The one length of word searched, when the answer list has several.
+    #[clap(skip)]
+    length: Option<usize>,
+}
+
+impl Args {
+    /// These arguments for searching just the words with `length` letters, writing everything to
+    /// files of its own, named for the length, like `word_scores.5.csv`.
+    fn for_length(&self, length: usize) -> Self {
+        let name = |path: &PathBuf| {
+            let mut name = path.file_stem().unwrap_or_default().to_os_string();
+            name.push(format!(".{}", length));
+            if let Some(extension) = path.extension() {
+                name.push(".");
+                name.push(extension);
+            }
+            path.with_file_name(name)
+        };
+        let name_each = |paths: &Option<PathBuf>| paths.as_ref().map(name);
+
+        Self {
+            output_file: name(&self.output_file),
+            output_dir: name_each(&self.output_dir),
+            journal: name_each(&self.journal),
+            memo: name_each(&self.memo),
+            memo_import: self.memo_import.iter().map(name).collect(),
+            bootstrap_file: name(&self.bootstrap_file),
+            failure_report: name_each(&self.failure_report),
+            streak_risk: name_each(&self.streak_risk),
+            limit_report: name_each(&self.limit_report),
+            outcome_file: name_each(&self.outcome_file),
+            stats: name_each(&self.stats),
+            dump_state: name_each(&self.dump_state),
+            previous_results: name_each(&self.previous_results),
+            word: self
+                .word
+                .iter()
+                .filter(|w| w.length() == length)
+                .copied()
+                .collect(),
+            length: Some(length),
+            ..self.clone()
+        }
+    }
+}
+
+/// Parses a guess limit the limit report can give scores at.
+fn parse_limit(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(limit @ 1..=distribution::TRACKED_GUESSES) => Ok(limit),
+        _ => Err(format!(
+            "invalid guess limit: {} (expected 1 to {})",
+            s,
+            distribution::TRACKED_GUESSES
+        )),
+    }
+}
+
+/// Parses a fraction strictly between 0 and 1, so there's something on both sides of it.
+fn parse_fraction(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(fraction) if fraction > 0.0 && fraction < 1.0 => Ok(fraction),
+        _ => Err(format!(
+            "invalid fraction: {} (expected more than 0 and less than 1)",
+            s
+        )),
+    }
+}
+
+fn parse_word_length(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(length @ 1..=MAX_WORD_LENGTH) => Ok(length),
+        _ => Err(format!(
+            "invalid word length: {} (expected 1 to {})",
+            s, MAX_WORD_LENGTH
+        )),
+    }
+}
+
+/// Parses a duration like `30s`, with a unit of `ms`, `s`, `m` or `h`, or seconds if there's none.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let split = s.find(|c: char| c.is_alphabetic()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value = value
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("invalid duration: {}", s))?;
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        _ => return Err(format!("unknown duration unit: {}", unit)),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("invalid duration: {}", s))
+}
+
+/// What the results are ranked by, best first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortBy {
+    /// The fewest guesses on average.
+    Average,
+    /// The highest success rate, then the fewest guesses.
+    Success,
+    /// The highest chance of solving in two guesses, then the fewest guesses.
+    TwoGuess,
+}
+
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "average" => Ok(Self::Average),
+            "success" => Ok(Self::Success),
+            "two-guess" => Ok(Self::TwoGuess),
+            _ => Err(format!("unknown ranking: {}", s)),
+        }
+    }
+}
+
+/// Which of several equally good guesses is preferred, by putting the guess list in order of
+/// preference.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TieBreak {
+    /// The order they're listed in.
+    List,
+    Alphabetical,
+    /// Words that could be the answer before those that can't.
+    AnswersFirst,
+    /// More frequent words first.
+    Common,
+    /// Words with more distinct letters first.
+    FewerRepeats,
+}
+
+impl FromStr for TieBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "list" => Ok(Self::List),
+            "alphabetical" => Ok(Self::Alphabetical),
+            "answers-first" => Ok(Self::AnswersFirst),
+            "common" => Ok(Self::Common),
+            "fewer-repeats" => Ok(Self::FewerRepeats),
+            _ => Err(format!("unknown tie-breaker: {}", s)),
+        }
+    }
+}
+
+impl TieBreak {
+    /// Sorts `words` into order of preference, keeping their listed order among equals.
+    fn order(self, words: &mut [Word], answers: &[Word], frequencies: Option<&WordFrequencies>) {
+        match self {
+            Self::List => (),
+            Self::Alphabetical => words.sort(),
+            Self::AnswersFirst => words.sort_by_key(|w| !answers.contains(w)),
+            Self::Common => {
+                let frequencies =
+                    frequencies.or_invalid_input(|| "--tie-break common requires --frequency-list");
+                words.sort_by(|a, b| frequencies.get(b).partial_cmp(&frequencies.get(a)).unwrap());
+            }
+            Self::FewerRepeats => words.sort_by_key(|w| {
+                let mut letters = *w;
+                letters.letters_mut().sort_unstable();
+                letters
+                    .letters()
+                    .windows(2)
+                    .filter(|l| l[0] == l[1])
+                    .count()
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum Command {
+    /// Install the standard word lists into the data directory
+    Init {
+        #[clap(
+            long,
+            help = "Replace lists even if they've been modified since they were installed"
+        )]
+        force: bool,
+    },
+    /// Show where configuration, caches and word lists are kept
+    Paths,
+    /// Find the best way to finish a game with only a few answers left, printing the tree of guesses to play after each feedback
+    Finish {
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The answers still possible, one per line"
+        )]
+        answers: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The words that can be guessed, as well as the answers",
+            default_value = "wordle_accepted_list.txt"
+        )]
+        guess_list: PathBuf,
+
+        #[clap(
+            short = 'l',
+            long,
+            parse(try_from_str = parse_limit),
+            help = "The guesses left to find the answer in, for labelling each guess safe, risky or informational only",
+            default_value_t = GUESS_LIMIT
+        )]
+        guesses_left: usize,
+
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Look up the positions in this tablebase, built with the same guesses, rather than searching them"
+        )]
+        tablebase: Option<PathBuf>,
+    },
+    /// Export how an opener splits the answers by feedback, as CSV, JSON or a treemap SVG
+    Partition {
+        #[clap(parse(try_from_str), help = "The opener to split the answers with")]
+        opener: Word,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(try_from_str),
+            help = "Write `csv`, with the answers for each feedback, `json`, or `svg`, a treemap with a tile for each feedback sized by its answers",
+            default_value = "csv"
+        )]
+        format: partition::Format,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Write to this file rather than to stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+    /// Report facts about the lists every writeup needs: their entropy, anagrams, the feedback patterns that happen, and how the best and worst openers split the answers, or record and report on your own games, like `brutal stats me`
+    Stats {
+        #[clap(subcommand)]
+        games: Option<games::Operation>,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The words that can be guessed, as well as the answers",
+            default_value = "wordle_accepted_list.txt"
+        )]
+        guess_list: PathBuf,
+
+        #[clap(
+            long,
+            help = "How many of the best and worst openers to show",
+            default_value_t = 5
+        )]
+        openers: usize,
+    },
+    /// Quiz yourself on positions from partway through games, grading each guess against the best, with a record kept of how you do
+    Train {
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The words that can be guessed, as well as the answers",
+            default_value = "wordle_accepted_list.txt"
+        )]
+        guess_list: PathBuf,
+
+        #[clap(
+            long,
+            help = "Stop after this many positions, rather than when you quit"
+        )]
+        rounds: Option<usize>,
+
+        #[clap(
+            long,
+            help = "Seed the positions, so the same seed sets the same ones [defaults to a different seed each time]"
+        )]
+        seed: Option<u64>,
+
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Keep the record in this file [defaults to training.csv in the data directory]"
+        )]
+        record: Option<PathBuf>,
+    },
+    /// Play along with a game: suggest each guess, and narrow down the answers from the feedback the game gave it
+    Solve {
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The words that can be guessed, as well as the answers",
+            default_value = "wordle_accepted_list.txt"
+        )]
+        guess_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(try_from_str),
+            help = "Suggest this first, rather than searching for it"
+        )]
+        opener: Option<Word>,
+
+        #[clap(
+            long,
+            help = "Search for each guess for at most this many milliseconds, then suggest the best found by then",
+            default_value_t = 1000
+        )]
+        budget_ms: u64,
+
+        #[clap(
+            long,
+            help = "Play by hard mode's rules, only suggesting guesses that keep to the greens and yellows revealed so far, and refusing guesses played that don't"
+        )]
+        hard_mode: bool,
+
+        #[clap(
+            long,
+            parse(try_from_str),
+            help = "How ties between equally good guesses are broken: `list`, `alphabetical`, `answers-first`, `common` (which needs --frequency-list) or `fewer-repeats`",
+            default_value = "answers-first"
+        )]
+        tie_break: TieBreak,
+
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Word frequencies or familiarity scores, as lines of `word score`, with unlisted words getting the lowest score"
+        )]
+        frequency_list: Option<PathBuf>,
+
+        #[clap(
+            long,
+            help = "Prefer the more familiar of equally good guesses, weighting them by their frequency to this power, and point out suggestions less familiar than any answer [requires --frequency-list]",
+            requires = "frequency-list"
+        )]
+        obscurity_penalty: Option<f32>,
+
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Carry on from a game saved with :save",
+            conflicts_with = "opener"
+        )]
+        load: Option<PathBuf>,
+
+        #[clap(
+            long,
+            help = "Show feedback without colors, marking each letter with * if it's green, ? if it's yellow and - if it's gray, like c-r-a?n-e-"
+        )]
+        symbols: bool,
+    },
+    /// Work out the best play for every endgame position of a few answers ahead of time, for the finish command to look up
+    Tablebase {
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The file to write the tablebase to"
+        )]
+        output: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "The words that can be guessed, as well as the answers",
+            default_value = "wordle_accepted_list.txt"
+        )]
+        guess_list: PathBuf,
+
+        #[clap(
+            long,
+            parse(try_from_str),
+            value_delimiter = ',',
+            help = "Start from the feedback for these openers, like crane,salet, rather than from nothing"
+        )]
+        openers: Vec<Word>,
+
+        #[clap(
+            short,
+            long,
+            help = "The most answers a position can have to be solved",
+            default_value_t = 5
+        )]
+        max_answers: usize,
+
+        #[clap(
+            short,
+            long,
+            help = "How many guesses past the start to follow positions with more answers than that, trying every guess",
+            default_value_t = 1
+        )]
+        depth: usize,
+    },
+    /// Compare a few openers under several policies, with a table each of their averages, success rates, most guesses and times
+    Shootout {
+        #[clap(
+            long,
+            parse(try_from_str),
+            value_delimiter = ',',
+            required = true,
+            help = "The openers to compare, like crane,salet,trace"
+        )]
+        openers: Vec<Word>,
+
+        #[clap(
+            long,
+            parse(try_from_str),
+            value_delimiter = ',',
+            help = "The policies to compare, like baseline,random,hybrid:20,hybrid:0, where hybrid:0 always plays the most informative guess and baseline any of the answers still possible",
+            default_value = "random"
+        )]
+        policies: Vec<Policy>,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default guess list [defaults to the answer list]"
+        )]
+        guess_list: Option<PathBuf>,
+
+        #[clap(
+            long,
+            help = "Add any answers missing from the guess list to it, so every answer can be guessed"
+        )]
+        guesses_include_answers: bool,
+
+        #[clap(short, long, default_value = "1")]
+        threads: usize,
+    },
+    /// Race two strategies on every answer, for how often each solves it in fewer guesses than the
+    /// other
+    Versus {
+        #[clap(
+            parse(try_from_str),
+            help = "The first strategy, an opener and the policy after it, like crane or salet/hybrid:0, which is random without one"
+        )]
+        first: Strategy,
+
+        #[clap(parse(try_from_str), help = "The second strategy")]
+        second: Strategy,
+
+        #[clap(
+            long,
+            parse(try_from_str),
+            help = "How guesses are counted against each other: simultaneous, where solving on the same guess is a draw, or alternating, where the first strategy guesses first and wins those",
+            default_value = "simultaneous"
+        )]
+        turns: Turns,
+
+        #[clap(
+            short = 'l',
+            long,
+            parse(try_from_str = parse_limit),
+            help = "The guesses each player gets, after which neither solving it is a draw",
+            default_value_t = GUESS_LIMIT
+        )]
+        guess_limit: usize,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default guess list [defaults to the answer list]"
+        )]
+        guess_list: Option<PathBuf>,
+
+        #[clap(
+            long,
+            help = "Add any answers missing from the guess list to it, so every answer can be guessed"
+        )]
+        guesses_include_answers: bool,
+    },
+    /// Print a completion script for a shell
+    Completions {
+        #[clap(arg_enum)]
+        shell: Shell,
+    },
+    /// Solve a Spelling Bee puzzle, listing its words, pangrams and maximum score
+    Bee {
+        #[clap(long, help = "The puzzle's letters, like abcdefg")]
+        letters: String,
+
+        #[clap(long, help = "The center letter, which every word uses")]
+        center: char,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "A dictionary of words of any length, one per line",
+            default_value = "wordle_accepted_list.txt"
+        )]
+        dictionary: PathBuf,
+    },
+    /// Build a word list for a custom game from a bigger dictionary
+    Curate {
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "A dictionary of words of any length, one per line"
+        )]
+        source: PathBuf,
+
+        #[clap(
+            long,
+            parse(try_from_str),
+            value_delimiter = ',',
+            help = "Leave out `plurals`, `proper-nouns` or `frequency>X` (which needs --frequency-list), separated by commas"
+        )]
+        filter: Vec<curate::Filter>,
+
+        #[clap(
+            long,
+            parse(try_from_str = parse_word_length),
+            help = "Keep the words with this many letters",
+            default_value = "5"
+        )]
+        length: usize,
+
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Word frequencies, as lines of `word score`"
+        )]
+        frequency_list: Option<PathBuf>,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default output path",
+            default_value = "curated_list.txt"
+        )]
+        output_file: PathBuf,
+
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Write each word that was left out, and why"
+        )]
+        report: Option<PathBuf>,
+    },
+    /// Combine or sample word lists, checking and normalizing every word, like `brutal lists subtract wordle_accepted_list.txt wordle_answer_list.txt`
+    Lists {
+        #[clap(subcommand)]
+        operation: lists::Operation,
+    },
+    /// Find the words in the lists nearest to one that might be mistyped
+    Lookup {
+        #[clap(help = "The word to look up, of any length")]
+        word: String,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Look in a guess list as well"
+        )]
+        guess_list: Option<PathBuf>,
+
+        #[clap(short, long, help = "How many words to show", default_value = "5")]
+        count: usize,
+    },
+    /// List the words that fit a game's feedback so far, or a crossword's known letters, like `brutal match _r_n_ --exclude se --contains a`
+    Match {
+        #[clap(flatten)]
+        query: query::Query,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Look in a guess list as well"
+        )]
+        guess_list: Option<PathBuf>,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Rank the words by their frequency in this list of `word score` lines, most common first"
+        )]
+        frequency_list: Option<PathBuf>,
+
+        #[clap(short, long, help = "Show at most this many words")]
+        count: Option<usize>,
+    },
+    /// Attribute the scores in a results file to the letters and positions of each opener
+    Letters {
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "A results file produced by a previous run"
+        )]
+        results: PathBuf,
+    },
+    /// Group openers in a results file whose first-guess partitions of the answers are nearly identical
+    Cluster {
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "A results file produced by a previous run"
+        )]
+        results: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Override the default answer list",
+            default_value = "wordle_answer_list.txt"
+        )]
+        answer_list: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            help = "The normalized mutual information two partitions must share to be clustered",
+            default_value = "0.8"
+        )]
+        threshold: f32,
+    },
+    /// Upgrade a results file written by an older version to the current schema, or merge an --output-dir into one file
+    Convert {
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "A results file produced by a previous run"
+        )]
+        results: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Where to write the converted file [default: stdout]"
+        )]
+        output: Option<PathBuf>,
+    },
+    /// Run a file of commands, one per line, against lists that are only loaded once
+    Script {
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Lines like `score crane slate`, `letters <RESULTS>` or `cluster <RESULTS>`, with `#` comments"
+        )]
+        script: PathBuf,
+
+        #[clap(flatten)]
+        lists: script::Lists,
+    },
+    /// Run the same commands as a script from a prompt, completing words and commands with tab
+    Repl {
+        #[clap(flatten)]
+        lists: script::Lists,
+    },
+    /// Keep the lists loaded, answering the same commands as a script for `brutal client`
+    #[cfg(unix)]
+    #[clap(after_long_help = serve::PROTOCOL)]
+    Serve {
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Listen here [default: brutal.sock in the runtime directory]"
+        )]
+        socket: Option<PathBuf>,
+
+        #[clap(flatten)]
+        lists: script::Lists,
+    },
+    /// Send a command to the server started by `brutal serve`, like `brutal client score crane`
+    #[cfg(unix)]
+    #[clap(trailing_var_arg = true)]
+    Client {
+        #[clap(
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Where the server's listening [default: brutal.sock in the runtime directory]"
+        )]
+        socket: Option<PathBuf>,
+
+        #[clap(required = true, multiple_values = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+}
+
+fn print_paths() {
+    let show =
+        |path: Option<PathBuf>| path.map_or_else(|| "none".into(), |p| p.display().to_string());
+
+    println!("config:   {}", show(paths::config_dir()));
+    println!("cache:    {}", show(paths::cache_dir()));
+    println!("data:     {}", show(paths::data_dir()));
+    println!("socket:   {}", show(paths::socket_file()));
+    println!("training: {}", show(paths::training_file()));
+    println!("games:    {}", show(paths::games_file()));
+}
+
+fn print_nearest(out: &mut impl Write, word: &str, words: &[Word], count: usize) {
+    writeln!(
+        out,
+        "{:w$} edits, shared",
+        "word,",
+        w = words.first().map_or(0, Word::length) + 1
+    )
+    .expect("cannot write line");
+    for nearest in lookup::nearest(word, words, count) {
+        let listed = nearest.to_string();
+        writeln!(
+            out,
+            "{}, {:5}, {:6}",
+            listed,
+            lookup::edit_distance(word, &listed),
+            lookup::shared_letters(word, &listed)
+        )
+        .expect("cannot write line");
+    }
+}
+
+/// Ranked by frequency, most common first, if there are `frequencies`, or else in the order
+/// they're listed.  With `simplify`, what the clues add up to comes first.
+fn print_matches(
+    out: &mut impl Write,
+    clues: &[clue::Clue],
+    words: &[Word],
+    frequencies: Option<&WordFrequencies>,
+    count: Option<usize>,
+    simplify: bool,
+) {
+    let length = words.first().map_or(0, Word::length);
+    if let Err(e) = clue::check_length(clues, length) {
+        exit::fail(exit::INVALID_INPUT, e);
+    }
+    if simplify {
+        let (bounds, _) = clue::Bounds::from_clues(clues, length);
+        bounds.write(out).expect("cannot write line");
+        writeln!(out).expect("cannot write line");
+    }
+    let count = count.unwrap_or(usize::MAX);
+    match frequencies {
+        Some(frequencies) => {
+            let mut matches = clue::enumerate_answers(words, clues)
+                .map(|w| (w, frequencies.get(&w)))
+                .collect::<Vec<_>>();
+            matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            for (word, frequency) in matches.into_iter().take(count) {
+                writeln!(out, "{}, {}", word, frequency).expect("cannot write line");
+            }
+        }
+        None => {
+            for word in clue::enumerate_answers(words, clues).take(count) {
+                writeln!(out, "{}", word).expect("cannot write line");
+            }
+        }
+    }
+}
+
+/// `value` rounded to `places` decimal places, without the sign if it rounds to zero, so it's
+/// never written as `-0.000`.
+fn unsigned_zero(value: f32, places: i32) -> f32 {
+    let scale = 10f32.powi(places);
+    // Adding zero turns a negative zero positive.
+    (value * scale).round() / scale + 0.0
+}
+
+fn print_letter_effects(out: &mut impl Write, results: &Path) {
+    let effects = analysis::letter_effects(&read_scores(results));
+
+    writeln!(out, "letter, position, openers,  average, success").expect("cannot write line");
+    for effect in effects {
+        let position = effect
+            .position
+            .map_or_else(|| "any".to_string(), |i| (i + 1).to_string());
+        writeln!(
+            out,
+            "{:>6}, {:>8}, {:7}, {:+8.3}, {:+7.4}",
+            effect.letter,
+            position,
+            effect.count,
+            unsigned_zero(effect.average_effect, 3),
+            unsigned_zero(effect.success_effect, 4)
+        )
+        .expect("cannot write line");
+    }
+}
+
+fn print_clusters(out: &mut impl Write, results: &Path, answers: &[Word], threshold: f32) {
+    let word_scores = read_scores(results);
+    check_length(
+        results.display(),
+        word_scores.iter().map(|(w, _)| w),
+        answers,
+    );
+    let clusters = analysis::cluster_openers(&word_scores, answers, threshold);
+
+    writeln!(
+        out,
+        "{:w$} average, success, members",
+        "word,",
+        w = answers.first().map_or(0, Word::length) + 1
+    )
+    .expect("cannot write line");
+    for cluster in clusters {
+        let (word, (guess_count, success_rate)) = word_scores[cluster[0]];
+        let members = cluster[1..]
+            .iter()
+            .map(|i| format!(" {}", word_scores[*i].0))
+            .collect::<String>();
+        // An opener on its own still has the members column, but left empty.
+        writeln!(
+            out,
+            "{}, {:7.3}, {:7.4},{}",
+            word, guess_count, success_rate, members
+        )
+        .expect("cannot write line");
+    }
+}
+
+/// Scores each of the already-scored words again, against different lists.
+fn rescore(word_scores: &[(Word, (f32, f32))], solver: &Solver) -> Vec<(f32, f32)> {
+    word_scores
+        .iter()
+        .map(|(w, _)| {
+            solver
+                .score(w)
+                .map_or_else(|| exit::exit(exit::INTERRUPTED), |r| r.score)
+        })
+        .collect()
+}
+
+/// Rescores the top openers with the full guess list, to show what restricting it to common words
+/// costs them.
+fn print_frequency_comparison(
+    word_scores: &[(Word, (f32, f32))],
+    unrestricted_solver: &Solver,
+    top_k: usize,
+) {
+    let top_words = &word_scores[..top_k.min(word_scores.len())];
+    let unrestricted_scores = rescore(top_words, unrestricted_solver);
+
+    println!();
+    println!(
+        "Cost of the frequency restriction for the top {} openers:",
+        top_words.len()
+    );
+    println!(
+        "  {:w$} average, success, unrestricted average, unrestricted success, average cost",
+        "word,",
+        w = unrestricted_solver.word_length() + 1
+    );
+    for ((word, (guess_count, success_rate)), (full_count, full_rate)) in
+        top_words.iter().zip(unrestricted_scores.iter())
+    {
+        println!(
+            "  {}, {:7.3}, {:7.4}, {:20.3}, {:20.4}, {:+12.3}",
+            word,
+            guess_count,
+            success_rate,
+            full_count,
+            full_rate,
+            guess_count - full_count
+        );
+    }
+}
+
+/// Rescores the top openers against the held-out answers, to show how much of their ranking is
+/// particular to the answers they were scored against.
+fn print_holdout_evaluation(
+    word_scores: &[(Word, (f32, f32))],
+    held_out_solver: &Solver,
+    top_k: usize,
+) {
+    let top_words = &word_scores[..top_k.min(word_scores.len())];
+    let held_out_scores = rescore(top_words, held_out_solver);
+
+    let mut held_out_ranks = (0..top_words.len()).collect::<Vec<_>>();
+    held_out_ranks.sort_by(|a, b| {
+        held_out_scores[*a]
+            .0
+            .partial_cmp(&held_out_scores[*b].0)
+            .unwrap()
+    });
+    let held_out_ranks = (0..top_words.len())
+        .map(|i| held_out_ranks.iter().position(|r| *r == i).unwrap())
+        .collect::<Vec<_>>();
+
+    println!();
+    println!(
+        "Held-out evaluation of the top {} openers:",
+        top_words.len()
+    );
+    println!(
+        "  {:w$} rank,  average, held-out rank, held-out average",
+        "word,",
+        w = held_out_solver.word_length() + 1
+    );
+    for (i, ((word, (guess_count, _)), (held_out_count, _))) in
+        top_words.iter().zip(held_out_scores.iter()).enumerate()
+    {
+        println!(
+            "  {}, {:4}, {:8.3}, {:13}, {:16.3}",
+            word,
+            i + 1,
+            guess_count,
+            held_out_ranks[i] + 1,
+            held_out_count
+        );
+    }
+
+    let count = top_words.len() as f32;
+    let squared_rank_differences = held_out_ranks
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (i as f32 - *r as f32).powi(2))
+        .sum::<f32>();
+    let mean_shift = top_words
+        .iter()
+        .zip(held_out_scores.iter())
+        .map(|((_, s), h)| h.0 - s.0)
+        .sum::<f32>()
+        / count;
+
+    if top_words.len() > 1 {
+        println!(
+            "  Rank correlation:     {:+.3}",
+            1.0 - 6.0 * squared_rank_differences / (count * (count * count - 1.0))
+        );
+    }
+    println!("  Mean average shift:   {:+.3}", mean_shift);
+}
+
+/// Shows how the work was shared out, so a worker that was left idle, or one that was given all
+/// the slow openers, stands out.
+fn print_worker_stats(worker_stats: &[WorkerStats]) {
+    println!();
+    println!("Workers:");
+    println!("  worker,  words,     busy,         nodes,        leaves, nodes/sec");
+    for (i, stats) in worker_stats.iter().enumerate() {
+        let busy = stats.busy.as_secs_f64();
+        let rate = if busy > 0.0 {
+            stats.search.nodes as f64 / busy
+        } else {
+            0.0
+        };
+        println!(
+            "  {:6}, {:6}, {:7.2}s, {:13}, {:13}, {:9.3e}",
+            i, stats.words, busy, stats.search.nodes, stats.search.leaves, rate
+        );
+    }
+}
+
+/// Shows how quickly each of the top openers narrows down the answers, rather than only how many
+/// guesses it takes in the end.
+fn print_entropy_curves(word_scores: &[(Word, (f32, f32))], solver: &Solver, top_k: usize) {
+    let top_words = &word_scores[..top_k.min(word_scores.len())];
+
+    println!();
+    println!(
+        "Expected bits of entropy left after each guess for the top {} openers (starting from {:.3}):",
+        top_words.len(),
+        (solver.answers().len() as f32).log2()
+    );
+    let header = (1..=analysis::CURVE_GUESSES)
+        .map(|i| format!("after {}", i))
+        .collect::<Vec<_>>();
+    println!(
+        "  {:w$} {}",
+        "word,",
+        header.join(", "),
+        w = solver.word_length() + 1
+    );
+    for (word, _) in top_words {
+        let curve = analysis::entropy_curve(word, solver.answers(), solver.guess_words())
+            .map(|e| format!("{:7.3}", e));
+        println!("  {}, {}", word, curve.join(", "));
+    }
+}
+
+/// Rescores the top openers with each of a series of narrower guess lists, to show how much each
+/// one's score depends on the follow-up guesses the narrower lists leave out.
+fn print_guess_sensitivity(
+    word_scores: &[(Word, (f32, f32))],
+    solvers: &[(&str, Solver)],
+    top_k: usize,
+) {
+    let top_words = &word_scores[..top_k.min(word_scores.len())];
+    let list_scores = solvers
+        .iter()
+        .map(|(_, solver)| rescore(top_words, solver))
+        .collect::<Vec<_>>();
+
+    println!();
+    println!(
+        "Guess list sensitivity of the top {} openers:",
+        top_words.len()
+    );
+    let mut header = Vec::new();
+    for (i, (name, _)) in solvers.iter().enumerate() {
+        header.push(format!("{:>8}", name));
+        if i > 0 {
+            header.push(format!("{:>8}", "cost"));
+        }
+    }
+    println!(
+        "  {:w$} {}",
+        "word,",
+        header.join(", "),
+        w = solvers.first().map_or(0, |(_, s)| s.word_length()) + 1
+    );
+
+    for (i, (word, _)) in top_words.iter().enumerate() {
+        let full = list_scores[0][i].0;
+        let mut row = Vec::new();
+        for (j, scores) in list_scores.iter().enumerate() {
+            row.push(format!("{:8.3}", scores[i].0));
+            if j > 0 {
+                row.push(format!("{:+8.3}", scores[i].0 - full));
+            }
+        }
+        println!("  {}, {}", word, row.join(", "));
+    }
+}
+
+fn main() {
+    let mut args = Args::parse();
+    args.hard_mode |= args.variant.hard_mode();
+
+    match &args.command {
+        Some(Command::Bee {
+            letters,
+            center,
+            dictionary,
+        }) => return bee::solve(dictionary, letters, *center),
+        Some(Command::Curate {
+            source,
+            filter,
+            length,
+            frequency_list,
+            output_file,
+            report,
+        }) => {
+            return curate::curate(
+                source,
+                filter,
+                *length,
+                frequency_list.as_ref().map(read_frequencies).as_ref(),
+                output_file,
+                report.as_deref(),
+            )
+        }
+        Some(Command::Lists { operation }) => return lists::run(operation),
+        Some(Command::Lookup {
+            word,
+            answer_list,
+            guess_list,
+            count,
+        }) => {
+            let mut words = read_lines(answer_list);
+            let guesses = guess_list.iter().flat_map(|l| read_guesses(l, &words));
+            words.extend(guesses.collect::<Vec<_>>());
+            words.sort();
+            words.dedup();
+            return print_nearest(&mut io::stdout(), word, &words, *count);
+        }
+        Some(Command::Match {
+            query,
+            answer_list,
+            guess_list,
+            frequency_list,
+            count,
+        }) => {
+            let mut words = read_lines(answer_list);
+            let guesses = guess_list.iter().flat_map(|l| read_guesses(l, &words));
+            words.extend(guesses.collect::<Vec<_>>());
+            words.sort();
+            words.dedup();
+            let frequencies = frequency_list.as_ref().map(read_frequencies);
+            return print_matches(
+                &mut io::stdout(),
+                &query.clues(),
+                &words,
+                frequencies.as_ref(),
+                *count,
+                query.simplify(),
+            );
+        }
+        Some(Command::Letters { results }) => {
+            return print_letter_effects(&mut io::stdout(), results)
+        }
+        Some(Command::Cluster {
+            results,
+            answer_list,
+            threshold,
+        }) => {
+            return print_clusters(
+                &mut io::stdout(),
+                results,
+                &read_lines(answer_list),
+                *threshold,
+            )
+        }
+        Some(Command::Convert { results, output }) => {
+            return convert::convert(results, output.as_deref())
+        }
+        Some(Command::Script { script, lists }) => {
+            return script::run_script(script, &lists.load())
+        }
+        Some(Command::Repl { lists }) => return repl::run(&lists.load()),
+        #[cfg(unix)]
+        Some(Command::Serve { socket, lists }) => {
+            return serve::serve(&serve::socket_or_default(socket.as_deref()), &lists.load())
+        }
+        #[cfg(unix)]
+        Some(Command::Client { socket, command }) => {
+            return serve::client(&serve::socket_or_default(socket.as_deref()), command)
+        }
+        Some(Command::Finish {
+            answers,
+            guess_list,
+            guesses_left,
+            tablebase,
+        }) => {
+            let answers = read_lines(answers);
+            let tablebase = tablebase.as_deref().map(|t| {
+                let read = Tablebase::read(t);
+                (t, read.or_invalid_input(|| "cannot read the tablebase"))
+            });
+            return finish::finish(
+                &mut io::stdout(),
+                &answers,
+                &read_guesses(guess_list, &answers),
+                *guesses_left,
+                tablebase.as_ref().map(|(_, t)| t),
+            )
+            .or_invalid_input(|| tablebase.unwrap().0.display());
+        }
+        Some(Command::Partition {
+            opener,
+            answer_list,
+            format,
+            output,
+        }) => {
+            let answers = read_lines(answer_list);
+            check_length("opener", [opener], &answers);
+            let mut out: Box<dyn Write> = match output {
+                Some(output) => Box::new(BufWriter::new(
+                    File::create(output).or_invalid_input(|| output.display().to_string()),
+                )),
+                None => Box::new(io::stdout()),
+            };
+            partition::write(&mut out, opener, &answers, *format)
+                .and_then(|_| out.flush())
+                .expect("cannot write line");
+            return;
+        }
+        Some(Command::Stats {
+            games: Some(operation),
+            ..
+        }) => return games::run(operation),
+        Some(Command::Stats {
+            games: None,
+            answer_list,
+            guess_list,
+            openers,
+        }) => {
+            let answers = read_lines(answer_list);
+            return stats::write(
+                &mut io::stdout(),
+                &answers,
+                &read_guesses(guess_list, &answers),
+                *openers,
+            )
+            .expect("cannot write line");
+        }
+        Some(Command::Train {
+            answer_list,
+            guess_list,
+            rounds,
+            seed,
+            record,
+        }) => {
+            let record = record
+                .clone()
+                .or_else(paths::training_file)
+                .or_invalid_input(|| "there's no data directory to keep the record in".to_string());
+            let answers = read_lines(answer_list);
+            return train::run(
+                &answers,
+                &read_guesses(guess_list, &answers),
+                *rounds,
+                &mut seed::stream(seed.unwrap_or_else(rand::random), "train", 0),
+                &record,
+            );
+        }
+        Some(Command::Solve {
+            answer_list,
+            guess_list,
+            opener,
+            budget_ms,
+            hard_mode,
+            tie_break,
+            frequency_list,
+            obscurity_penalty,
+            load,
+            symbols,
+        }) => {
+            let answers = read_lines(answer_list);
+            check_length("--opener", opener, &answers);
+            let mut guesses = read_guesses(guess_list, &answers);
+            let frequencies = frequency_list.as_ref().map(read_frequencies);
+            tie_break.order(&mut guesses, &answers, frequencies.as_ref());
+            let solver = SolverConfig::new(answers, guesses)
+                .guess_weights(
+                    obscurity_penalty
+                        .zip(frequencies.as_ref())
+                        .map(|(penalty, f)| f.powf(penalty)),
+                )
+                .hard_mode(*hard_mode)
+                .build();
+            return solve::run(
+                &solver,
+                *opener,
+                load.as_deref(),
+                Duration::from_millis(*budget_ms),
+                *symbols,
+            );
+        }
+        Some(Command::Tablebase {
+            output,
+            answer_list,
+            guess_list,
+            openers,
+            max_answers,
+            depth,
+        }) => {
+            let answers = read_lines(answer_list);
+            check_length("--openers", openers, &answers);
+            let tablebase = finish::build_tablebase(
+                &answers,
+                &read_guesses(guess_list, &answers),
+                openers,
+                *max_answers,
+                *depth,
+            )
+            .unwrap_or_else(|e| exit::fail(exit::INVALID_INPUT, e));
+            tablebase
+                .write(output)
+                .or_invalid_input(|| output.display());
+            println!(
+                "Solved {} positions of up to {} answers, in {}",
+                tablebase.len(),
+                max_answers,
+                output.display()
+            );
+            return;
+        }
+        Some(Command::Shootout {
+            openers,
+            policies,
+            answer_list,
+            guess_list,
+            guesses_include_answers,
+            threads,
+        }) => {
+            let mut openers = openers.clone();
+            let mut seen = HashSet::new();
+            openers.retain(|w| seen.insert(*w));
+            let answers = read_lines(answer_list);
+            let mut guesses = read_guesses(guess_list.as_ref().unwrap_or(answer_list), &answers);
+            check_list_roles(&answers, &mut guesses, *guesses_include_answers);
+            check_length("--openers", &openers, &answers);
+            return shootout::run(answers, guesses, &openers, policies, *threads);
+        }
+        Some(Command::Versus {
+            first,
+            second,
+            turns,
+            guess_limit,
+            answer_list,
+            guess_list,
+            guesses_include_answers,
+        }) => {
+            let answers = read_lines(answer_list);
+            let mut guesses = read_guesses(guess_list.as_ref().unwrap_or(answer_list), &answers);
+            check_list_roles(&answers, &mut guesses, *guesses_include_answers);
+            let openers = [&first.opener, &second.opener];
+            check_length("strategy", openers, &answers);
+            return versus::run(
+                &mut io::stdout(),
+                &answers,
+                &guesses,
+                [first, second],
+                *turns,
+                *guess_limit,
+            )
+            .expect("cannot write results");
+        }
+        Some(Command::Init { force }) => return setup::init(*force),
+        Some(Command::Paths) => return print_paths(),
+        Some(Command::Completions { shell }) => {
+            return clap_complete::generate(
+                *shell,
+                &mut Args::command(),
+                env!("CARGO_PKG_NAME"),
+                &mut io::stdout(),
+            )
+        }
+        None => (),
+    }
+
+    let mut lengths = match args.variant.words() {
+        Some(_) => BTreeSet::new(),
+        None => list_lengths(&args.answer_list),
+    };
+    if lengths.len() < 2 {
+        return search(args);
+    }
+
+    println!(
+        "{} has words of {} lengths, so each length is searched on its own.",
+        args.answer_list.display(),
+        lengths.len()
+    );
+    // Openers given with --word only find the answers as long as them.
+    if !args.word.is_empty() {
+        lengths.retain(|&l| args.word.iter().any(|w| w.length() == l));
+    }
+    for length in lengths {
+        let args = args.for_length(length);
+        println!();
+        println!(
+            "== {}-letter words, into {} ==",
+            length,
+            args.output_dir
+                .as_ref()
+                .unwrap_or(&args.output_file)
+                .display()
+        );
+        println!();
+        search(args);
+    }
+}
+
+/// The main search, scoring every opener.
+fn search(args: Args) {
+    let read_words = |list: &Path| stream_group(list, args.length).collect::<Vec<_>>();
+    let read_guesses = |list: &Path, answers: &[Word]| {
+        let guesses = read_words(list);
+        check_length(list.display(), &guesses, answers);
+        guesses
+    };
+
+    let variant_words = args.variant.words();
+    let mut answer_words = variant_words
+        .clone()
+        .unwrap_or_else(|| read_words(&args.answer_list));
+    if answer_words.is_empty() {
+        exit::fail(exit::INVALID_INPUT, "the answer list is empty");
+    }
+    let held_out_words = args.holdout.map(|fraction| {
+        answer_words.shuffle(&mut seed::stream(args.seed, "holdout", 0));
+        let held_out_count = (answer_words.len() as f32 * fraction).round() as usize;
+        if held_out_count == 0 || held_out_count == answer_words.len() {
+            exit::fail(
+                exit::INVALID_INPUT,
+                format!(
+                    "--holdout {} of {} answers leaves nothing to {}",
+                    fraction,
+                    answer_words.len(),
+                    match held_out_count {
+                        0 => "hold out",
+                        _ => "search with",
+                    }
+                ),
+            );
+        }
+        answer_words.split_off(answer_words.len() - held_out_count)
+    });
+
+    let all_answers = answer_words
+        .iter()
+        .chain(held_out_words.iter().flatten())
+        .copied()
+        .collect::<Vec<_>>();
+    let mut guess_words = variant_words.unwrap_or_else(|| {
+        read_guesses(
+            args.guess_list.as_ref().unwrap_or(&args.answer_list),
+            &all_answers,
+        )
+    });
+    check_list_roles(&all_answers, &mut guess_words, args.guesses_include_answers);
+    let generated_words = match args.augment_guesses {
+        true => {
+            let listed = guess_words.iter().copied().collect::<HashSet<_>>();
+            morphology::forms(&all_answers)
+                .into_iter()
+                .filter(|w| !listed.contains(w))
+                .collect()
+        }
+        false => Vec::new(),
+    };
+    guess_words.extend_from_slice(&generated_words);
+    let frequencies = args
+        .frequency_list
+        .as_ref()
+        .map(|f| Arc::new(read_frequencies(f)));
+    let is_common = {
+        let frequencies = frequencies.clone();
+        let min_frequency = args.min_frequency;
+        move |w: &Word| match (&frequencies, min_frequency) {
+            (Some(frequencies), Some(min_frequency)) => frequencies.get(w) >= min_frequency,
+            _ => true,
+        }
+    };
+
+    let streamed_search_list = args.search_list.clone().filter(|_| args.stream);
+    let mut search_words = match &args.search_list {
+        Some(_) if args.stream => Vec::new(),
+        Some(search_list) => read_guesses(search_list, &all_answers),
+        None => guess_words.clone(),
+    };
+
+    let mut unrestricted_guess_words =
+        (args.compare_frequency || args.guess_sensitivity).then(|| guess_words.clone());
+    guess_words.retain(&is_common);
+    search_words.retain(&is_common);
+
+    check_length("--word", &args.word, &all_answers);
+    // The previous guesses, as this run would have taken them.
+    let previous_guess_words = args.previous_guess_list.as_ref().map(|f| {
+        let mut words = read_guesses(f, &all_answers);
+        words.retain(&is_common);
+        words.extend_from_slice(&args.word);
+        words
+    });
+
+    if let Some(word) = args.word.iter().find(|w| !args.variant.allows(w)) {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!("{} can't be guessed in {}", word, args.variant),
+        );
+    }
+    if !args.word.is_empty() {
+        guess_words.extend_from_slice(&args.word);
+        if let Some(unrestricted_guess_words) = &mut unrestricted_guess_words {
+            unrestricted_guess_words.extend_from_slice(&args.word);
+        }
+        if args.search_list.is_some() {
+            search_words.extend_from_slice(&args.word);
+        } else {
+            search_words = args.word;
+        }
+    }
+
+    let mut metadata = args.metadata.as_deref().map(Metadata::read);
+    if let Some(metadata) = &metadata {
+        let missing = metadata.missing(&search_words);
+        if missing > 0 {
+            eprintln!(
+                "warning: {} of the openers aren't in {}, and their columns from it are left blank",
+                missing,
+                args.metadata.as_ref().unwrap().display()
+            );
+        }
+    }
+
+    if args.augment_guesses {
+        println!(
+            "Added {} generated guesses to the guess list, marked in the generated column.",
+            generated_words.len()
+        );
+        metadata
+            .get_or_insert_with(Metadata::default)
+            .flag(morphology::GENERATED, &generated_words);
+    }
+
+    let anagram_classes = args.anagram_hints.then(|| anagram::classes(&search_words));
+
+    // Streamed words are counted now, and read again as they're searched.
+    let search_count = search_words.len()
+        + streamed_search_list.as_ref().map_or(0, |l| {
+            stream_group(l, args.length)
+                .inspect(|w| check_length(l.display(), [w], &all_answers))
+                .filter(&is_common)
+                .count()
+        });
+    let search_count = args.guided.map_or(search_count, |g| g.min(search_count));
+    // A guided search picks its words as it goes, once it has the solver to pick them with.
+    let guided_words = args.guided.map(|_| mem::take(&mut search_words));
+    let guided_total = guided_words.as_ref().map_or(0, Vec::len);
+    let search_words: Box<dyn Iterator<Item = Word> + Send> = match streamed_search_list {
+        Some(search_list) => Box::new(
+            stream_group(search_list, args.length)
+                .filter(is_common)
+                .chain(search_words),
+        ),
+        None => Box::new(search_words.into_iter()),
+    };
+
+    let order_guesses = |words: &mut Vec<Word>| {
+        args.tie_break
+            .order(words, &answer_words, frequencies.as_deref())
+    };
+    order_guesses(&mut guess_words);
+    if let Some(unrestricted_guess_words) = &mut unrestricted_guess_words {
+        order_guesses(unrestricted_guess_words);
+    }
+
+    let guess_weights = args
+        .obscurity_penalty
+        .zip(frequencies.as_ref())
+        .map(|(penalty, f)| f.powf(penalty));
+    let answer_weights = args.answer_weights.as_ref().map(read_frequencies);
+    let first_guesses = args.first_guesses.as_ref().map(|f| {
+        let mut first_guesses = read_frequencies(f).listed();
+        if let Some(length) = args.length {
+            first_guesses.retain(|(w, _)| w.length() == length);
+        }
+        check_length(
+            f.display(),
+            first_guesses.iter().map(|(w, _)| w),
+            &all_answers,
+        );
+        first_guesses
+    });
+    let player_stats = args.player_stats.as_deref().map(PlayerStats::read);
+    let within = args
+        .within
+        .or_else(|| player_stats.as_ref().map(PlayerStats::median));
+    let guess_limit = within.unwrap_or(GUESS_LIMIT);
+    let sort_by = args.sort_by.unwrap_or(match within {
+        Some(_) => SortBy::Success,
+        None => SortBy::Average,
+    });
+    if (args.race.is_some() || args.guided.is_some()) && sort_by != SortBy::Average {
+        exit::fail(
+            exit::INVALID_INPUT,
+            "--race and --guided rank openers by their average, so the results have to be sorted by it",
+        );
+    }
+    if (args.limit_report.is_some() || args.outcome_file.is_some())
+        && !args.unlimited
+        && guess_limit > distribution::TRACKED_GUESSES
+    {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!(
+                "distributions only go up to {} guesses",
+                distribution::TRACKED_GUESSES
+            ),
+        );
+    }
+    // The word always comes first, whether or not it's asked for.
+    let columns = match args.columns.split_first() {
+        None => columns::default_columns(!args.unlimited, args.two_guess_chance),
+        Some((first, rest)) if first.field == Field::Word => rest.to_vec(),
+        Some(_) => args.columns.clone(),
+    };
+    // The results of each length are set apart by their length as well as their file.
+    let columns = match args.length {
+        Some(_) if !columns.iter().any(|c| c.field == Field::Length) => {
+            [Column::from(Field::Length)]
+                .into_iter()
+                .chain(columns)
+                .collect()
+        }
+        _ => columns,
+    };
+    if columns.iter().any(|c| c.field == Field::Word) {
+        exit::fail(exit::INVALID_INPUT, "the word can only be the first column");
+    }
+    let cancellation = CancellationToken::new();
+    // For rescoring the top openers against other lists, the same way they were scored.
+    let rescorer = |answers: Vec<Word>, guesses: Vec<Word>| {
+        SolverConfig::new(answers, guesses)
+            .guess_weights(guess_weights.clone())
+            .answer_weights(answer_weights.clone())
+            .first_guesses(first_guesses.clone())
+            .policy(args.policy)
+            .hard_mode(args.hard_mode)
+            .guess_limit(guess_limit)
+            .unlimited(args.unlimited)
+            .cancellation(cancellation.clone())
+            .build()
+    };
+    let unrestricted_solver = unrestricted_guess_words
+        .clone()
+        .filter(|_| args.compare_frequency)
+        .map(|w| rescorer(answer_words.clone(), w));
+    let held_out_solver = held_out_words.map(|w| rescorer(w, guess_words.clone()));
+    let sensitivity_solvers = unrestricted_guess_words
+        .filter(|_| args.guess_sensitivity)
+        .map(|w| {
+            let mut answers_only = answer_words.clone();
+            order_guesses(&mut answers_only);
+            let mut lists = vec![("full", w), ("answers", answers_only)];
+            if args.min_frequency.is_some() {
+                lists.push(("common", guess_words.clone()));
+            }
+            lists
+                .into_iter()
+                .map(|(name, w)| (name, rescorer(answer_words.clone(), w)))
+                .collect::<Vec<_>>()
+        });
+
+    let solver = SolverConfig::new(answer_words, guess_words)
+        .guess_weights(guess_weights)
+        .answer_weights(answer_weights)
+        .first_guesses(first_guesses)
+        .policy(args.policy)
+        .hard_mode(args.hard_mode)
+        .guess_limit(guess_limit)
+        .unlimited(args.unlimited)
+        .threads(args.threads)
+        .keep_answer_scores(args.bootstrap.is_some() || args.streak_risk.is_some())
+        .record_failures(args.failure_report.is_some() || args.streak_risk.is_some())
+        .keep_distributions(args.limit_report.is_some())
+        .keep_answer_distributions(args.outcome_file.is_some())
+        .race(args.race, args.race_confidence, args.seed)
+        .sample(args.sample, args.seed)
+        .memo(args.memo.is_some() || !args.memo_import.is_empty())
+        .cancellation(cancellation.clone())
+        .build();
+
+    let files = Files::default();
+    // The store given for the cache keeps the memo tables too, so shards can share them.
+    let store = args
+        .cache
+        .as_ref()
+        .map(|s| s.open().or_invalid_input(|| "--cache"));
+    let memo_storage = store.as_deref().unwrap_or(&files);
+    if let Some(memo) = solver.memo() {
+        if let Some(filename) = &args.memo {
+            read_memo(memo_storage, filename, false, memo, solver.guess_words());
+        }
+        for filename in &args.memo_import {
+            read_memo(memo_storage, filename, true, memo, solver.guess_words());
+        }
+    }
+
+    println!("Word counts:");
+    println!("  Possible answers:  {:5}", solver.answers().len());
+    if let Some(held_out_solver) = &held_out_solver {
+        println!("  Held-out answers:  {:5}", held_out_solver.answers().len());
+    }
+    if let Some(sample) = solver.sample() {
+        println!("  Sampled answers:   {:5}", sample.count());
+    }
+    println!("  Available guesses: {:5}", solver.guess_words().len());
+    if let Some(first_guesses) = solver.first_guesses() {
+        println!("  First guesses:     {:5}", first_guesses.len());
+    }
+    println!("  Words to search:   {:5}", search_count);
+    println!();
+    if solver.hard_mode() {
+        println!("Scoring for hard mode, where every guess keeps to the clues so far.");
+        println!();
+    }
+    if let Some(answer_weights) = &args.answer_weights {
+        println!(
+            "Weighting the answers by {}, so the likelier ones count for more.",
+            answer_weights.display()
+        );
+        println!();
+    }
+
+    let equivalents = anagram_classes
+        .map(|classes| anagram::equivalents(&classes, &solver))
+        .unwrap_or_default();
+    let unsearched = equivalents
+        .values()
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>();
+    if args.anagram_hints {
+        println!(
+            "Found {} openers that score the same as an anagram of theirs, so they won't be searched.",
+            unsearched.len()
+        );
+        println!();
+    }
+
+    if let Some(dump_state) = &args.dump_state {
+        dump::write_tables(dump_state, &solver);
+    }
+
+    if let Some(within) = within {
+        print!(
+            "Ranking openers by their chance of solving within {} guesses",
+            within
+        );
+        match &player_stats {
+            Some(stats) => println!(
+                ", as {:.1}% of your games do.",
+                100.0 * stats.within(within)
+            ),
+            None => println!("."),
+        }
+        println!();
+    }
+
+    let progress_bars = MultiProgress::new();
+    let progress_bar_style =
+        ProgressStyle::default_bar().template("{elapsed_precise} {bar:50} {pos:>5}/{len:>5} {msg}");
+
+    if let Some(dir) = &args.output_dir {
+        fs::create_dir_all(dir).or_invalid_input(|| dir.display().to_string());
+    }
+
+    // A journal that was asked for by name is expected to be resumed, rather than started over.
+    let journal_file = args
+        .journal
+        .clone()
+        .unwrap_or_else(|| match &args.output_dir {
+            Some(dir) => dir.join("journal"),
+            None => {
+                let mut journal = args.output_file.clone().into_os_string();
+                journal.push(".journal");
+                journal.into()
+            }
+        });
+    let fingerprint = journal::fingerprint(&solver);
+    let (journal, mut recovered) =
+        Journal::open(&files, &journal_file, fingerprint, args.journal.is_none()).unwrap_or_else(
+            || {
+                exit::fail(
+                    exit::JOURNAL_MISMATCH,
+                    format!(
+                        "{} was left by a run with different answers or guesses",
+                        journal_file.display()
+                    ),
+                )
+            },
+        );
+    let mut journals = vec![journal];
+
+    let journal_count = recovered.len();
+    let default_cache = store.is_none().then(journal::default_cache).flatten();
+    let cache = store
+        .as_deref()
+        .or(default_cache.as_ref().map(|f| f as &dyn Storage));
+    if let Some((cache, cached)) = cache
+        .filter(|_| !args.no_cache)
+        .and_then(|c| Journal::open(c, &journal::cache_key(fingerprint), fingerprint, true))
+    {
+        recovered.extend(cached);
+        journals.push(cache);
+    }
+    let journals = Arc::new(Mutex::new(journals));
+    let synced_journals = Arc::clone(&journals);
+    exit::on_exit(move || {
+        let mut journals = synced_journals
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        journals.iter_mut().for_each(Journal::sync);
+    });
+    let cache_count = recovered.len() - journal_count;
+
+    let mut reused = 0;
+    if let Some((previous_guess_words, previous_results)) =
+        previous_guess_words.zip(args.previous_results.as_ref())
+    {
+        let mut previous = previous_guess_words;
+        previous.sort();
+        previous.dedup();
+        let mut current = solver
+            .guess_words()
+            .indices()
+            .iter()
+            .map(|i| *solver.guess_words().word(*i))
+            .collect::<Vec<_>>();
+        current.sort();
+        current.dedup();
+        let changed = previous
+            .iter()
+            .filter(|w| current.binary_search(w).is_err())
+            .chain(
+                current
+                    .iter()
+                    .filter(|w| previous.binary_search(w).is_err()),
+            )
+            .copied()
+            .collect::<Vec<_>>();
+
+        let previous_policy = read_policy(previous_results);
+        let previous_fingerprint = read_fingerprint(previous_results);
+        let reusable = if previous_policy != solver.policy() {
+            eprintln!(
+                "warning: {} was searched with the {} policy, not {}, so none of its scores are reused",
+                previous_results.display(),
+                previous_policy,
+                solver.policy()
+            );
+            false
+        } else if previous_fingerprint.is_some_and(|f| f != incremental::fingerprint(&solver)) {
+            eprintln!(
+                "warning: {} was searched with different answers, weights, guess limit or mode, so none of its scores are reused",
+                previous_results.display()
+            );
+            false
+        } else {
+            if previous_fingerprint.is_none() {
+                eprintln!(
+                    "warning: {} doesn't say what else its scores were searched with, so they're reused as long as its answers are the same length",
+                    previous_results.display()
+                );
+            }
+            true
+        };
+        let previous_scores = read_scores(previous_results);
+        check_length(
+            previous_results.display(),
+            previous_scores.iter().map(|(w, _)| w),
+            solver.answers(),
+        );
+        for (word, score) in previous_scores.into_iter().filter(|_| reusable) {
+            if !recovered.contains_key(&word)
+                && incremental::unaffected(&word, solver.answers(), &changed)
+            {
+                recovered.insert(word, score);
+                reused += 1;
+            }
+        }
+        println!(
+            "Reused {} scores from {}, which the {} changed guesses can't affect.",
+            reused,
+            previous_results.display(),
+            changed.len()
+        );
+        println!();
+    }
+
+    // The journal only keeps the scores, so anything needing more than that is searched again.
+    let recovered = if args.bootstrap.is_none()
+        && args.failure_report.is_none()
+        && args.streak_risk.is_none()
+        && args.limit_report.is_none()
+        && args.outcome_file.is_none()
+    {
+        recovered
+    } else {
+        HashMap::new()
+    };
+    if recovered.len() > reused {
+        match cache_count {
+            0 => println!(
+                "Recovered {} scores from the journal.",
+                recovered.len() - reused
+            ),
+            _ => println!(
+                "Recovered {} scores from the journal and the cache, {} of them from the cache.",
+                recovered.len() - reused,
+                cache_count
+            ),
+        }
+        println!();
+    }
+
+    // Openers that are already finished are in the race from the start.
+    if let Some(race) = solver.race() {
+        recovered
+            .values()
+            .for_each(|(average, _)| race.enter(*average));
+    }
+
+    exit::handle_interrupts(cancellation);
+
+    // Flushing by count alone shouldn't also flush every second.
+    let flush_interval = args
+        .flush_interval
+        .or_else(|| args.flush_every.is_none().then_some(DEFAULT_FLUSH_INTERVAL));
+
+    let progress = SearchProgress {
+        worker_progress: (0..solver.threads())
+            .map(|_| {
+                let progress = progress_bars.add(ProgressBar::new(0));
+                progress.set_style(progress_bar_style.clone());
+                progress.enable_steady_tick(500);
+                progress
+            })
+            .collect(),
+        total_progress: progress_bars.add(ProgressBar::new(search_count as u64)),
+        results: Mutex::new(Results::default()),
+        writer: Mutex::new(ScoreWriter::new(
+            match &args.output_dir {
+                Some(dir) => Output::Chunks(dir.clone(), args.split_by),
+                None => Output::File(args.output_file.clone()),
+            },
+            columns,
+            sort_by,
+            &solver,
+            metadata,
+            args.flush_every,
+            flush_interval,
+        )),
+        journals: Arc::clone(&journals),
+        outcomes: Mutex::new(
+            args.outcome_file
+                .as_deref()
+                .map(|f| OutcomeFile::create(f, solver.answers())),
+        ),
+        equivalents,
+    };
+    progress.total_progress.set_style(progress_bar_style);
+    progress.total_progress.enable_steady_tick(500);
+
+    let progress_thread = thread::spawn(move || progress_bars.join().unwrap());
+
+    let mut guide = guided_words.map(|words| {
+        Guide::new(
+            words,
+            solver.answers(),
+            args.guided.unwrap(),
+            &progress.results,
+        )
+    });
+    let search_words: Box<dyn Iterator<Item = Word> + Send> = match &mut guide {
+        Some(guide) => Box::new(guide),
+        None => search_words,
+    };
+
+    // Words with recovered scores are finished without being searched again.
+    let search_words = search_words
+        .take_while(|_| !exit::interrupted())
+        .filter(|word| !unsearched.contains(word))
+        .filter(|word| match recovered.get(word) {
+            Some(score) => {
+                progress.finish(WordResult {
+                    word: *word,
+                    score: *score,
+                    answer_scores: None,
+                    failures: None,
+                    distribution: None,
+                    answer_distributions: None,
+                    search: None,
+                });
+                false
+            }
+            None => true,
+        });
+
+    let worker_stats = solver.score_all(search_words, &progress);
+    let guided = guide.map(|g| (g.given(), g.converged()));
+    progress
+        .worker_progress
+        .iter()
+        .for_each(|p| p.finish_with_message("done"));
+    progress.total_progress.finish_with_message("done");
     progress_thread.join().unwrap();
+
+    let SearchProgress {
+        results,
+        writer,
+        outcomes,
+        equivalents,
+        ..
+    } = progress;
+    journals.lock().unwrap().iter_mut().for_each(Journal::sync);
+    outcomes
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .for_each(OutcomeFile::flush);
+
+    let Results {
+        mut word_scores,
+        word_answer_scores,
+        word_failures,
+        word_distributions,
+        word_search,
+    } = mem::take(&mut *results.lock().unwrap());
+    writer.lock().unwrap().write(&mut word_scores);
+
+    if let Some(dump_state) = &args.dump_state {
+        dump::write_results(dump_state, &word_scores, solver.answers(), &worker_stats);
+    }
+
+    // Even if it was interrupted, since only the endgames that were finished are remembered.
+    if let Some((filename, memo)) = args.memo.as_ref().zip(solver.memo()) {
+        let mut table = Vec::new();
+        memo.write(&mut table, solver.guess_words())
+            .expect("cannot write line");
+        memo_storage
+            .write(filename, &table)
+            .or_invalid_input(|| memo_storage.locate(filename));
+    }
+
+    if exit::interrupted() {
+        println!();
+        println!("Interrupted, with {} words scored.", word_scores.len());
+        exit::exit(exit::INTERRUPTED);
+    }
+
+    print_worker_stats(&worker_stats);
+
+    if let Some(top) = args.race {
+        let dropped = worker_stats.iter().map(|s| s.dropped).sum::<usize>();
+        println!();
+        println!(
+            "Dropped {} openers partway through, once they couldn't make the top {}, so they're not in the results.",
+            dropped, top
+        );
+    }
+
+    if let Some((given, converged)) = guided {
+        println!();
+        match converged {
+            true => println!(
+                "The guided search converged after {} of the {} openers, once none of the last {} made the top {}.",
+                given,
+                guided_total,
+                guided::PATIENCE,
+                guided::LEADERS
+            ),
+            false => println!("The guided search searched {} of the {} openers.", given, guided_total),
+        }
+    }
+
+    if !equivalents.is_empty() {
+        let mut equivalents = equivalents.into_iter().collect::<Vec<_>>();
+        equivalents.sort();
+        println!();
+        println!("Scored the same as an anagram, without being searched:");
+        for (searched, same) in equivalents {
+            let same = same.iter().map(Word::to_string).collect::<Vec<_>>();
+            println!("  {}: {}", searched, same.join(" "));
+        }
+    }
+
+    if alloc::ENABLED {
+        let allocations = worker_stats.iter().map(|s| s.allocations).sum::<usize>();
+        println!();
+        println!("Heap allocations during the search: {}", allocations);
+    }
+
+    if let Some(stats_file) = &args.stats {
+        write_search_stats(stats_file, &word_scores, &word_search);
+    }
+
+    if let Some(failure_report) = &args.failure_report {
+        write_failure_report(
+            failure_report,
+            &word_scores,
+            &word_failures,
+            solver.answers().len(),
+        );
+    }
+
+    if let Some(streak_risk) = &args.streak_risk {
+        write_streak_risk(
+            streak_risk,
+            &word_scores,
+            &word_answer_scores,
+            &word_failures,
+            solver.answers().len(),
+        );
+    }
+
+    if let Some(limit_report) = &args.limit_report {
+        write_limit_report(
+            limit_report,
+            &word_scores,
+            &word_distributions,
+            &args.limits,
+        );
+    }
+
+    if let Some(resamples) = args.bootstrap {
+        let intervals =
+            analysis::bootstrap(&word_answer_scores, resamples, args.seed, solver.threads());
+        write_bootstrap_intervals(&args.bootstrap_file, &intervals);
+    }
+
+    if let Some(unrestricted_solver) = unrestricted_solver {
+        print_frequency_comparison(&word_scores, &unrestricted_solver, args.top_k);
+    }
+
+    if let Some(held_out_solver) = held_out_solver {
+        print_holdout_evaluation(&word_scores, &held_out_solver, args.top_k);
+    }
+
+    if let Some(sensitivity_solvers) = sensitivity_solvers {
+        print_guess_sensitivity(&word_scores, &sensitivity_solvers, args.top_k);
+    }
+
+    if args.entropy_curve {
+        print_entropy_curves(&word_scores, &solver, args.top_k);
+    }
 }
@@ -0,0 +1,178 @@
+//! Remembers the scores of the endgames the search reaches, so one that's reached again, by the same
+//! guesses in a different order or from another opener, isn't searched again.  An endgame's score
+//! only depends on the answer, the guesses still possible and how many have been made, so a table
+//! can be kept between runs, and shared between shards of one run, for as long as they search with
+//! the same guesses, weights, guess limit and policy, whatever their answers and openers.  Written
+//! out, it starts with a line identifying those, as `# brutal memo 0123456789abcdef`, and then has
+//! a line for each endgame, like `abide 3: abide aside azure: 3.6666667 1`, with the answer, the
+//! guesses it's reached after, the guesses still possible, and its average and success rate.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::RwLock;
+
+use crate::fingerprint::Fingerprint;
+use crate::solver::Policy;
+use crate::word_list::WordList;
+use crate::Word;
+
+const HEADER: &str = "# brutal memo";
+/// Endgames with fewer guesses possible are quicker to search than to look up...
+pub const MIN_WORDS: usize = 3;
+/// ...and ones with more are rarely reached twice.
+pub const MAX_WORDS: usize = 32;
+/// The most endgames remembered, so a long run's table can't take all the memory with it.
+const CAPACITY: usize = 1 << 21;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct Endgame {
+    answer: Word,
+    /// How many guesses it's reached after.
+    guess: usize,
+    /// Indices into the guess list, in order.
+    words: Vec<u32>,
+}
+
+pub struct Memo {
+    fingerprint: u64,
+    scores: RwLock<HashMap<Endgame, (f32, f32)>>,
+}
+
+impl Memo {
+    /// An empty table for searches with `list`, `guess_limit` and `policy`.
+    pub fn new(list: &WordList, guess_limit: usize, policy: Policy) -> Self {
+        let mut fingerprint = Fingerprint::new("memo");
+        fingerprint
+            .add(list.word_length())
+            .add(guess_limit)
+            .add(policy);
+        for i in list.indices() {
+            fingerprint.add(list.word(*i)).add_weight(list.weight(*i));
+        }
+        Self {
+            fingerprint: fingerprint.finish(),
+            scores: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, answer: &Word, guess: usize, words: &[u32]) -> Option<(f32, f32)> {
+        let endgame = Endgame {
+            answer: *answer,
+            guess,
+            words: words.to_vec(),
+        };
+        self.scores.read().unwrap().get(&endgame).copied()
+    }
+
+    pub(crate) fn insert(&self, answer: &Word, guess: usize, words: &[u32], score: (f32, f32)) {
+        let mut scores = self.scores.write().unwrap();
+        if scores.len() < CAPACITY {
+            let endgame = Endgame {
+                answer: *answer,
+                guess,
+                words: words.to_vec(),
+            };
+            scores.insert(endgame, score);
+        }
+    }
+
+    /// Adds the endgames in `contents`, a table written out by `write` with the same `list`, and
+    /// returns how many there were, or `None` if it was kept for a different search.  An error has
+    /// the line it's on.
+    pub fn read(&self, contents: &str, list: &WordList) -> Result<Option<usize>, (usize, String)> {
+        let mut lines = contents.lines().enumerate();
+        match lines.next() {
+            Some((_, h)) if h == format!("{} {:016x}", HEADER, self.fingerprint) => (),
+            Some((_, h)) if h.starts_with(HEADER) => return Ok(None),
+            _ => return Err((1, "not a memo table".to_string())),
+        }
+
+        let indices = list
+            .indices()
+            .iter()
+            .map(|i| (*list.word(*i), *i))
+            .collect::<HashMap<_, _>>();
+        let read = lines
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| parse_line(l, &indices).map_err(|e| (i + 1, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let count = read.len();
+        let mut scores = self.scores.write().unwrap();
+        let room = CAPACITY.saturating_sub(scores.len());
+        scores.extend(read.into_iter().take(room));
+        Ok(Some(count))
+    }
+
+    /// Writes the table out, in order, so the same table is always written the same way.
+    pub fn write(&self, out: &mut impl Write, list: &WordList) -> io::Result<()> {
+        writeln!(out, "{} {:016x}", HEADER, self.fingerprint)?;
+        let scores = self.scores.read().unwrap();
+        let mut endgames = scores.iter().collect::<Vec<_>>();
+        endgames.sort_unstable_by(|a, b| {
+            (a.0.answer, a.0.guess, &a.0.words).cmp(&(b.0.answer, b.0.guess, &b.0.words))
+        });
+        for (endgame, (average, success)) in endgames {
+            let words = endgame
+                .words
+                .iter()
+                .map(|i| list.word(*i).to_string())
+                .collect::<Vec<_>>();
+            writeln!(
+                out,
+                "{} {}: {}: {} {}",
+                endgame.answer,
+                endgame.guess,
+                words.join(" "),
+                average,
+                success
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a line of a written table, with `indices` the index of each word in the guess list.
+fn parse_line(line: &str, indices: &HashMap<Word, u32>) -> Result<(Endgame, (f32, f32)), String> {
+    let (endgame, words, score) = match line.split(':').map(str::trim).collect::<Vec<_>>()[..] {
+        [endgame, words, score] => (endgame, words, score),
+        _ => return Err("expected 3 columns".to_string()),
+    };
+    let (answer, guess) = endgame.split_once(' ').ok_or("missing guess")?;
+    let words = words
+        .split_whitespace()
+        .map(|w| {
+            let word = w.parse::<Word>().map_err(|e| format!("{}: {}", e, w))?;
+            indices
+                .get(&word)
+                .copied()
+                .ok_or_else(|| format!("{} isn't in the guess list", word))
+        })
+        .collect::<Result<_, String>>()?;
+    let (average, success) = score.split_once(' ').ok_or("missing success rate")?;
+
+    let endgame = Endgame {
+        answer: answer.parse().map_err(|e| format!("{}: {}", e, answer))?,
+        guess: guess
+            .parse()
+            .map_err(|_| format!("invalid guess number: {}", guess))?,
+        words,
+    };
+    let score = (
+        average
+            .parse()
+            .map_err(|_| format!("invalid average: {}", average))?,
+        success
+            .parse()
+            .map_err(|_| format!("invalid success rate: {}", success))?,
+    );
+    Ok((endgame, score))
+}
@@ -0,0 +1,142 @@
+//! Facts about words from outside brutal, like their part of speech or how common they are, joined
+//! onto the results by word so they can be filtered on right there.  The file's first line names
+//! its columns, starting with `word`, like `word, pos, frequency, plural`, and each line after it
+//! has a word and its values, separated by commas. Blank lines and `#` comments are skipped.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::columns;
+use crate::exit::{self, OrInvalidInput};
+use crate::Word;
+
+#[derive(Clone, Debug, Default)]
+pub struct Metadata {
+    names: Vec<String>,
+    values: HashMap<Word, Vec<String>>,
+}
+
+impl Metadata {
+    pub fn read(filename: &Path) -> Self {
+        let contents =
+            fs::read_to_string(filename).or_invalid_input(|| filename.display().to_string());
+        let location = |i: usize| format!("{}:{}", filename.display(), i + 1);
+        let split = |line: &str| line.split(',').map(|c| c.trim().to_string()).collect();
+
+        let mut lines = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+        let (i, header) = lines.next().or_invalid_input(|| {
+            format!(
+                "{}: there's no header naming the columns",
+                filename.display()
+            )
+        });
+        let mut names: Vec<String> = split(header);
+        if names[0] != "word" {
+            exit::fail(
+                exit::INVALID_INPUT,
+                format!("{}: the first column must be the word", location(i)),
+            );
+        }
+        names.remove(0);
+        let mut seen = HashSet::new();
+        for name in &names {
+            let problem = if name.is_empty() {
+                Some("a column has no name".to_string())
+            } else if columns::is_field(name) || name == "word" {
+                Some(format!("{} is already a column of the results", name))
+            } else if !seen.insert(name) {
+                Some(format!("there are two columns named {}", name))
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                exit::fail(exit::INVALID_INPUT, format!("{}: {}", location(i), problem));
+            }
+        }
+
+        let mut values = HashMap::new();
+        for (i, line) in lines {
+            let mut cells: Vec<String> = split(line);
+            if cells.len() != names.len() + 1 {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!(
+                        "{}: expected {} columns, as in the header",
+                        location(i),
+                        names.len() + 1
+                    ),
+                );
+            }
+            let word = cells
+                .remove(0)
+                .to_lowercase()
+                .parse::<Word>()
+                .or_invalid_input(|| location(i));
+            if values.insert(word, cells).is_some() {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!("{}: {} is listed twice", location(i), word),
+                );
+            }
+        }
+
+        Self { names, values }
+    }
+
+    /// Adds a column `name`, with `yes` for each of `words` and nothing for the rest.
+    pub fn flag(&mut self, name: &str, words: &[Word]) {
+        if self.names.iter().any(|n| n == name) {
+            exit::fail(
+                exit::INVALID_INPUT,
+                format!("the metadata already has a column named {}", name),
+            );
+        }
+        let column = self.names.len();
+        self.names.push(name.to_string());
+        for cells in self.values.values_mut() {
+            cells.push(String::new());
+        }
+        for word in words {
+            self.values
+                .entry(*word)
+                .or_insert_with(|| vec![String::new(); column + 1])[column] = "yes".to_string();
+        }
+    }
+
+    /// How many of `words` there's nothing on.
+    pub fn missing(&self, words: &[Word]) -> usize {
+        words
+            .iter()
+            .filter(|w| !self.values.contains_key(w))
+            .count()
+    }
+
+    pub fn header(&self) -> String {
+        header(&self.names)
+    }
+
+    /// `word`'s values, to go after its results, which are left blank if there's nothing on it.
+    pub fn cells(&self, word: &Word) -> String {
+        let values = self.values.get(word);
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let value = values.map_or("", |v| &v[i]);
+                format!(", {:>w$}", value, w = columns::width(n))
+            })
+            .collect()
+    }
+}
+
+/// The names of joined columns, to go after the results' own in the header.
+pub fn header(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| format!(", {:>w$}", n, w = columns::width(n)))
+        .collect()
+}
@@ -0,0 +1,54 @@
+//! Plausible guesses made from the answers' word forms, for games whose guess lists aren't known
+//! but whose answers are.  An answer that's a base with an ending, like `baked` or `aimer`, has its
+//! base's other forms made too: a four-letter base gets `-s`, and `-d` and `-r` if it ends in `e`,
+//! and a three-letter one gets `-ed` and `-er`.  They're only guesses at words, so they're kept
+//! apart from the real ones.
+
+use std::collections::BTreeSet;
+
+use crate::Word;
+
+/// The column the generated guesses are marked in.
+pub const GENERATED: &str = "generated";
+
+/// The endings a four-letter base can take, and whether it has to end in `e` to take them.
+const SHORT_ENDINGS: [(char, bool); 3] = [('s', false), ('d', true), ('r', true)];
+const LONG_ENDINGS: [[char; 2]; 2] = [['e', 'd'], ['e', 'r']];
+
+/// The forms of `answers`' bases that aren't already answers, in order.
+pub fn forms(answers: &[Word]) -> Vec<Word> {
+    let answers = answers
+        .iter()
+        .map(|w| w.to_string().chars().collect::<Vec<_>>())
+        .collect::<BTreeSet<_>>();
+
+    let mut forms = BTreeSet::new();
+    let mut add = |base: &[char], ending: &[char]| {
+        forms.insert(base.iter().chain(ending).copied().collect::<Vec<_>>());
+    };
+    for answer in &answers {
+        let (base, ending) = answer.split_at(answer.len() - 1);
+        let ends_in_e = base.last() == Some(&'e');
+        if SHORT_ENDINGS
+            .iter()
+            .any(|(e, needs_e)| ending == [*e] && (ends_in_e || !needs_e))
+        {
+            for (e, needs_e) in SHORT_ENDINGS {
+                if ends_in_e || !needs_e {
+                    add(base, &[e]);
+                }
+            }
+        }
+
+        let (base, ending) = answer.split_at(answer.len().saturating_sub(2));
+        if LONG_ENDINGS.iter().any(|e| ending == e) && base.last() != Some(&'e') {
+            for e in LONG_ENDINGS {
+                add(base, &e);
+            }
+        }
+    }
+    forms
+        .difference(&answers)
+        .map(|f| f.iter().collect::<String>().parse().unwrap())
+        .collect()
+}
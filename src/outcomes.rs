@@ -0,0 +1,37 @@
+//! An outcome file keeps each opener's distribution against every answer, for working out any
+//! other measure later.  It starts with a line of the answers, in order, as `answers: aback abase
+//! ...`, and then has a line for each opener as it's scored, like `crane: 2:0.25 0.75; 3:1; ...`,
+//! with an outcome for each answer, separated by `; `, each a distribution as it's shown.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::distribution::Distribution;
+use crate::exit::OrInvalidInput;
+use crate::Word;
+
+/// Writes each opener's distributions as it's scored.
+pub struct OutcomeFile(BufWriter<File>);
+
+impl OutcomeFile {
+    pub fn create(filename: &Path, answers: &[Word]) -> Self {
+        let file = File::create(filename).or_invalid_input(|| filename.display().to_string());
+        let mut file = BufWriter::new(file);
+        let answers = answers.iter().map(Word::to_string).collect::<Vec<_>>();
+        writeln!(file, "answers: {}", answers.join(" ")).expect("cannot write header");
+        Self(file)
+    }
+
+    pub fn record(&mut self, word: &Word, distributions: &[Distribution]) {
+        let outcomes = distributions
+            .iter()
+            .map(Distribution::to_string)
+            .collect::<Vec<_>>();
+        writeln!(self.0, "{}: {}", word, outcomes.join("; ")).expect("cannot write line");
+    }
+
+    pub fn flush(&mut self) {
+        self.0.flush().expect("cannot write line");
+    }
+}
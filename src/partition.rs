@@ -0,0 +1,284 @@
+//! Exports how an opener splits the answers by feedback, for looking at its buckets in other tools
+//! or showing them as a treemap.
+
+use std::cmp::Reverse;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::{clue, get_pattern, pattern_count, Word};
+
+const SVG_WIDTH: f64 = 800.0;
+const SVG_HEIGHT: f64 = 500.0;
+
+/// The colors of gray, yellow and green feedback.
+const COLORS: [(f64, f64, f64); 3] = [
+    (120.0, 124.0, 126.0),
+    (201.0, 180.0, 88.0),
+    (106.0, 170.0, 100.0),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// A line for each feedback, with its answers separated by spaces.
+    Csv,
+    Json,
+    /// A treemap with a tile for each feedback, sized by its answers.
+    Svg,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "svg" => Ok(Self::Svg),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Bucket {
+    /// Like `gy...`, with `g` for green, `y` for yellow and `.` for gray.
+    feedback: String,
+    answers: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Partition {
+    opener: String,
+    answers: usize,
+    buckets: Vec<Bucket>,
+}
+
+/// The answers for each feedback to `opener`, biggest first.
+fn partition(opener: &Word, answers: &[Word]) -> Vec<(usize, Vec<Word>)> {
+    let mut buckets = vec![Vec::new(); pattern_count(opener.length())];
+    for answer in answers {
+        buckets[get_pattern(answer, opener)].push(*answer);
+    }
+    let mut buckets = buckets
+        .into_iter()
+        .enumerate()
+        .filter(|(_, b)| !b.is_empty())
+        .collect::<Vec<_>>();
+    buckets.sort_by_key(|(pattern, b)| (Reverse(b.len()), *pattern));
+    buckets
+}
+
+/// Writes how `opener` partitions `answers` in `format`.
+pub fn write(
+    out: &mut impl Write,
+    opener: &Word,
+    answers: &[Word],
+    format: Format,
+) -> io::Result<()> {
+    let buckets = partition(opener, answers);
+    match format {
+        Format::Csv => {
+            writeln!(out, "feedback,   count, answers")?;
+            for (pattern, words) in &buckets {
+                let words = words.iter().map(Word::to_string).collect::<Vec<_>>();
+                writeln!(
+                    out,
+                    "{}, {:7}, {}",
+                    clue::feedback(*pattern, opener.length()),
+                    words.len(),
+                    words.join(" ")
+                )?;
+            }
+            Ok(())
+        }
+        Format::Json => {
+            let partition = Partition {
+                opener: opener.to_string(),
+                answers: answers.len(),
+                buckets: buckets
+                    .iter()
+                    .map(|(pattern, words)| Bucket {
+                        feedback: clue::feedback(*pattern, opener.length()),
+                        answers: words.iter().map(Word::to_string).collect(),
+                    })
+                    .collect(),
+            };
+            serde_json::to_writer_pretty(&mut *out, &partition)?;
+            writeln!(out)
+        }
+        Format::Svg => write_treemap(out, opener, answers.len(), &buckets),
+    }
+}
+
+/// A tile's place and size.
+#[derive(Clone, Copy, Debug)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Lays `areas`, which are biggest first and add up to `rect`'s area, out as tiles filling it,
+/// kept as close to square as they can be.  Tiles go in rows along the shorter side, each row
+/// taking tiles for as long as that makes its worst tile squarer.
+fn squarify(areas: &[f64], mut rect: Rect) -> Vec<Rect> {
+    // How far from square the worst of a row's tiles is, along a side of `side`.
+    let worst = |row: &[f64], side: f64| {
+        let sum = row.iter().sum::<f64>();
+        let (max, min) = (row[0], row[row.len() - 1]);
+        (side * side * max / (sum * sum)).max(sum * sum / (side * side * min))
+    };
+
+    let mut tiles = Vec::with_capacity(areas.len());
+    let mut start = 0;
+    while start < areas.len() {
+        let side = rect.width.min(rect.height);
+        let mut end = start + 1;
+        while end < areas.len()
+            && worst(&areas[start..=end], side) <= worst(&areas[start..end], side)
+        {
+            end += 1;
+        }
+
+        let row = &areas[start..end];
+        let sum = row.iter().sum::<f64>();
+        if rect.width >= rect.height {
+            // A column down the left.
+            let width = if rect.height > 0.0 {
+                sum / rect.height
+            } else {
+                0.0
+            };
+            let mut y = rect.y;
+            for area in row {
+                let height = if width > 0.0 { area / width } else { 0.0 };
+                tiles.push(Rect {
+                    x: rect.x,
+                    y,
+                    width,
+                    height,
+                });
+                y += height;
+            }
+            rect.x += width;
+            rect.width -= width;
+        } else {
+            // A row along the top.
+            let height = if rect.width > 0.0 {
+                sum / rect.width
+            } else {
+                0.0
+            };
+            let mut x = rect.x;
+            for area in row {
+                let width = if height > 0.0 { area / height } else { 0.0 };
+                tiles.push(Rect {
+                    x,
+                    y: rect.y,
+                    width,
+                    height,
+                });
+                x += width;
+            }
+            rect.y += height;
+            rect.height -= height;
+        }
+        start = end;
+    }
+    tiles
+}
+
+/// The color for `feedback`: its grays, yellows and greens mixed in proportion.
+fn color(feedback: &str) -> String {
+    let mut mix = (0.0, 0.0, 0.0);
+    for c in feedback.chars() {
+        let (r, g, b) = COLORS[match c {
+            'g' => 2,
+            'y' => 1,
+            _ => 0,
+        }];
+        mix = (mix.0 + r, mix.1 + g, mix.2 + b);
+    }
+    let n = feedback.len() as f64;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        (mix.0 / n).round() as u8,
+        (mix.1 / n).round() as u8,
+        (mix.2 / n).round() as u8
+    )
+}
+
+fn write_treemap(
+    out: &mut impl Write,
+    opener: &Word,
+    answer_count: usize,
+    buckets: &[(usize, Vec<Word>)],
+) -> io::Result<()> {
+    let scale = SVG_WIDTH * SVG_HEIGHT / answer_count.max(1) as f64;
+    let areas = buckets
+        .iter()
+        .map(|(_, words)| words.len() as f64 * scale)
+        .collect::<Vec<_>>();
+    let tiles = squarify(
+        &areas,
+        Rect {
+            x: 0.0,
+            y: 0.0,
+            width: SVG_WIDTH,
+            height: SVG_HEIGHT,
+        },
+    );
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="monospace">"#,
+        w = SVG_WIDTH,
+        h = SVG_HEIGHT
+    )?;
+    writeln!(
+        out,
+        "<title>{}: {} answers in {} buckets</title>",
+        opener,
+        answer_count,
+        buckets.len()
+    )?;
+    for ((pattern, words), tile) in buckets.iter().zip(tiles) {
+        let feedback = clue::feedback(*pattern, opener.length());
+        let listed = words.iter().map(Word::to_string).collect::<Vec<_>>();
+        writeln!(out, "<g>")?;
+        writeln!(
+            out,
+            "<title>{} ({}): {}</title>",
+            feedback,
+            words.len(),
+            listed.join(" ")
+        )?;
+        writeln!(
+            out,
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" stroke="white"/>"#,
+            tile.x,
+            tile.y,
+            tile.width,
+            tile.height,
+            color(&feedback)
+        )?;
+        // Labels only go on tiles big enough to hold them.
+        let size = (tile.height / 3.0).min(tile.width / 8.0).min(16.0);
+        if size >= 6.0 {
+            writeln!(
+                out,
+                r#"<text x="{:.2}" y="{:.2}" font-size="{:.1}" fill="white" text-anchor="middle">{} {}</text>"#,
+                tile.x + tile.width / 2.0,
+                tile.y + tile.height / 2.0 + size / 3.0,
+                size,
+                feedback,
+                words.len()
+            )?;
+        }
+        writeln!(out, "</g>")?;
+    }
+    writeln!(out, "</svg>")
+}
@@ -0,0 +1,60 @@
+//! Where brutal keeps things between runs, in each platform's usual place for them (the XDG
+//! directories, on Linux).
+
+use std::path::{Path, PathBuf};
+
+const NAME: &str = "brutal";
+const SOCKET: &str = "brutal.sock";
+const TRAINING: &str = "training.csv";
+const GAMES: &str = "games.db";
+const OLD_GAMES: &str = "games.csv";
+
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join(NAME))
+}
+
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join(NAME))
+}
+
+/// Where the server listens unless it's told otherwise: in the runtime directory, or the cache
+/// directory on platforms without one.
+pub fn socket_file() -> Option<PathBuf> {
+    dirs::runtime_dir()
+        .map(|d| d.join(NAME))
+        .or_else(cache_dir)
+        .map(|d| d.join(SOCKET))
+}
+
+/// Where word lists are kept, so they can be named from anywhere.
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join(NAME))
+}
+
+/// Finds a word list named on the command line: as given, if there's such a file, or else in the
+/// data directory.
+pub fn find_list(path: &Path) -> PathBuf {
+    if path.exists() || path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    data_dir()
+        .map(|d| d.join(path))
+        .filter(|p| p.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Where the trainer keeps the player's record.
+pub fn training_file() -> Option<PathBuf> {
+    data_dir().map(|d| d.join(TRAINING))
+}
+
+/// Where the player's own games are kept.
+pub fn games_file() -> Option<PathBuf> {
+    data_dir().map(|d| d.join(GAMES))
+}
+
+/// Where the player's games were kept before there was a database of them, for moving them in.
+pub fn old_games_file() -> Option<PathBuf> {
+    data_dir().map(|d| d.join(OLD_GAMES))
+}
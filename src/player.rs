@@ -0,0 +1,91 @@
+//! A player's own record, for aiming the search at what would improve their games in particular.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::exit::{self, OrInvalidInput};
+use crate::GUESS_LIMIT;
+
+/// How many games a player has solved in each number of guesses, read from lines of
+/// `guesses count`, with `x` for the guesses of the games they lost.
+#[derive(Clone, Debug)]
+pub struct PlayerStats {
+    /// The guesses each bunch of games took, or `None` for the ones that were lost.
+    games: Vec<(Option<usize>, usize)>,
+}
+
+impl PlayerStats {
+    pub fn read(filename: &Path) -> Self {
+        let file = File::open(filename).or_invalid_input(|| filename.display().to_string());
+        let buf = BufReader::new(file);
+
+        let games = buf
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i, l.or_invalid_input(|| filename.display().to_string())))
+            .filter(|(_, l)| !l.trim().is_empty() && !l.trim().starts_with('#'))
+            .map(|(i, l)| {
+                let line = || format!("{}:{}", filename.display(), i + 1);
+                let mut columns = l
+                    .split(|c: char| c == ',' || c == ':' || c.is_whitespace())
+                    .filter(|c| !c.is_empty());
+                let guesses = match columns.next().or_invalid_input(line) {
+                    "x" | "X" => None,
+                    g => Some(g.parse::<usize>().or_invalid_input(line)),
+                };
+                let count = columns
+                    .next()
+                    .or_invalid_input(|| format!("{}: missing count", line()))
+                    .parse::<usize>()
+                    .or_invalid_input(line);
+                (guesses, count)
+            })
+            .collect::<Vec<_>>();
+
+        if games.iter().all(|(_, c)| *c == 0) {
+            exit::fail(
+                exit::INVALID_INPUT,
+                format!("{} doesn't record any games", filename.display()),
+            );
+        }
+
+        Self { games }
+    }
+
+    /// The fewest guesses that at least half of the games were solved within, or the most any game
+    /// took if fewer than half were solved.
+    pub fn median(&self) -> usize {
+        let total = self.total();
+        let mut solved = self
+            .games
+            .iter()
+            .filter_map(|(g, c)| g.map(|g| (g, *c)))
+            .collect::<Vec<_>>();
+        solved.sort_unstable();
+
+        let mut count = 0;
+        for (guesses, c) in &solved {
+            count += c;
+            if 2 * count >= total {
+                return *guesses;
+            }
+        }
+        solved.last().map_or(GUESS_LIMIT, |(g, _)| *g)
+    }
+
+    /// The share of the games solved within `limit` guesses.
+    pub fn within(&self, limit: usize) -> f32 {
+        let solved = self
+            .games
+            .iter()
+            .filter(|(g, _)| g.is_some_and(|g| g <= limit))
+            .map(|(_, c)| c)
+            .sum::<usize>();
+        solved as f32 / self.total() as f32
+    }
+
+    fn total(&self) -> usize {
+        self.games.iter().map(|(_, c)| c).sum()
+    }
+}
@@ -0,0 +1,94 @@
+//! The clues given on the command line, for the commands that narrow the words down by them.
+
+use std::str::FromStr;
+
+use clap::Args;
+
+use crate::alphabet;
+use crate::clue::Clue;
+
+/// The clues given on the command line.
+#[derive(Clone, Debug, Args)]
+pub struct Query {
+    #[clap(
+        parse(try_from_str),
+        help = "Guesses and their feedback, like crane:g.y.. with g for green, y for yellow and . for gray, or the known letters in place, like _r_n_"
+    )]
+    clues: Vec<Clue>,
+
+    #[clap(
+        short,
+        long,
+        parse(try_from_str),
+        help = "Letters the words don't have, like se"
+    )]
+    exclude: Option<Letters>,
+
+    #[clap(
+        long,
+        parse(try_from_str),
+        help = "Letters the words have, like a, repeated for letters they have more than once"
+    )]
+    contains: Option<Letters>,
+
+    #[clap(
+        long,
+        parse(try_from_str),
+        help = "Only words made from these letters, each used as many times as it's listed, for anagrams"
+    )]
+    bank: Option<Letters>,
+
+    #[clap(
+        long,
+        help = "Let the bank's letters be used any number of times, as in Spelling Bee",
+        requires = "bank"
+    )]
+    reuse: bool,
+
+    #[clap(
+        long,
+        parse(try_from_str),
+        help = "A letter the words must use, like Spelling Bee's center letter",
+        requires = "bank"
+    )]
+    center: Option<Letters>,
+
+    #[clap(
+        long,
+        help = "Show what the clues add up to, merged letter by letter, before the words"
+    )]
+    simplify: bool,
+}
+
+impl Query {
+    pub fn clues(&self) -> Vec<Clue> {
+        let mut clues = self.clues.clone();
+        clues.extend(self.exclude.clone().map(|l| Clue::Excludes(l.0)));
+        clues.extend(self.contains.clone().map(|l| Clue::Contains(l.0)));
+        clues.extend(self.center.clone().map(|l| Clue::Contains(l.0)));
+        clues.extend(self.bank.clone().map(|l| Clue::Bank {
+            letters: l.0,
+            reuse: self.reuse,
+        }));
+        clues
+    }
+
+    /// Whether to show the clues merged into [`crate::clue::Bounds`].
+    pub fn simplify(&self) -> bool {
+        self.simplify
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Letters(Vec<u8>);
+
+impl FromStr for Letters {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| alphabet::encode(c).map_err(|e| format!("{}: {}", e, c)))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
@@ -0,0 +1,94 @@
+//! Races openers against each other for the top places, so that one with no chance of making them
+//! isn't scored against every answer.  Each opener plays the answers in the same random order, and
+//! after every batch of them, its average so far is compared with the openers already finished: if
+//! it's behind the last of the top ones by more than chance over the answers it hasn't played yet
+//! could make up, it's dropped.  The openers that finish are scored exactly, so only the ones that
+//! were never going to make the top are ever left out.
+
+use std::sync::Mutex;
+
+use rand::seq::SliceRandom;
+
+use crate::{combine_scores, seed};
+
+/// How many answers are played between looks at how an opener's doing.
+pub const BATCH: usize = 25;
+/// An opener's never dropped on fewer answers than this, since its spread isn't to be trusted yet.
+const MIN_SAMPLE: usize = 50;
+
+pub struct Race {
+    /// How many of the best openers are raced for.
+    top: usize,
+    /// How many standard errors behind an opener has to be to be dropped.
+    confidence: f32,
+    /// The order the answers are played in, as indices into them.
+    order: Vec<usize>,
+    /// The averages of the best openers finished so far, best first, up to `top` of them.
+    leaders: Mutex<Vec<f32>>,
+}
+
+impl Race {
+    /// A race for the `top` best openers, dropping any more than `confidence` standard errors
+    /// behind them, over `answer_count` answers played in an order from `seed`.
+    pub fn new(top: usize, confidence: f32, seed: u64, answer_count: usize) -> Self {
+        let mut order = (0..answer_count).collect::<Vec<_>>();
+        order.shuffle(&mut seed::stream(seed, "race", 0));
+        Self {
+            top: top.max(1),
+            confidence,
+            order,
+            leaders: Mutex::new(Vec::with_capacity(top)),
+        }
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Counts an opener that finished with `average` guesses against the ones still racing.
+    pub fn enter(&self, average: f32) {
+        let mut leaders = self.leaders.lock().unwrap();
+        let place = leaders.partition_point(|l| *l <= average);
+        if place < self.top {
+            leaders.insert(place, average);
+            leaders.truncate(self.top);
+        }
+    }
+
+    /// The average an opener has to beat to make the top, once there are enough finished to say.
+    fn cutoff(&self) -> Option<f32> {
+        let leaders = self.leaders.lock().unwrap();
+        (leaders.len() == self.top).then(|| leaders[self.top - 1])
+    }
+
+    /// Whether an opener with `scores` against the first of the answers, in order, can be dropped.
+    pub fn is_out(&self, scores: &[(f32, f32)]) -> bool {
+        let (played, total) = (scores.len(), self.order.len());
+        if played < MIN_SAMPLE || played >= total {
+            return false;
+        }
+        let cutoff = match self.cutoff() {
+            Some(cutoff) => cutoff,
+            None => return false,
+        };
+
+        let (average, _) = combine_scores(scores, None);
+        let (squares, found) =
+            scores
+                .iter()
+                .fold((0.0, 0.0), |(squares, found), (guesses, success)| {
+                    (
+                        squares + success * (guesses - average).powi(2),
+                        found + success,
+                    )
+                });
+        if found == 0.0 {
+            return false;
+        }
+        // The answers are drawn without replacement, so the error shrinks to nothing as the last
+        // of them are played.
+        let left = (total - played) as f32 / (total - 1) as f32;
+        let error = (squares / found / played as f32 * left).sqrt();
+        average - self.confidence * error > cutoff
+    }
+}
@@ -0,0 +1,82 @@
+//! Runs the script commands from a prompt, with the lists kept loaded between them.
+
+use std::io;
+
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{Context, Editor, Helper, Highlighter, Hinter, Validator};
+
+use crate::script::Session;
+
+const PROMPT: &str = "brutal> ";
+const QUIT: &[&str] = &["quit", "exit"];
+
+/// Completes the command at the start of a line, words after `score`, and files after anything
+/// else.
+#[derive(Helper, Highlighter, Hinter, Validator)]
+struct Completion {
+    commands: Vec<String>,
+    words: Vec<String>,
+    files: FilenameCompleter,
+}
+
+impl Completer for Completion {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let candidates = match line[..start].split_whitespace().next() {
+            None => &self.commands,
+            Some("score") => &self.words,
+            Some(_) => return self.files.complete(line, pos, ctx),
+        };
+
+        let prefix = &line[start..pos];
+        let matches = candidates
+            .iter()
+            .filter(|c| c.starts_with(prefix))
+            .map(|c| Pair {
+                display: c.clone(),
+                replacement: c.clone(),
+            })
+            .collect();
+        Ok((start, matches))
+    }
+}
+
+/// Reads commands until the input ends or it's told to quit.  Unlike a script, a command that
+/// doesn't parse is only reported.
+pub fn run(session: &Session) {
+    let mut commands = Session::commands();
+    commands.extend(QUIT.iter().map(|q| q.to_string()));
+
+    let mut editor = Editor::<Completion, DefaultHistory>::new().expect("cannot start prompt");
+    editor.set_helper(Some(Completion {
+        commands,
+        words: session.words().iter().map(|w| w.to_string()).collect(),
+        files: FilenameCompleter::new(),
+    }));
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => panic!("cannot read command: {}", e),
+        };
+        if QUIT.contains(&line.trim()) {
+            break;
+        }
+
+        let _ = editor.add_history_entry(line.as_str());
+        if let Err(e) = session.run(&line, &mut io::stdout(), &mut io::stderr()) {
+            let _ = e.print();
+        }
+    }
+}
@@ -0,0 +1,644 @@
+//! A store in an S3 bucket, or anything else that speaks S3 like MinIO, so that a run spread over
+//! machines that come and go, like spot instances, can share a cache without sharing a disk.  It's
+//! named like `s3://<BUCKET>/<PREFIX>`, with every key kept under the prefix, and the endpoint,
+//! region and credentials come from the usual `AWS_ENDPOINT_URL`, `AWS_REGION`,
+//! `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`.  Requests go over HTTPS,
+//! unless the endpoint's given as an `http://` one, like a MinIO on the same network, and a
+//! session token is never sent over plain HTTP.
+//!
+//! An object can't be added to, so each log opened on a key is a series of objects next to it,
+//! like `scores/0123456789abcdef.log-<ID>-<PART>`, one for what was written before each sync.
+//! Reading the key reads the key's own object and then every part, in the order they were
+//! written, so machines can add to the same key at once without losing each other's lines.  No
+//! object but the key's own is ever replaced: compacting a key writes its parts out together as a
+//! log of its own, and only then removes them.
+
+use std::collections::HashSet;
+use std::env;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+use ureq::http::Request;
+use ureq::Agent;
+
+use crate::games::Date;
+use crate::storage::{Log, Storage};
+
+/// How long a request can take before it's given up on.
+const TIMEOUT: Duration = Duration::from_secs(60);
+/// How many times a request that didn't get through, or that S3 was too busy for, is tried again,
+/// waiting twice as long each time.
+const RETRIES: u32 = 4;
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+/// What goes between a key's object and the ID of each log opened on it.
+const LOG_SEPARATOR: &str = ".log-";
+
+#[derive(Clone)]
+pub struct Bucket {
+    agent: Agent,
+    /// `http` or `https`.
+    scheme: &'static str,
+    /// The endpoint's host, and its port if it has one.
+    host: String,
+    bucket: String,
+    /// Goes before every key, ending in `/` unless it's empty.
+    prefix: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+    /// The logs opened here, which are the only ones ever removed when a key's written, since
+    /// another machine's might still be being added to.
+    own: Arc<Mutex<HashSet<String>>>,
+}
+
+/// A response's status code and body.
+struct Response {
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl Response {
+    /// The body, unless the request failed, in which case the error has the code S3 gave.
+    fn check(self) -> io::Result<Vec<u8>> {
+        if (200..300).contains(&self.status) {
+            return Ok(self.body);
+        }
+        let body = String::from_utf8_lossy(&self.body);
+        let message = match between(&body, "<Code>", "</Code>").next() {
+            Some(code) => format!("status {}, {}", self.status, code),
+            None => format!("status {}", self.status),
+        };
+        Err(io::Error::other(message))
+    }
+
+    /// Whether the request might get through if it's tried again.
+    fn is_transient(&self) -> bool {
+        self.status == 429 || self.status >= 500
+    }
+}
+
+impl Bucket {
+    /// The bucket `bucket`, keeping keys under `prefix`, with everything else from the environment.
+    pub fn from_env(bucket: &str, prefix: &str) -> Result<Self, String> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+        let region = var("AWS_REGION")
+            .or_else(|| var("AWS_DEFAULT_REGION"))
+            .unwrap_or_else(|| "us-east-1".to_string());
+        let endpoint = var("AWS_ENDPOINT_URL")
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+        let (scheme, host) = match endpoint.split_once("://") {
+            Some((_, host)) if host.trim_end_matches('/').contains('/') => {
+                return Err(format!("{}: an endpoint can't have a path", endpoint))
+            }
+            Some(("https", host)) => ("https", host.trim_end_matches('/')),
+            Some(("http", host)) => ("http", host.trim_end_matches('/')),
+            _ => return Err(format!("{}: not an https:// or http:// endpoint", endpoint)),
+        };
+        let session_token = var("AWS_SESSION_TOKEN");
+        if scheme == "http" && session_token.is_some() {
+            return Err(format!(
+                "{}: AWS_SESSION_TOKEN is set, and it's only ever sent over https://",
+                endpoint
+            ));
+        }
+        let credential = |name: &str| var(name).ok_or_else(|| format!("{} isn't set", name));
+
+        Ok(Self {
+            agent: Agent::config_builder()
+                .timeout_global(Some(TIMEOUT))
+                .http_status_as_error(false)
+                .build()
+                .into(),
+            scheme,
+            host: host.to_string(),
+            bucket: bucket.to_string(),
+            prefix: match prefix.trim_matches('/') {
+                "" => String::new(),
+                prefix => format!("{}/", prefix),
+            },
+            region,
+            access_key: credential("AWS_ACCESS_KEY_ID")?,
+            secret_key: credential("AWS_SECRET_ACCESS_KEY")?,
+            session_token,
+            own: Arc::default(),
+        })
+    }
+
+    /// The object `key` is kept in.
+    fn object(&self, key: &Path) -> String {
+        format!("{}{}", self.prefix, key.display())
+    }
+
+    /// Sends a request for `object`, or for the bucket itself if it's empty, signed the way S3
+    /// asks, with its version 4 signatures.  It's tried again while it doesn't get through.
+    fn request(
+        &self,
+        method: &str,
+        object: &str,
+        query: &[(&str, &str)],
+        body: &[u8],
+    ) -> io::Result<Response> {
+        let path = match object {
+            "" => format!("/{}", encode(&self.bucket, false)),
+            object => format!("/{}/{}", encode(&self.bucket, false), encode(object, true)),
+        };
+        let mut query = query
+            .iter()
+            .map(|(name, value)| (encode(name, false), encode(value, false)))
+            .collect::<Vec<_>>();
+        query.sort();
+        let query = query
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+        let (year, month, day) = Date::from_ymd(1970, 1, 1).plus((now / 86400) as i64).ymd();
+        let time = format!(
+            "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+            year,
+            month,
+            day,
+            now / 3600 % 24,
+            now / 60 % 60,
+            now % 60
+        );
+
+        let payload = sha256(body);
+        // In order by name, as they're signed.
+        let mut headers = vec![
+            ("host", self.host.clone()),
+            ("x-amz-content-sha256", payload.clone()),
+            ("x-amz-date", time.clone()),
+        ];
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}/{}/s3/aws4_request, SignedHeaders={}, Signature={}",
+            self.access_key,
+            &time[..8],
+            self.region,
+            signed_headers(&headers),
+            signature(
+                &self.secret_key,
+                &self.region,
+                &time,
+                [method, &path, &query],
+                &headers,
+                &payload
+            )
+        );
+
+        let uri = match query.is_empty() {
+            true => format!("{}://{}{}", self.scheme, self.host, path),
+            false => format!("{}://{}{}?{}", self.scheme, self.host, path, query),
+        };
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 0;
+        loop {
+            let mut request = Request::builder()
+                .method(method)
+                .uri(&uri)
+                .header("authorization", &authorization);
+            for (name, value) in &headers {
+                request = request.header(*name, value);
+            }
+            if method == "PUT" {
+                request = request.header("content-length", body.len());
+            }
+            let request = request.body(body).map_err(io::Error::other)?;
+
+            let response = self.agent.run(request).and_then(|mut response| {
+                Ok(Response {
+                    status: response.status().as_u16(),
+                    body: response.body_mut().with_config().read_to_vec()?,
+                })
+            });
+            match response {
+                Ok(response) if response.is_transient() && attempt < RETRIES => (),
+                Ok(response) => return Ok(response),
+                Err(_) if attempt < RETRIES => (),
+                Err(e) => return Err(e.into_io()),
+            }
+            thread::sleep(delay);
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+
+    /// What's in `object`, or `None` if there isn't one.
+    fn get(&self, object: &str) -> io::Result<Option<Vec<u8>>> {
+        let response = self.request("GET", object, &[], &[])?;
+        match response.status {
+            404 => Ok(None),
+            _ => response.check().map(Some),
+        }
+    }
+
+    fn delete(&self, object: &str) -> io::Result<()> {
+        self.request("DELETE", object, &[], &[])?.check().map(drop)
+    }
+
+    /// The parts of every log opened on `object`, in the order they were written.
+    fn logs(&self, object: &str) -> io::Result<Vec<String>> {
+        let prefix = format!("{}{}", object, LOG_SEPARATOR);
+        let mut logs = Vec::new();
+        let mut token = None::<String>;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix.as_str())];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token.as_str()));
+            }
+            let listing = self.request("GET", "", &query, &[])?.check()?;
+            let listing = String::from_utf8_lossy(&listing);
+            logs.extend(between(&listing, "<Key>", "</Key>").map(unescape));
+            token = between(
+                &listing,
+                "<NextContinuationToken>",
+                "</NextContinuationToken>",
+            )
+            .next()
+            .map(unescape);
+            if token.is_none() {
+                // S3 lists them by name, which only puts a log's parts in order while their
+                // numbers have the same number of digits.
+                logs.sort_by(|a, b| part_number(a).cmp(&part_number(b)));
+                return Ok(logs);
+            }
+        }
+    }
+
+    /// A new log on `object`, removed along with the key's own object when it's written.
+    fn open_log(&self, object: &str) -> String {
+        let log = log_name(object);
+        self.own.lock().unwrap().insert(log.clone());
+        log
+    }
+}
+
+/// A name for a new log on `object`, sorting after every log opened on it before.
+fn log_name(object: &str) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO);
+    format!(
+        "{}{}{:016x}-{:08x}",
+        object,
+        LOG_SEPARATOR,
+        now.as_nanos() as u64,
+        process::id()
+    )
+}
+
+/// The object the `number`th part of `log` is kept in.
+fn part(log: &str, number: usize) -> String {
+    format!("{}-{:08x}", log, number)
+}
+
+/// The log `part` belongs to and its number within it, for putting parts in the order they were
+/// written.
+fn part_number(part: &str) -> (&str, u64) {
+    let (log, number) = part.rsplit_once('-').unwrap_or((part, ""));
+    (log, u64::from_str_radix(number, 16).unwrap_or(0))
+}
+
+/// Adds `part` to the end of `contents`.  Each part starts on a line of its own, even if the last
+/// was cut off.
+fn join(contents: &mut Vec<u8>, part: Vec<u8>) {
+    if !contents.is_empty() && !contents.ends_with(b"\n") {
+        contents.push(b'\n');
+    }
+    contents.extend(part);
+}
+
+impl Storage for Bucket {
+    /// Reads the key's own object and then every part, a request each, which compacting the key
+    /// keeps down to a few.
+    fn read(&self, key: &Path) -> io::Result<Option<Vec<u8>>> {
+        let object = self.object(key);
+        let mut contents = None::<Vec<u8>>;
+        for name in [object.clone()].into_iter().chain(self.logs(&object)?) {
+            // A part can be compacted away between being listed and being read.
+            if let Some(part) = self.get(&name)? {
+                join(contents.get_or_insert_with(Vec::new), part);
+            }
+        }
+        Ok(contents)
+    }
+
+    /// Replaces the key's own object, and removes the logs opened on it here.  Other machines'
+    /// logs are left, since they might still be adding to them; a key's only started over when
+    /// nothing else can be using it, as the cache's keys are named for what they're for.
+    fn write(&self, key: &Path, contents: &[u8]) -> io::Result<()> {
+        let object = self.object(key);
+        self.request("PUT", &object, &[], contents)?.check()?;
+        let own = self.own.lock().unwrap().clone();
+        for part in self.logs(&object)? {
+            if own.iter().any(|log| part.starts_with(log.as_str())) {
+                self.delete(&part)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn append(&self, key: &Path) -> io::Result<Box<dyn Log>> {
+        Ok(Box::new(Upload {
+            bucket: self.clone(),
+            log: self.open_log(&self.object(key)),
+            parts: 0,
+            pending: Vec::new(),
+        }))
+    }
+
+    /// Writes out every part of every log on the key together, as the only part of a log of its
+    /// own, and then removes the parts it's made of.  Parts are never replaced, so another machine
+    /// compacting the key at the same time can only leave lines twice, never lose them.
+    fn compact(&self, key: &Path) -> io::Result<()> {
+        let object = self.object(key);
+        let parts = self.logs(&object)?;
+        if parts.len() < 2 {
+            return Ok(());
+        }
+        let mut contents = Vec::new();
+        for name in &parts {
+            if let Some(part) = self.get(name)? {
+                join(&mut contents, part);
+            }
+        }
+        // Most of its lines are other machines', so it mustn't be one of the logs that writing
+        // the key here removes.
+        self.request("PUT", &part(&log_name(&object), 0), &[], &contents)?
+            .check()?;
+        for part in parts {
+            self.delete(&part)?;
+        }
+        Ok(())
+    }
+
+    fn locate(&self, key: &Path) -> String {
+        format!("s3://{}/{}", self.bucket, self.object(key))
+    }
+}
+
+/// A log kept as a series of objects, each what was written between one sync and the next, so a
+/// sync only ever uploads what's new.
+struct Upload {
+    bucket: Bucket,
+    log: String,
+    /// How many parts have been uploaded.
+    parts: usize,
+    /// What's been written since the last part.
+    pending: Vec<u8>,
+}
+
+impl Write for Upload {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Log for Upload {
+    fn sync(&mut self) -> io::Result<()> {
+        if !self.pending.is_empty() {
+            self.bucket
+                .request("PUT", &part(&self.log, self.parts), &[], &self.pending)?
+                .check()?;
+            self.parts += 1;
+            self.pending.clear();
+        }
+        Ok(())
+    }
+}
+
+/// The names of `headers`, as they go in the signature and the authorization header.
+fn signed_headers(headers: &[(&str, String)]) -> String {
+    headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// The version 4 signature for a request at `time`, like `20130524T000000Z`: its method, its
+/// path, encoded, and its query, encoded and in order; its `headers`, lowercase and in order by
+/// name; and the SHA-256 of its body.
+fn signature(
+    secret_key: &str,
+    region: &str,
+    time: &str,
+    [method, path, query]: [&str; 3],
+    headers: &[(&str, String)],
+    payload: &str,
+) -> String {
+    let date = &time[..8];
+    let canonical = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method,
+        path,
+        query,
+        headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect::<String>(),
+        signed_headers(headers),
+        payload
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        time,
+        scope,
+        sha256(canonical.as_bytes())
+    );
+    let key = [date, region, "s3", "aws4_request"]
+        .iter()
+        .fold(format!("AWS4{}", secret_key).into_bytes(), |key, part| {
+            hmac(&key, part.as_bytes())
+        });
+    hex(&hmac(&key, to_sign.as_bytes()))
+}
+
+/// The text between each `open` and the `close` after it, for picking values out of S3's XML.
+fn between<'a>(xml: &'a str, open: &'a str, close: &'a str) -> impl Iterator<Item = &'a str> {
+    xml.split(open)
+        .skip(1)
+        .filter_map(move |s| s.split_once(close).map(|(value, _)| value))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Percent-encodes everything but the characters S3 leaves as they are, and `/` too unless it's
+/// for a `path`.
+fn encode(text: &str, path: bool) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b'/' if path => "/".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// HMAC-SHA256, which is all the signatures are made of.
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut block = [0u8; 64];
+    match key.len() > block.len() {
+        true => block[..32].copy_from_slice(&Sha256::digest(key)),
+        false => block[..key.len()].copy_from_slice(key),
+    }
+    let pad = |byte: u8| block.iter().map(|b| b ^ byte).collect::<Vec<_>>();
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .to_vec()
+}
+
+fn sha256(contents: &[u8]) -> String {
+    hex(&Sha256::digest(contents))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The examples from AWS's "Signature Calculations for the Authorization Header" for S3.
+    const SECRET_KEY: &str = "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY";
+    const TIME: &str = "20130524T000000Z";
+    const HOST: &str = "examplebucket.s3.amazonaws.com";
+    const EMPTY: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    fn headers(before: &[(&'static str, &str)], payload: &str) -> Vec<(&'static str, String)> {
+        let mut headers = vec![("host", HOST.to_string())];
+        headers.extend(
+            before
+                .iter()
+                .map(|(name, value)| (*name, value.to_string())),
+        );
+        headers.push(("x-amz-content-sha256", payload.to_string()));
+        headers.push(("x-amz-date", TIME.to_string()));
+        headers.sort();
+        headers
+    }
+
+    fn sign(request: [&str; 3], headers: &[(&str, String)], payload: &str) -> String {
+        signature(SECRET_KEY, "us-east-1", TIME, request, headers, payload)
+    }
+
+    #[test]
+    fn signs_get_object() {
+        let headers = headers(&[("range", "bytes=0-9")], EMPTY);
+        assert_eq!(
+            sign(["GET", "/test.txt", ""], &headers, EMPTY),
+            "f0e8bdb87c964420e857bd35b5d6ed310bd44f0170aba48dd91039c6036bdb41"
+        );
+    }
+
+    #[test]
+    fn signs_put_object() {
+        let payload = sha256(b"Welcome to Amazon S3.");
+        assert_eq!(
+            payload,
+            "44ce7dd67c959e0d3524ffac1771dfbba87d2b6b4b4e99e42034a8b803f8b072"
+        );
+        let headers = headers(
+            &[
+                ("date", "Fri, 24 May 2013 00:00:00 GMT"),
+                ("x-amz-storage-class", "REDUCED_REDUNDANCY"),
+            ],
+            &payload,
+        );
+        let path = format!("/{}", encode("test$file.text", true));
+        assert_eq!(path, "/test%24file.text");
+        assert_eq!(
+            sign(["PUT", &path, ""], &headers, &payload),
+            "98ad721746da40c64f1a55b78f14c238d841ea1380cd77a1b5971af0ece108bd"
+        );
+    }
+
+    #[test]
+    fn signs_get_bucket_lifecycle() {
+        let headers = headers(&[], EMPTY);
+        assert_eq!(
+            sign(["GET", "/", "lifecycle="], &headers, EMPTY),
+            "fea454ca298b7da1c68078a5d1bdbfbbe0d65c699e0f91ac7a200a0136783543"
+        );
+    }
+
+    #[test]
+    fn signs_list_objects() {
+        let headers = headers(&[], EMPTY);
+        assert_eq!(
+            sign(["GET", "/", "max-keys=2&prefix=J"], &headers, EMPTY),
+            "34b48302e7b5fa45bde8084f4b7868a86f0a534bc59db6670ed5711ef69dc6f7"
+        );
+    }
+
+    // RFC 4231's test cases 1, 2 and 6, the last for a key longer than a block.
+    #[test]
+    fn hmac_matches_rfc_4231() {
+        assert_eq!(
+            hex(&hmac(&[0x0b; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex(&hmac(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex(&hmac(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn sorts_parts_by_number() {
+        let mut parts = vec!["a.log-1-00000010", "a.log-1-2", "a.log-0-00000003"];
+        parts.sort_by(|a, b| part_number(a).cmp(&part_number(b)));
+        assert_eq!(parts, ["a.log-0-00000003", "a.log-1-2", "a.log-1-00000010"]);
+    }
+
+    #[test]
+    fn reads_listings() {
+        let listing = "<Key>a&amp;b</Key><Key>c</Key>";
+        let keys = between(listing, "<Key>", "</Key>")
+            .map(unescape)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["a&b", "c"]);
+    }
+}
@@ -0,0 +1,83 @@
+//! Scores openers against a sample of the answers, for approximate scores in a fraction of the
+//! time, chosen so that comparisons between openers are as steady as they can be.  The answers are
+//! split up by the feedback an opener gets from them, and the sample's taken from each group in
+//! proportion to its size, so no opener's luckier than another in which of its groups got
+//! sampled.  Within each group, the answers taken are the first in one random order of all of them,
+//! the same for every opener, so that what noise is left is mostly the same noise for all of them.
+
+use rand::seq::SliceRandom;
+
+use crate::{get_pattern, pattern_count, seed, Word};
+
+pub struct Sample {
+    /// How many answers each opener is scored against.
+    count: usize,
+    seed: u64,
+    /// Each answer's place in the order the sample's taken in.
+    place: Vec<usize>,
+}
+
+impl Sample {
+    /// `count` of `answer_count` answers, in an order from `seed`.
+    pub fn new(count: usize, seed: u64, answer_count: usize) -> Self {
+        let mut order = (0..answer_count).collect::<Vec<_>>();
+        order.shuffle(&mut seed::stream(seed, "answer sample", 0));
+        let mut place = vec![0; answer_count];
+        for (i, answer) in order.into_iter().enumerate() {
+            place[answer] = i;
+        }
+        Self {
+            count: count.clamp(1, answer_count.max(1)),
+            seed,
+            place,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The answers `opener` is scored against, as indices into `answers`, and how many answers each
+    /// stands for.
+    pub fn choose(&self, opener: &Word, answers: &[Word]) -> (Vec<usize>, Vec<f32>) {
+        let mut groups = vec![Vec::new(); pattern_count(opener.length())];
+        for (i, answer) in answers.iter().enumerate() {
+            groups[get_pattern(answer, opener)].push(i);
+        }
+        groups.retain(|g| !g.is_empty());
+        groups.sort_by_key(|g| std::cmp::Reverse(g.len()));
+
+        // Every group gets one, as far as the sample goes, so even the smallest are represented,
+        // and the rest go one at a time to whichever group has the most answers for each taken.
+        let mut taken = vec![0; groups.len()];
+        taken.iter_mut().take(self.count).for_each(|t| *t = 1);
+        for _ in groups.len()..self.count {
+            let (_, group) = groups
+                .iter()
+                .enumerate()
+                .filter(|(i, g)| taken[*i] < g.len())
+                .map(|(i, g)| (g.len() as f32 / taken[i] as f32, i))
+                .fold(
+                    (0.0, 0),
+                    |best, next| if next.0 > best.0 { next } else { best },
+                );
+            taken[group] += 1;
+        }
+
+        let mut chosen = Vec::with_capacity(self.count);
+        let mut weights = Vec::with_capacity(self.count);
+        for (group, taken) in groups.iter_mut().zip(taken).filter(|(_, t)| *t > 0) {
+            group.sort_by_key(|i| self.place[*i]);
+            chosen.extend(&group[..taken]);
+            weights.extend(std::iter::repeat_n(
+                group.len() as f32 / taken as f32,
+                taken,
+            ));
+        }
+        (chosen, weights)
+    }
+}
@@ -0,0 +1,424 @@
+//! Runs a series of brutal's commands against lists that are only loaded once, so a batch of small
+//! questions doesn't pay for loading them each time.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use clap::{Args, CommandFactory, ErrorKind, Parser, Subcommand, ValueHint};
+
+use crate::chunks;
+use crate::clue::{self, Clue};
+use crate::exit::{self, OrInvalidInput};
+use crate::finish::Suggestion;
+use crate::lookup;
+use crate::query::Query;
+use crate::solver::{ProgressSink, Solver, SolverConfig, WordResult};
+use crate::{
+    check_list_roles, print_clusters, print_letter_effects, print_matches, print_nearest,
+    read_guesses, read_lines, TieBreak, Word,
+};
+
+/// The lists a script, or the prompt, is run against.
+#[derive(Clone, Debug, Args)]
+pub struct Lists {
+    #[clap(
+        short,
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Override the default answer list",
+        default_value = "wordle_answer_list.txt"
+    )]
+    answer_list: PathBuf,
+
+    #[clap(
+        short,
+        long,
+        parse(from_os_str),
+        value_hint = ValueHint::FilePath,
+        help = "Override the default guess list [defaults to the answer list]"
+    )]
+    guess_list: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Add any answers missing from the guess list to it, so every answer can be guessed"
+    )]
+    guesses_include_answers: bool,
+
+    #[clap(short, long, default_value = "1")]
+    threads: usize,
+
+    #[clap(
+        long,
+        help = "Refuse to score words that aren't in the loaded lists, rather than only warning about them"
+    )]
+    strict: bool,
+}
+
+impl Lists {
+    pub fn load(&self) -> Session {
+        let answers = read_lines(&self.answer_list);
+        let guess_list = self.guess_list.as_ref().unwrap_or(&self.answer_list);
+        let mut guesses = read_guesses(guess_list, &answers);
+        check_list_roles(&answers, &mut guesses, self.guesses_include_answers);
+        // Suggestions prefer a guess that could be the answer to one as good that couldn't.
+        TieBreak::AnswersFirst.order(&mut guesses, &answers, None);
+        let mut words = answers
+            .iter()
+            .chain(guesses.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        words.sort();
+        words.dedup();
+
+        Session {
+            solver: SolverConfig::new(answers, guesses)
+                .threads(self.threads)
+                .build(),
+            words,
+            strict: self.strict,
+            scores: Mutex::default(),
+        }
+    }
+}
+
+/// A line of a script, parsed like brutal's own arguments.
+#[derive(Debug, Parser)]
+#[clap(no_binary_name = true, about = "Commands run against the loaded lists")]
+struct Line {
+    #[clap(subcommand)]
+    command: ScriptCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ScriptCommand {
+    /// Score openers against the loaded lists
+    Score {
+        #[clap(required = true, parse(try_from_str))]
+        words: Vec<Word>,
+    },
+    /// Find the words in the loaded lists nearest to one that might be mistyped
+    Lookup {
+        word: String,
+
+        #[clap(short, long, help = "How many words to show", default_value = "5")]
+        count: usize,
+    },
+    /// List the loaded answers that fit a game's feedback so far, or a crossword's known letters
+    Match {
+        #[clap(flatten)]
+        query: Query,
+
+        #[clap(short, long, help = "Show at most this many words")]
+        count: Option<usize>,
+    },
+    /// Suggest the best next guess for the loaded answers that fit a game's feedback so far
+    Suggest {
+        #[clap(flatten)]
+        query: Query,
+
+        #[clap(
+            long,
+            help = "Search for at most this many milliseconds, then suggest the best guess found by then, or the one that splits the answers most evenly if none was searched in time"
+        )]
+        budget_ms: Option<u64>,
+
+        #[clap(
+            long,
+            help = "Show each better guess as it's found, starting with the most promising before any are searched, with the one suggested last"
+        )]
+        refine: bool,
+    },
+    /// Attribute the scores in a results file to the letters and positions of each opener
+    Letters {
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "A results file produced by a previous run"
+        )]
+        results: PathBuf,
+    },
+    /// Group openers in a results file whose first-guess partitions of the loaded answers are nearly identical
+    Cluster {
+        #[clap(
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "A results file produced by a previous run"
+        )]
+        results: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            help = "The normalized mutual information two partitions must share to be clustered",
+            default_value = "0.8"
+        )]
+        threshold: f32,
+    },
+}
+
+/// Catches a missing results file before the command's run, so it's reported like any other
+/// mistake in the line rather than ending the run.
+fn existing_file(path: &Path) -> Result<(), clap::Error> {
+    if path.is_file() || chunks::is_chunked(path) {
+        Ok(())
+    } else {
+        let message = format!("no such file: {}\n", path.display());
+        Err(clap::Error::raw(ErrorKind::ValueValidation, message))
+    }
+}
+
+/// Keeps the results of a batch of openers, in whatever order they finish.
+#[derive(Default)]
+struct Collected(Mutex<Vec<WordResult>>);
+
+impl ProgressSink for Collected {
+    fn word_finished(&self, _worker: usize, result: WordResult, _elapsed: Duration) {
+        self.0.lock().unwrap().push(result);
+    }
+}
+
+/// The lists each command is run against.
+pub struct Session {
+    solver: Solver,
+    /// Every word in the lists, in order.
+    words: Vec<Word>,
+    /// Whether words that aren't in the lists are refused.
+    strict: bool,
+    /// The scores of the openers scored so far, so they're only ever searched once.
+    scores: Mutex<HashMap<Word, (f32, f32)>>,
+}
+
+impl Session {
+    /// Every word the commands know of, in order.
+    pub fn words(&self) -> &[Word] {
+        &self.words
+    }
+
+    pub fn solver(&self) -> &Solver {
+        &self.solver
+    }
+
+    /// The names of the commands, for completing them.
+    pub fn commands() -> Vec<String> {
+        let mut command = Line::command();
+        command.build();
+        command
+            .get_subcommands()
+            .map(|c| c.get_name().to_string())
+            .collect()
+    }
+
+    /// Runs a line, which can also be blank or a `#` comment, writing what it prints to `out` and
+    /// any warnings to `warnings`.  Help is written to `out` too, rather than being returned as an
+    /// error.
+    pub fn run(
+        &self,
+        line: &str,
+        out: &mut impl Write,
+        warnings: &mut impl Write,
+    ) -> Result<(), clap::Error> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+        self.run_args(Path::new(""), line.split_whitespace(), out, warnings)
+    }
+
+    /// Runs a line that's already been split into its arguments, with any files it names relative
+    /// to `dir`.
+    pub fn run_args<S: Into<OsString> + Clone>(
+        &self,
+        dir: &Path,
+        args: impl IntoIterator<Item = S>,
+        out: &mut impl Write,
+        warnings: &mut impl Write,
+    ) -> Result<(), clap::Error> {
+        match Line::try_parse_from(args) {
+            Ok(mut line) => {
+                if let ScriptCommand::Letters { results } | ScriptCommand::Cluster { results, .. } =
+                    &mut line.command
+                {
+                    *results = dir.join(&*results);
+                }
+                self.check(&line.command, warnings)?;
+                self.execute(line.command, out, warnings)
+            }
+            Err(e) if !e.use_stderr() => write!(out, "{}", e).expect("cannot write help"),
+            Err(e) => return Err(e),
+        }
+        Ok(())
+    }
+
+    /// Warns about, or refuses, words that aren't in the lists, since they're usually typos, and
+    /// refuses words and clues with a different number of letters than the lists' words, and
+    /// results files that aren't there.
+    fn check(&self, command: &ScriptCommand, warnings: &mut impl Write) -> Result<(), clap::Error> {
+        let length = self.solver.word_length();
+        let words = match command {
+            ScriptCommand::Score { words } => words,
+            ScriptCommand::Match { query, .. } | ScriptCommand::Suggest { query, .. } => {
+                return clue::check_length(&query.clues(), length)
+                    .map_err(|e| clap::Error::raw(ErrorKind::InvalidValue, e + "\n"));
+            }
+            ScriptCommand::Letters { results } | ScriptCommand::Cluster { results, .. } => {
+                return existing_file(results);
+            }
+            _ => return Ok(()),
+        };
+
+        for word in words {
+            if word.length() != length {
+                let message = format!(
+                    "{} has {} letters, but the loaded lists' words have {}\n",
+                    word,
+                    word.length(),
+                    length
+                );
+                return Err(clap::Error::raw(ErrorKind::InvalidValue, message));
+            }
+            if self.words.binary_search(word).is_ok() {
+                continue;
+            }
+
+            let mut message = format!("{} isn't in the loaded lists", word);
+            if let Some(nearest) = lookup::nearest(&word.to_string(), &self.words, 1).first() {
+                message.push_str(&format!(" (did you mean {}?)", nearest));
+            }
+            if self.strict {
+                return Err(clap::Error::raw(ErrorKind::InvalidValue, message + "\n"));
+            }
+            writeln!(warnings, "warning: {}", message).expect("cannot write warning");
+        }
+        Ok(())
+    }
+
+    fn execute(&self, command: ScriptCommand, out: &mut impl Write, warnings: &mut impl Write) {
+        match command {
+            ScriptCommand::Score { words } => self.print_scores(out, &words),
+            ScriptCommand::Lookup { word, count } => print_nearest(out, &word, &self.words, count),
+            ScriptCommand::Match { query, count } => print_matches(
+                out,
+                &query.clues(),
+                self.solver.answers(),
+                None,
+                count,
+                query.simplify(),
+            ),
+            ScriptCommand::Suggest {
+                query,
+                budget_ms,
+                refine,
+            } => self.print_suggestion(
+                out,
+                warnings,
+                &query.clues(),
+                budget_ms.map(Duration::from_millis),
+                refine,
+            ),
+            ScriptCommand::Letters { results } => print_letter_effects(out, &results),
+            ScriptCommand::Cluster { results, threshold } => {
+                print_clusters(out, &results, self.solver.answers(), threshold)
+            }
+        }
+    }
+
+    fn print_suggestion(
+        &self,
+        out: &mut impl Write,
+        warnings: &mut impl Write,
+        clues: &[Clue],
+        budget: Option<Duration>,
+        refine: bool,
+    ) {
+        if clue::enumerate_answers(self.solver.answers(), clues)
+            .next()
+            .is_none()
+        {
+            writeln!(out, "There are no answers left.").expect("cannot write line");
+            return;
+        }
+
+        writeln!(out, "guess,  answers,  average,   exact").expect("cannot write header");
+        let suggestion = self
+            .solver
+            .suggest(clues, budget, |refined| {
+                if refine {
+                    write_suggestion(out, refined);
+                }
+            })
+            .unwrap();
+        if !suggestion.searched {
+            writeln!(
+                warnings,
+                "warning: no guess could be searched in time, so the average is the fewest it could take"
+            )
+            .expect("cannot write warning");
+        }
+        write_suggestion(out, &suggestion);
+    }
+
+    fn print_scores(&self, out: &mut impl Write, words: &[Word]) {
+        let mut scores = self.scores.lock().unwrap();
+        let unscored = words
+            .iter()
+            .filter(|w| !scores.contains_key(w))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let collected = Collected::default();
+        self.solver.score_all(unscored.into_iter(), &collected);
+        for result in collected.0.into_inner().unwrap() {
+            scores.insert(result.word, result.score);
+        }
+
+        writeln!(
+            out,
+            "{:w$} average, success",
+            "word,",
+            w = self.solver.word_length() + 1
+        )
+        .expect("cannot write header");
+        for word in words {
+            let (guess_count, success_rate) = scores[word];
+            writeln!(out, "{}, {:7.3}, {:7.4}", word, guess_count, success_rate)
+                .expect("cannot write line");
+        }
+    }
+}
+
+/// Writes a line of `suggest`'s table, flushing it so it's seen as soon as it's found.
+fn write_suggestion(out: &mut impl Write, suggestion: &Suggestion) {
+    writeln!(
+        out,
+        "{}, {:8}, {:8.3}, {:>7}",
+        suggestion.guess,
+        suggestion.answers,
+        suggestion.total as f32 / suggestion.answers as f32,
+        suggestion.exact
+    )
+    .expect("cannot write line");
+    out.flush().expect("cannot write line");
+}
+
+/// Runs each line of a script in turn, stopping at the first that doesn't parse.
+pub fn run_script(filename: &Path, session: &Session) {
+    let file = File::open(filename).or_invalid_input(|| filename.display().to_string());
+    let buf = BufReader::new(file);
+
+    for (i, l) in buf.lines().enumerate() {
+        let line = || format!("{}:{}", filename.display(), i + 1);
+        let l = l.or_invalid_input(line);
+        if let Err(e) = session.run(&l, &mut io::stdout(), &mut io::stderr()) {
+            eprintln!("{}: {}", line(), l.trim());
+            let _ = e.print();
+            exit::exit(exit::INVALID_INPUT);
+        }
+    }
+}
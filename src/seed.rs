@@ -0,0 +1,17 @@
+//! Random streams derived from the one `--seed`, so a run's randomness doesn't depend on how many
+//! threads it has or the order they get to things in.  Each use of randomness has its own stream,
+//! and work split across threads takes a stream for each piece of it, not for each thread.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sha2::{Digest, Sha256};
+
+/// The stream for the `index`th piece of the work named by `purpose`.  The same stream comes from
+/// the same seed on every platform, though not necessarily with another version of `rand`.
+pub fn stream(seed: u64, purpose: &str, index: u64) -> StdRng {
+    let mut hasher = Sha256::new();
+    hasher.update(seed.to_le_bytes());
+    hasher.update(purpose.as_bytes());
+    hasher.update(index.to_le_bytes());
+    StdRng::from_seed(hasher.finalize().into())
+}
@@ -0,0 +1,577 @@
+//! Keeps the lists loaded in a server, so a stream of small questions through the day is answered
+//! without loading them for each one, and editors, shell functions and the like can all share it.
+//! The protocol clients speak is described by [`PROTOCOL`], which `brutal serve --help` shows.
+//!
+//! Batch jobs are scored in the background, one after another, on a thread of their own.  They
+//! give way to the requests clients are waiting on, pausing between the answers they search
+//! whenever one's being answered, so a long job doesn't hold up a quick question.
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::mem;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use clap::{Parser, ValueHint};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::columns::{self, Column, Field};
+use crate::exit::{self, OrInvalidInput};
+use crate::paths;
+use crate::script::Session;
+use crate::solver::{ProgressSink, WordResult};
+use crate::Word;
+
+pub const PROTOCOL: &str = "\
+PROTOCOL:
+    Clients connect to the socket and send messages of JSON, each preceded by its length in bytes
+    as a big-endian 32-bit integer.  A request looks like
+
+        {\"dir\": \"/home/me/wordle\", \"args\": [\"cluster\", \"word_scores.csv\", \"-t\", \"0.5\"]}
+
+    where `args` is a script command split into its arguments, and `dir` is the directory any files
+    it names are relative to.  Each request is answered in turn with
+
+        {\"status\": \"ok\", \"out\": \"word,  average, success\\n...\", \"err\": \"\"}
+
+    where `status` is `ok` or `error`, `out` is what the command printed, and `err` holds any
+    warnings, or what was wrong with the command.  A connection can carry any number of requests,
+    one after another, but clients are answered one at a time, so one that leaves the server
+    waiting 10 seconds on it is hung up on.  Messages over 16 MiB are refused.
+
+    A request with `\"stream\": true` has what the command prints sent on ahead as it's printed, a
+    line or more at a time, in responses with a `status` of `partial` and no `err`, before the
+    usual response with the rest.  That's for commands that print as they go, like
+    `suggest --refine`, which shows the most promising guess straight away and then each better one
+    the search finds.
+
+    Besides the script commands, there are two for scoring in the background:
+
+        batch -o <OUTPUT> [-s <SEARCH_LIST>] [WORDS]...
+
+    queues a job scoring the openers, writing their results file once they're all scored, and
+    answers straight away with the job's number.  Jobs run one at a time, and give way to the
+    other requests, which are answered first.
+
+        jobs
+
+    lists the jobs and how far along they are.";
+
+/// Longer messages are refused, so a bad length can't take the server's memory with it.
+const MESSAGE_LIMIT: u32 = 16 * 1024 * 1024;
+/// How long a client can keep the server waiting, to send a request or to take an answer, before
+/// it's hung up on, so one that's stuck can't keep every other from being answered.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Request {
+    pub dir: PathBuf,
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub stream: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Error,
+    /// More of the output is still to come.
+    Partial,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Response {
+    pub status: Status,
+    pub out: String,
+    pub err: String,
+}
+
+/// The requests the server answers itself, rather than running as script commands.
+#[derive(Debug, Parser)]
+#[clap(no_binary_name = true)]
+enum ServerCommand {
+    /// Score openers in the background, writing their results file once they're done
+    Batch {
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Where to write the results"
+        )]
+        output: PathBuf,
+
+        #[clap(
+            short,
+            long,
+            parse(from_os_str),
+            value_hint = ValueHint::FilePath,
+            help = "Score each opener in this list, as well as any given"
+        )]
+        search_list: Option<PathBuf>,
+
+        #[clap(parse(try_from_str), required_unless_present = "search-list")]
+        words: Vec<Word>,
+    },
+    /// List the batch jobs, and how far along they are
+    Jobs,
+}
+
+impl ServerCommand {
+    fn is_one(args: &[String]) -> bool {
+        matches!(args.first().map(String::as_str), Some("batch" | "jobs"))
+    }
+}
+
+/// Has batch work wait while any of the requests clients are waiting on are being answered.
+#[derive(Default)]
+struct Scheduler {
+    /// How many requests are being answered.
+    answering: Mutex<usize>,
+    answered: Condvar,
+}
+
+impl Scheduler {
+    /// Runs `f`, with batch work held up until it's done.
+    fn answer<T>(&self, f: impl FnOnce() -> T) -> T {
+        *self.answering.lock().unwrap() += 1;
+        let result = f();
+        *self.answering.lock().unwrap() -= 1;
+        self.answered.notify_all();
+        result
+    }
+
+    /// Returns once no request's being answered.
+    fn wait_turn(&self) {
+        let answering = self.answering.lock().unwrap();
+        drop(
+            self.answered
+                .wait_while(answering, |answering| *answering > 0)
+                .unwrap(),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum JobState {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+struct Job {
+    output: PathBuf,
+    words: Vec<Word>,
+    state: JobState,
+    /// The openers scored so far.
+    results: Vec<WordResult>,
+    /// What went wrong, if it failed.
+    error: Option<String>,
+}
+
+/// The batch jobs, in the order they were queued, numbered from 1.
+#[derive(Default)]
+struct Jobs(Mutex<Vec<Job>>);
+
+/// Hears a job's results, and holds its workers up between openers and answers while requests are
+/// being answered.
+struct JobProgress<'a> {
+    scheduler: &'a Scheduler,
+    jobs: &'a Jobs,
+    job: usize,
+}
+
+impl ProgressSink for JobProgress<'_> {
+    fn word_started(&self, _worker: usize, _word: &Word, _answer_count: usize) {
+        self.scheduler.wait_turn();
+    }
+
+    fn answer_started(&self, _worker: usize, _word: &Word, _answer: &Word) {
+        self.scheduler.wait_turn();
+    }
+
+    fn word_finished(&self, _worker: usize, result: WordResult, _elapsed: Duration) {
+        self.jobs.0.lock().unwrap()[self.job].results.push(result);
+    }
+}
+
+/// The server's state besides the lists, shared by the thread answering clients and the one
+/// running batch jobs.
+struct Server<'a> {
+    session: &'a Session,
+    scheduler: Scheduler,
+    jobs: Jobs,
+}
+
+impl Server<'_> {
+    /// Runs each job sent down `queue` in turn, until the server's stopped.
+    fn run_jobs(&self, queue: mpsc::Receiver<usize>) {
+        for job in queue {
+            let words = {
+                let mut jobs = self.jobs.0.lock().unwrap();
+                jobs[job].state = JobState::Running;
+                jobs[job].words.clone()
+            };
+            let progress = JobProgress {
+                scheduler: &self.scheduler,
+                jobs: &self.jobs,
+                job,
+            };
+            self.session
+                .solver()
+                .score_all(words.into_iter(), &progress);
+
+            let mut jobs = self.jobs.0.lock().unwrap();
+            let job = &mut jobs[job];
+            let word_length = self.session.solver().word_length();
+            match write_results(&job.output, &mut job.results, word_length) {
+                Ok(()) => job.state = JobState::Done,
+                Err(e) => {
+                    job.state = JobState::Failed;
+                    job.error = Some(format!("{}: {}", job.output.display(), e));
+                }
+            }
+        }
+    }
+
+    fn run(&self, request: &Request, queue: &Sender<usize>, out: &mut Output) -> Response {
+        if !ServerCommand::is_one(&request.args) {
+            return self.scheduler.answer(|| {
+                run(request, out, |out, err| {
+                    self.session.run_args(&request.dir, &request.args, out, err)
+                })
+            });
+        }
+
+        run(request, out, |out, _| {
+            match ServerCommand::try_parse_from(&request.args) {
+                Ok(ServerCommand::Batch {
+                    output,
+                    search_list,
+                    mut words,
+                }) => {
+                    if let Some(search_list) = search_list {
+                        let listed = read_words(&request.dir.join(search_list))
+                            .map_err(|e| clap::Error::raw(clap::ErrorKind::Io, e + "\n"))?;
+                        words.extend(listed);
+                    }
+                    let length = self.session.solver().word_length();
+                    if let Some(word) = words.iter().find(|w| w.length() != length) {
+                        let message = format!(
+                            "{} has {} letters, but the loaded lists' words have {}\n",
+                            word,
+                            word.length(),
+                            length
+                        );
+                        return Err(clap::Error::raw(clap::ErrorKind::InvalidValue, message));
+                    }
+                    let output = request.dir.join(output);
+                    let mut jobs = self.jobs.0.lock().unwrap();
+                    writeln!(
+                        out,
+                        "Queued job {}, scoring {} openers, for {}",
+                        jobs.len() + 1,
+                        words.len(),
+                        output.display()
+                    )
+                    .expect("cannot write line");
+                    jobs.push(Job {
+                        output,
+                        words,
+                        state: JobState::Queued,
+                        results: Vec::new(),
+                        error: None,
+                    });
+                    queue
+                        .send(jobs.len() - 1)
+                        .expect("the batch thread has stopped");
+                }
+                Ok(ServerCommand::Jobs) => self.print_jobs(out),
+                Err(e) if !e.use_stderr() => write!(out, "{}", e).expect("cannot write help"),
+                Err(e) => return Err(e),
+            }
+            Ok(())
+        })
+    }
+
+    fn print_jobs(&self, out: &mut impl Write) {
+        writeln!(out, "job,   state, openers,  scored, output").expect("cannot write header");
+        for (i, job) in self.jobs.0.lock().unwrap().iter().enumerate() {
+            let state = match job.state {
+                JobState::Queued => "queued",
+                JobState::Running => "running",
+                JobState::Done => "done",
+                JobState::Failed => "failed",
+            };
+            let output = match &job.error {
+                Some(error) => error.clone(),
+                None => job.output.display().to_string(),
+            };
+            writeln!(
+                out,
+                "{:3}, {:>7}, {:7}, {:7}, {}",
+                i + 1,
+                state,
+                job.words.len(),
+                job.results.len(),
+                output
+            )
+            .expect("cannot write line");
+        }
+    }
+}
+
+/// Writes a job's results file, for words of `word_length` letters, best openers first.
+fn write_results(
+    filename: &Path,
+    results: &mut [WordResult],
+    word_length: usize,
+) -> io::Result<()> {
+    results.sort_by(|a, b| {
+        a.score
+            .0
+            .partial_cmp(&b.score.0)
+            .unwrap()
+            .then(a.word.cmp(&b.word))
+    });
+    let columns = [Column::from(Field::Average), Column::from(Field::Success)];
+    let mut file = BufWriter::new(File::create(filename)?);
+    writeln!(file, "{}", columns::schema_line())?;
+    writeln!(file, "{}", columns::header(&columns, word_length))?;
+    for result in results.iter() {
+        let (guess_count, success_rate) = result.score;
+        writeln!(
+            file,
+            "{}, {:7.3}, {:7.4}",
+            result.word, guess_count, success_rate
+        )?;
+    }
+    file.flush()
+}
+
+/// The words in the list at `filename`, one to a line, or what's wrong with it.
+fn read_words(filename: &Path) -> Result<Vec<Word>, String> {
+    let filename = paths::find_list(filename);
+    let contents =
+        fs::read_to_string(&filename).map_err(|e| format!("{}: {}", filename.display(), e))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            Word::from_str(l.trim())
+                .map_err(|e| format!("{}:{}: {:?}: {}", filename.display(), i + 1, l, e))
+        })
+        .collect()
+}
+
+/// What a request prints, kept for its response, or if it's streamed, sent on ahead a line at a
+/// time.
+struct Output<'a> {
+    buffer: Vec<u8>,
+    stream: Option<&'a mut UnixStream>,
+    /// Why the output couldn't be sent on, after which the rest is dropped.
+    error: Option<io::Error>,
+}
+
+impl<'a> Output<'a> {
+    fn new(stream: Option<&'a mut UnixStream>) -> Self {
+        Self {
+            buffer: Vec::new(),
+            stream,
+            error: None,
+        }
+    }
+}
+
+impl Write for Output<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.error.is_some() {
+            return Ok(buf.len());
+        }
+        self.buffer.extend_from_slice(buf);
+        if let (Some(stream), Some(end)) = (
+            &mut self.stream,
+            self.buffer.iter().rposition(|b| *b == b'\n'),
+        ) {
+            let lines = self.buffer.drain(..=end).collect::<Vec<_>>();
+            let partial = Response {
+                status: Status::Partial,
+                out: String::from_utf8_lossy(&lines).into_owned(),
+                err: String::new(),
+            };
+            // The command carries on regardless, so a client that's gone doesn't take it down.
+            if let Err(e) = write_message(stream, &partial) {
+                self.error = Some(e);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads the next message, or `None` if the other end has closed the connection.
+fn read_message<T: DeserializeOwned>(stream: &mut impl Read) -> io::Result<Option<T>> {
+    let mut length = [0; 4];
+    match stream.read_exact(&mut length) {
+        Ok(()) => (),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let length = u32::from_be_bytes(length);
+    if length > MESSAGE_LIMIT {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {} bytes is too long", length),
+        ));
+    }
+
+    let mut message = vec![0; length as usize];
+    stream.read_exact(&mut message)?;
+    Ok(Some(serde_json::from_slice(&message)?))
+}
+
+fn write_message(stream: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+    let message = serde_json::to_vec(message)?;
+    stream.write_all(&(message.len() as u32).to_be_bytes())?;
+    stream.write_all(&message)?;
+    stream.flush()
+}
+
+/// The socket given, or the usual one.
+pub fn socket_or_default(socket: Option<&Path>) -> PathBuf {
+    socket
+        .map(Path::to_path_buf)
+        .or_else(paths::socket_file)
+        .or_invalid_input(|| "there's nowhere to keep the socket; give one with --socket")
+}
+
+/// Answers clients one at a time, until the server's stopped.
+pub fn serve(socket: &Path, session: &Session) {
+    if UnixStream::connect(socket).is_ok() {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!("a server is already listening at {}", socket.display()),
+        );
+    }
+    // Anything left there is the socket of a server that's gone.
+    let _ = fs::remove_file(socket);
+    if let Some(dir) = socket.parent() {
+        fs::create_dir_all(dir).or_invalid_input(|| dir.display().to_string());
+    }
+
+    let listener = UnixListener::bind(socket).or_invalid_input(|| socket.display().to_string());
+    println!("Listening at {}", socket.display());
+
+    let server = Server {
+        session,
+        scheduler: Scheduler::default(),
+        jobs: Jobs::default(),
+    };
+    let (queue, jobs) = mpsc::channel();
+    thread::scope(|scope| {
+        scope.spawn(|| server.run_jobs(jobs));
+        for stream in listener.incoming() {
+            let answered = stream.and_then(|mut stream| {
+                stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+                stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+                answer(&mut stream, &server, &queue)
+            });
+            if let Err(e) = answered {
+                eprintln!("warning: cannot answer a client: {}", e);
+            }
+        }
+    });
+}
+
+/// Answers each of a client's requests, until it hangs up.
+fn answer(stream: &mut UnixStream, server: &Server, queue: &Sender<usize>) -> io::Result<()> {
+    while let Some(request) = read_message::<Request>(stream)? {
+        let mut out = Output::new(request.stream.then_some(&mut *stream));
+        let response = server.run(&request, queue, &mut out);
+        if let Some(e) = out.error {
+            return Err(e);
+        }
+        write_message(stream, &response)?;
+    }
+    Ok(())
+}
+
+/// Answers `request` with what `command` writes to its output and warnings, or the rest of its
+/// output, if it's been sent on ahead.
+fn run(
+    request: &Request,
+    out: &mut Output,
+    command: impl FnOnce(&mut Output, &mut Vec<u8>) -> Result<(), clap::Error>,
+) -> Response {
+    let mut err = Vec::new();
+
+    // Files are named relative to wherever the client was run, which is checked for first so a
+    // client that's in a directory the server can't see is told so.
+    let status = match fs::metadata(&request.dir) {
+        Ok(_) => match command(out, &mut err) {
+            Ok(()) => Status::Ok,
+            Err(e) => {
+                err.extend(e.to_string().bytes());
+                Status::Error
+            }
+        },
+        Err(e) => {
+            err.extend(format!("error: {}: {}\n", request.dir.display(), e).bytes());
+            Status::Error
+        }
+    };
+
+    Response {
+        status,
+        out: String::from_utf8_lossy(&mem::take(&mut out.buffer)).into_owned(),
+        err: String::from_utf8_lossy(&err).into_owned(),
+    }
+}
+
+/// Sends `args` to the server and prints its answer, exiting with [`exit::INVALID_INPUT`] if it
+/// was an error.
+pub fn client(socket: &Path, args: &[String]) {
+    let mut stream = UnixStream::connect(socket).unwrap_or_else(|e| {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!(
+                "no server is listening at {}: {} (start one with `brutal serve`)",
+                socket.display(),
+                e
+            ),
+        )
+    });
+    let request = Request {
+        dir: env::current_dir().or_invalid_input(|| "cannot find the current directory"),
+        args: args.to_vec(),
+        stream: true,
+    };
+    write_message(&mut stream, &request).expect("cannot send request");
+
+    loop {
+        let response = read_message::<Response>(&mut stream)
+            .expect("cannot read response")
+            .expect("the server stopped before answering");
+        eprint!("{}", response.err);
+        print!("{}", response.out);
+        match response.status {
+            Status::Ok => return,
+            Status::Error => exit::exit(exit::INVALID_INPUT),
+            Status::Partial => io::stdout().flush().expect("cannot write output"),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Installs the canonical word lists, which are built in, into the data directory.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::paths;
+
+const LISTS: &[(&str, &str)] = &[
+    (
+        "wordle_answer_list.txt",
+        include_str!("../wordle_answer_list.txt"),
+    ),
+    (
+        "wordle_accepted_list.txt",
+        include_str!("../wordle_accepted_list.txt"),
+    ),
+];
+
+/// Each installed list's checksum is kept in the config, so a later install can tell a list it can
+/// safely replace from one that's been edited since.
+const CONFIG_FILE: &str = "config";
+const LIST_KEY: &str = "list.";
+
+fn sha256(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Reads the `key = value` lines of the config.
+fn read_config(path: &Path) -> HashMap<String, String> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+fn write_config(path: &Path, config: &HashMap<String, String>) {
+    let mut keys = config.keys().collect::<Vec<_>>();
+    keys.sort();
+
+    let mut contents = String::from("# Written by `brutal init`.\n");
+    for key in keys {
+        contents.push_str(&format!("{} = {}\n", key, config[key]));
+    }
+
+    fs::write(path, contents).expect("cannot write config");
+}
+
+/// Installs any of the lists that are missing or out of date, leaving alone any that have been
+/// edited unless `force` is set.
+pub fn init(force: bool) {
+    let data_dir = paths::data_dir().expect("no data directory on this platform");
+    let config_dir = paths::config_dir().expect("no config directory on this platform");
+    fs::create_dir_all(&data_dir).expect("cannot create data directory");
+    fs::create_dir_all(&config_dir).expect("cannot create config directory");
+
+    let config_path = config_dir.join(CONFIG_FILE);
+    let mut config = read_config(&config_path);
+
+    for (name, contents) in LISTS {
+        let path = data_dir.join(name);
+        let checksum = sha256(contents.as_bytes());
+        let key = format!("{}{}", LIST_KEY, name);
+
+        let status = match fs::read(&path).map(|c| sha256(&c)) {
+            Ok(installed) if installed == checksum => "up to date",
+            Ok(installed) if !force && config.get(&key) != Some(&installed) => {
+                println!(
+                    "{}: modified since it was installed, leaving it (use --force to replace it)",
+                    path.display()
+                );
+                continue;
+            }
+            Ok(_) => "updated",
+            Err(_) => "installed",
+        };
+
+        if status != "up to date" {
+            install(&path, contents, &checksum);
+        }
+        config.insert(key, checksum);
+
+        println!("{}: {}", path.display(), status);
+    }
+
+    write_config(&config_path, &config);
+}
+
+/// Writes a list, and reads it back to make sure it arrived intact.
+fn install(path: &Path, contents: &str, checksum: &str) {
+    fs::write(path, contents).expect("cannot write word list");
+
+    let written = fs::read(path).expect("cannot read back word list");
+    if sha256(&written) != checksum {
+        panic!(
+            "{} doesn't match its checksum after writing",
+            path.display()
+        );
+    }
+}
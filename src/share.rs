@@ -0,0 +1,160 @@
+//! Reads the grids Wordle shares, like `Wordle 1,234 4/6` and a row of squares for each guess,
+//! pasted one after another into a file, as games for the player's record.  The shares don't say
+//! what the answer was, so it's looked up by puzzle number in an archive of the answers, as lines
+//! of `number, answer`.  A game's day is the one on the line before its grid, if that's a date like
+//! `2022-01-31`, or else its puzzle's, counting from the first puzzle's day.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::exit::{self, OrInvalidInput};
+use crate::games::{Date, Game, Guess};
+use crate::{Word, GUESS_LIMIT, MAX_WORD_LENGTH};
+
+/// The day of the first puzzle, number 0.
+const FIRST_DAY: (i64, i64, i64) = (2021, 6, 19);
+
+/// The answer to each puzzle in `filename`, by its number.
+pub fn read_archive(filename: &Path) -> HashMap<u32, Word> {
+    let contents = fs::read_to_string(filename).or_invalid_input(|| filename.display().to_string());
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim().starts_with('#'))
+        .map(|(i, l)| {
+            let line = || format!("{}:{}", filename.display(), i + 1);
+            let mut columns = l
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|c| !c.is_empty());
+            let number = columns
+                .next()
+                .or_invalid_input(line)
+                .parse::<u32>()
+                .or_invalid_input(line);
+            let answer = columns
+                .next()
+                .or_invalid_input(|| format!("{}: missing answer", line()))
+                .to_lowercase()
+                .parse::<Word>()
+                .or_invalid_input(line);
+            (number, answer)
+        })
+        .collect()
+}
+
+/// The puzzle number and how many guesses it took, or `None` if it was lost, from a line like
+/// `Wordle 1,234 4/6`, which can end in `*` for hard mode.
+pub fn parse_title(line: &str) -> Option<(u32, Option<usize>)> {
+    let rest = line.trim().strip_prefix("Wordle ")?;
+    let (number, score) = rest.rsplit_once(' ')?;
+    let number = number
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    let (guesses, _) = score.trim_end_matches('*').split_once('/')?;
+    let guesses = match guesses {
+        "X" | "x" => None,
+        g => Some(g.parse().ok()?),
+    };
+    Some((number, guesses))
+}
+
+/// The feedback in a row of squares, in either the usual colors or the high contrast ones, which
+/// the game checks is for as many letters as its answer has.
+pub fn parse_row(line: &str) -> Option<Guess> {
+    let squares = line.trim().chars().collect::<Vec<_>>();
+    if squares.is_empty() || squares.len() > MAX_WORD_LENGTH {
+        return None;
+    }
+    let pattern = squares.iter().try_fold(0, |pattern, square| {
+        let digit = match square {
+            '🟩' | '🟧' => 2,
+            '🟨' | '🟦' => 1,
+            '⬛' | '⬜' => 0,
+            _ => return None,
+        };
+        Some(pattern * 3 + digit)
+    })?;
+    Some(Guess::Feedback {
+        pattern,
+        length: squares.len(),
+    })
+}
+
+/// The games shared in `filename`, with their answers from `archive`.  Grids for puzzles that aren't
+/// in it are left out, with a warning.
+pub fn read(filename: &Path, archive: &HashMap<u32, Word>) -> Vec<Game> {
+    let contents = fs::read_to_string(filename).or_invalid_input(|| filename.display().to_string());
+    let lines = contents.lines().collect::<Vec<_>>();
+    let first_day = Date::from_ymd(FIRST_DAY.0, FIRST_DAY.1, FIRST_DAY.2);
+
+    let mut games = Vec::new();
+    let mut date = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = |i: usize| format!("{}:{}", filename.display(), i + 1);
+        let (number, guesses) = match parse_title(lines[i]) {
+            Some(title) => title,
+            None => {
+                // Anything else between the grids is ignored, but a date is kept for the next.
+                let trimmed = lines[i].trim();
+                if !trimmed.is_empty() {
+                    date = trimmed.parse::<Date>().ok();
+                }
+                i += 1;
+                continue;
+            }
+        };
+        let title = i;
+        i += 1;
+        while i < lines.len() && lines[i].trim().is_empty() {
+            i += 1;
+        }
+        let mut rows = Vec::new();
+        while let Some(row) = lines.get(i).and_then(|l| parse_row(l)) {
+            rows.push(row);
+            i += 1;
+        }
+
+        if rows.len() != guesses.unwrap_or(GUESS_LIMIT) {
+            exit::fail(
+                exit::INVALID_INPUT,
+                format!(
+                    "{}: the grid has {} rows, not the {} its title says",
+                    line(title),
+                    rows.len(),
+                    guesses.unwrap_or(GUESS_LIMIT)
+                ),
+            );
+        }
+        let answer = match archive.get(&number) {
+            Some(answer) => *answer,
+            None => {
+                eprintln!(
+                    "warning: {}: puzzle {} isn't in the archive, so it's left out",
+                    line(title),
+                    number
+                );
+                date = None;
+                continue;
+            }
+        };
+        let game = Game::new(
+            date.take().unwrap_or_else(|| first_day.plus(number as i64)),
+            answer,
+            rows,
+        )
+        .or_invalid_input(|| line(title));
+        if game.solved_in() != guesses {
+            exit::fail(
+                exit::INVALID_INPUT,
+                format!("{}: the grid doesn't end as its title says", line(title)),
+            );
+        }
+        games.push(game);
+    }
+    games
+}
@@ -0,0 +1,110 @@
+//! Scores a few openers under each of several policies, against the same lists on the same
+//! threads, for comparing them side by side in one run.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::distribution::{Distribution, TRACKED_GUESSES};
+use crate::solver::{Policy, ProgressSink, SolverConfig, WordResult};
+use crate::{Word, GUESS_LIMIT};
+
+/// What one opener did under one policy.
+struct Entry {
+    distribution: Distribution,
+    elapsed: Duration,
+}
+
+/// Keeps each opener's result as it's scored.
+#[derive(Default)]
+struct Entries(Mutex<Vec<(Word, Entry)>>);
+
+impl ProgressSink for Entries {
+    fn word_finished(&self, _worker: usize, result: WordResult, elapsed: Duration) {
+        let distribution = result
+            .distribution
+            .expect("distributions aren't being kept");
+        self.0.lock().unwrap().push((
+            result.word,
+            Entry {
+                distribution,
+                elapsed,
+            },
+        ));
+    }
+}
+
+/// Prints a table for each of the average and success rate within the usual guess limit, the most
+/// guesses each opener can take, and how long each took to score, with a row per opener and a
+/// column per policy.  Each opener is searched without a guess limit, as for the limit report.
+pub fn run(
+    answers: Vec<Word>,
+    guesses: Vec<Word>,
+    openers: &[Word],
+    policies: &[Policy],
+    threads: usize,
+) {
+    let results = policies
+        .iter()
+        .map(|policy| {
+            println!(
+                "Scoring {} openers with the {} policy...",
+                openers.len(),
+                policy
+            );
+            let solver = SolverConfig::new(answers.clone(), guesses.clone())
+                .policy(*policy)
+                .threads(threads)
+                .keep_distributions(true)
+                .build();
+            let entries = Entries::default();
+            solver.score_all(openers.iter().copied(), &entries);
+            entries.0.into_inner().unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let entry = |opener: &Word, policy: usize| {
+        &results[policy]
+            .iter()
+            .find(|(w, _)| w == opener)
+            .expect("opener wasn't scored")
+            .1
+    };
+    let names = policies.iter().map(Policy::to_string).collect::<Vec<_>>();
+    let width = |i: usize| names[i].len().max(8);
+    let word_length = answers.first().map_or(0, Word::length);
+    let opener_width = "opener,".len().max(word_length + 1);
+
+    let print_table = |title: &str, cell: &dyn Fn(&Entry) -> String| {
+        println!();
+        println!("{}:", title);
+        let header = names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| format!("{:>w$}", n, w = width(i)))
+            .collect::<Vec<_>>();
+        println!("  {:w$} {}", "opener,", header.join(", "), w = opener_width);
+        for opener in openers {
+            let cells = (0..policies.len())
+                .map(|i| format!("{:>w$}", cell(entry(opener, i)), w = width(i)))
+                .collect::<Vec<_>>();
+            println!(
+                "  {:w$} {}",
+                format!("{},", opener),
+                cells.join(", "),
+                w = opener_width
+            );
+        }
+    };
+
+    print_table(&format!("Average within {} guesses", GUESS_LIMIT), &|e| {
+        format!("{:.3}", e.distribution.score(GUESS_LIMIT).0)
+    });
+    print_table(&format!("Success within {} guesses", GUESS_LIMIT), &|e| {
+        format!("{:.2}%", e.distribution.score(GUESS_LIMIT).1 * 100.0)
+    });
+    print_table("Most guesses", &|e| match e.distribution.worst() {
+        Some(worst) => worst.to_string(),
+        None => format!(">{}", TRACKED_GUESSES),
+    });
+    print_table("Time", &|e| format!("{:.3}s", e.elapsed.as_secs_f64()));
+}
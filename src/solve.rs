@@ -0,0 +1,344 @@
+//! Plays along with a game of Wordle being played somewhere else: it suggests a guess, reads back
+//! the feedback the game gave it, and suggests the next guess from the answers that are left, until
+//! the answer's found.  Once only a few answers are left, each suggestion also says what it risks
+//! with the guesses left, as `brutal finish` does.  The second guess after each feedback to an
+//! opener is kept in a [`Book`], for the next game with it.  A game can be saved partway through,
+//! to pick up later or pass on to someone else.  The feedback can be pasted from the grid Wordle
+//! shares, too, a row of squares at a time or the whole grid at once, for the guesses suggested.
+//! Feedback is shown as it's typed, like `crane ..y..`, or with symbols by each letter, like
+//! `c-r-a?n-e-`, for anyone who'd rather not tell colors apart.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::book::Book;
+use crate::clue::{self, Clue};
+use crate::exit;
+use crate::finish;
+use crate::games::Guess;
+use crate::lookup;
+use crate::share;
+use crate::solver::Solver;
+use crate::{get_pattern, solved_pattern, Word};
+
+/// The most answers left that are listed after each feedback.
+const LISTED: usize = 10;
+
+/// The most answers left for a suggestion to be labelled safe or risky, since finding out is a
+/// search of its own.
+const SAFETY_CHECKED: usize = 100;
+
+/// A game saved partway through, as `:save` writes it and `--load` reads it.
+#[derive(Default, Deserialize, Serialize)]
+struct Saved {
+    guesses: Vec<Played>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Played {
+    guess: String,
+    /// Like `gy..g`.
+    feedback: String,
+}
+
+/// The game so far.
+struct Game<'a> {
+    solver: &'a Solver,
+    /// Every word that can be guessed, in order.
+    words: Vec<Word>,
+    clues: Vec<Clue>,
+    left: Vec<Word>,
+    book: Option<Book>,
+    /// Whether feedback's shown with symbols rather than as it's typed.
+    symbols: bool,
+}
+
+impl<'a> Game<'a> {
+    fn new(solver: &'a Solver, symbols: bool) -> Self {
+        let guess_words = solver.guess_words();
+        let mut words = solver
+            .answers()
+            .iter()
+            .chain(guess_words.indices().iter().map(|i| guess_words.word(*i)))
+            .copied()
+            .collect::<Vec<_>>();
+        words.sort();
+        words.dedup();
+        Self {
+            solver,
+            words,
+            clues: Vec::new(),
+            left: solver.answers().to_vec(),
+            book: None,
+            symbols,
+        }
+    }
+
+    /// `guess` and the feedback it got.
+    fn show(&self, guess: &Word, pattern: usize) -> String {
+        if self.symbols {
+            clue::annotated(guess, pattern)
+        } else {
+            format!("{} {}", guess, clue::feedback(pattern, guess.length()))
+        }
+    }
+
+    /// Why `guess` can't get `pattern` next, if it can't.
+    fn check(&self, guess: &Word, pattern: usize) -> Result<(), String> {
+        if self.words.binary_search(guess).is_err() {
+            return Err(
+                match lookup::nearest(&guess.to_string(), &self.words, 1).first() {
+                    Some(nearest) => {
+                        format!("{} isn't in the lists (did you mean {}?)", guess, nearest)
+                    }
+                    None => format!("{} isn't in the lists", guess),
+                },
+            );
+        }
+        if self.solver.hard_mode() && !clue::keeps_to(guess, &self.clues) {
+            return Err(format!(
+                "{} doesn't keep to the clues so far, as hard mode needs",
+                guess
+            ));
+        }
+        if !self.left.iter().any(|a| get_pattern(a, guess) == pattern) {
+            return Err("None of the answers left would give that feedback".to_string());
+        }
+        Ok(())
+    }
+
+    fn play(&mut self, guess: Word, pattern: usize) {
+        if self.clues.is_empty() {
+            self.book = Book::open(self.solver, guess);
+        }
+        self.clues.push(Clue::Feedback { guess, pattern });
+        self.left.retain(|a| get_pattern(a, &guess) == pattern);
+    }
+
+    fn solved(&self) -> bool {
+        matches!(self.clues.last(), Some(Clue::Feedback { guess, pattern }) if *pattern == solved_pattern(guess.length()))
+    }
+
+    fn save(&self, filename: &Path) -> Result<(), String> {
+        let guesses = self
+            .clues
+            .iter()
+            .filter_map(|clue| match clue {
+                Clue::Feedback { guess, pattern } => Some(Played {
+                    guess: guess.to_string(),
+                    feedback: clue::feedback(*pattern, guess.length()),
+                }),
+                _ => None,
+            })
+            .collect();
+        let json = serde_json::to_string_pretty(&Saved { guesses }).map_err(|e| e.to_string())?;
+        fs::write(filename, json + "\n").map_err(|e| format!("{}: {}", filename.display(), e))
+    }
+
+    /// Plays the guesses saved in `filename`, as if they'd been typed in.
+    fn load(&mut self, filename: &Path) -> Result<(), String> {
+        let location = |e: &dyn std::fmt::Display| format!("{}: {}", filename.display(), e);
+        let json = fs::read_to_string(filename).map_err(|e| location(&e))?;
+        let saved = serde_json::from_str::<Saved>(&json).map_err(|e| location(&e))?;
+        for played in saved.guesses {
+            if self.solved() {
+                return Err(location(&"there are guesses after the answer was found"));
+            }
+            let guess = played
+                .guess
+                .parse::<Word>()
+                .map_err(|e| location(&format!("{}: {}", e, played.guess)))?;
+            let pattern =
+                clue::pattern(&played.feedback, guess.length()).map_err(|e| location(&e))?;
+            self.check(&guess, pattern).map_err(|e| location(&e))?;
+            self.play(guess, pattern);
+        }
+        Ok(())
+    }
+}
+
+/// A line of input: the feedback for the suggested guess, like `gy..g` or a row of a shared grid,
+/// or a guess that was played instead and its feedback, like `crane gy..g`.
+fn parse_line(line: &str, suggested: Word) -> Result<(Word, usize), String> {
+    let (guess, feedback) = match line.split_once(char::is_whitespace) {
+        Some((guess, feedback)) => (
+            guess.parse().map_err(|e| format!("{}: {}", e, guess))?,
+            feedback.trim(),
+        ),
+        None => (suggested, line),
+    };
+    let pattern = match share::parse_row(feedback) {
+        Some(Guess::Feedback { pattern, length }) if length == guess.length() => pattern,
+        Some(Guess::Feedback { length, .. }) => {
+            return Err(format!(
+                "the row has {} squares, but {} has {} letters",
+                length,
+                guess,
+                guess.length()
+            ))
+        }
+        _ => clue::pattern(feedback, guess.length())?,
+    };
+    Ok((guess, pattern))
+}
+
+/// Suggests guesses for a game for one of `solver`'s answers, played with any of them or its
+/// guesses, by its rules, starting with `opener` if there's one, or else carrying on from the game
+/// saved in `load`, and searching each for at most `budget`.  Feedback's shown with `symbols` if
+/// asked.
+pub fn run(
+    solver: &Solver,
+    opener: Option<Word>,
+    load: Option<&Path>,
+    budget: Duration,
+    symbols: bool,
+) {
+    let guess_words = solver.guess_words();
+    let weights = guess_words
+        .indices()
+        .iter()
+        .map(|i| (*guess_words.word(*i), guess_words.weight(*i)))
+        .collect::<HashMap<_, _>>();
+    let guesses = guess_words
+        .indices()
+        .iter()
+        .map(|i| *guess_words.word(*i))
+        .collect::<Vec<_>>();
+    // The answers are words anyone would know, so a guess less familiar than all of them isn't.
+    let familiar = solver
+        .answers()
+        .iter()
+        .filter_map(|a| weights.get(a))
+        .copied()
+        .fold(f32::INFINITY, f32::min);
+
+    let mut game = Game::new(solver, symbols);
+    if let Some(load) = load {
+        game.load(load)
+            .unwrap_or_else(|e| exit::fail(exit::INVALID_INPUT, e));
+    }
+
+    println!("Type the feedback for each guess, like gy..g with g for green, y for yellow and . or b for gray,");
+    println!("or the guess played and its feedback, like crane gy..g, :save <FILE> to save the game, or quit to stop.");
+    println!("The rows of a shared grid can be pasted as the feedback, too.");
+    if solver.hard_mode() {
+        println!("Playing by hard mode's rules.");
+    }
+    if !game.clues.is_empty() {
+        let played = game
+            .clues
+            .iter()
+            .filter_map(|clue| match clue {
+                Clue::Feedback { guess, pattern } => Some(game.show(guess, *pattern)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        println!();
+        println!("Carrying on from {}", played.join(", "));
+    }
+    if game.solved() {
+        println!("Solved in {} guesses.", game.clues.len());
+        return;
+    }
+    let mut input = io::stdin().lock().lines();
+    loop {
+        let played = game.clues.len();
+        // The book's only for the guess after the opener.
+        let after_opener = match game.clues.as_slice() {
+            [Clue::Feedback { pattern, .. }] => Some(*pattern),
+            _ => None,
+        };
+        let suggestion = match opener.filter(|_| played == 0) {
+            Some(opener) => opener,
+            None => match game
+                .book
+                .as_ref()
+                .zip(after_opener)
+                .and_then(|(b, p)| b.get(p))
+            {
+                Some(entry) if entry.exact => entry.guess,
+                _ => match solver.suggest(&game.clues, Some(budget), |_| {}) {
+                    Some(suggestion) => {
+                        if let (Some(book), Some(pattern)) = (&mut game.book, after_opener) {
+                            book.insert(pattern, &suggestion);
+                        }
+                        suggestion.guess
+                    }
+                    None => {
+                        println!("There are no answers left.");
+                        return;
+                    }
+                },
+            },
+        };
+        let left = &game.left;
+        let mut notes = vec![match left.len() {
+            1 => "the only answer left".to_string(),
+            count => format!("with {} answers left", count),
+        }];
+        if left.len() <= SAFETY_CHECKED {
+            let guesses_left = solver.guess_limit().saturating_sub(played);
+            notes.push(finish::safety(left, &guesses, &suggestion, guesses_left).to_string());
+        }
+        if weights.get(&suggestion).is_some_and(|w| *w < familiar) {
+            notes.push("less familiar than any answer".to_string());
+        }
+        println!();
+        println!("Guess {}: {}, {}", played + 1, suggestion, notes.join(", "));
+
+        let (guess, pattern) = loop {
+            print!("Feedback: ");
+            io::stdout().flush().expect("cannot write prompt");
+            let line = match input.next() {
+                Some(line) => line.expect("cannot read feedback"),
+                None => {
+                    println!();
+                    return;
+                }
+            };
+            // The file name keeps its case; nothing else needs to.
+            let raw = line.trim();
+            if let Some(filename) = raw.strip_prefix(":save") {
+                match filename.trim() {
+                    "" => println!("Save the game to which file?"),
+                    filename => match game.save(Path::new(filename)) {
+                        Ok(()) => println!("Saved the game to {}.", filename),
+                        Err(e) => println!("{}", e),
+                    },
+                }
+                continue;
+            }
+            let line = raw.to_lowercase();
+            if line == "quit" {
+                return;
+            }
+            // A pasted grid's title, and the blank line after it, say nothing about the feedback.
+            if line.is_empty() || share::parse_title(raw).is_some() {
+                continue;
+            }
+            // The answers are left as they were, in case it was mistyped.
+            match parse_line(&line, suggestion)
+                .and_then(|(guess, pattern)| game.check(&guess, pattern).map(|_| (guess, pattern)))
+            {
+                Ok(played) => break played,
+                Err(e) => println!("{}", e),
+            }
+        };
+
+        println!("Played {}", game.show(&guess, pattern));
+        game.play(guess, pattern);
+        if game.solved() {
+            println!("Solved in {} guesses.", played + 1);
+            return;
+        }
+        if game.left.len() <= LISTED {
+            let listed = game.left.iter().map(Word::to_string).collect::<Vec<_>>();
+            println!("Answers left: {}", listed.join(", "));
+        }
+    }
+}
@@ -0,0 +1,665 @@
+//! Scores openers against the answers, spread over as many threads as it's given.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::clue::{self, Clue};
+use crate::distribution::{self, Distribution};
+use crate::failures::FailureLog;
+use crate::finish::{self, Suggestion};
+use crate::frequency::WordFrequencies;
+use crate::memo::Memo;
+use crate::race::{self, Race};
+use crate::sample::Sample;
+use crate::word_list::WordList;
+use crate::{combine_scores, score_second_guess, score_word, SearchState, Word, GUESS_LIMIT};
+
+/// A scored opener.
+pub struct WordResult {
+    pub word: Word,
+    pub score: (f32, f32),
+    /// Its score against each answer, if they're being kept.
+    pub answer_scores: Option<Vec<(f32, f32)>>,
+    /// Why it failed to find the answers it did, if that's being recorded.
+    pub failures: Option<FailureLog>,
+    /// Which guess it finds the answers on, if that's being kept.
+    pub distribution: Option<Distribution>,
+    /// Which guess it finds each answer on, if that's being kept.
+    pub answer_distributions: Option<Vec<Distribution>>,
+    /// How much searching it took, if it was searched rather than recovered.
+    pub search: Option<SearchStats>,
+}
+
+/// How big a search was, which is a steadier measure of the work in it than the time it took.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SearchStats {
+    /// Positions visited, one for each guess considered.
+    pub nodes: u64,
+    /// Positions whose score was known without searching any deeper.
+    pub leaves: u64,
+}
+
+impl SearchStats {
+    fn add(&mut self, other: SearchStats) {
+        self.nodes += other.nodes;
+        self.leaves += other.leaves;
+    }
+}
+
+/// What a worker did over the course of a `score_all`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WorkerStats {
+    /// Openers scored, not counting any that were cancelled.
+    pub words: usize,
+    /// Openers dropped partway through by the race.
+    pub dropped: usize,
+    /// Time spent scoring them, rather than waiting on the others to finish.
+    pub busy: Duration,
+    pub search: SearchStats,
+    /// Heap allocations made inside the search, if they're counted.
+    pub allocations: usize,
+}
+
+/// How the follow-up guesses are chosen.  They're only ever words still possible, which hard mode
+/// always allows, so the games are the same in hard mode and the scores hold for it too.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Policy {
+    /// Any of the words still possible, at random, so every one of them is searched.
+    #[default]
+    Random,
+    /// The most informative of the words still possible while more than this many remain, so only
+    /// that one is searched, and then any of them at random.
+    Hybrid(usize),
+    /// Any of the answers still possible, at random and all equally likely, whatever the guess
+    /// list and its weights.  With the answers as the guess list this is the same as `Random`, so
+    /// it's the model brutal's numbers assume by default.
+    Baseline,
+}
+
+impl FromStr for Policy {
+    type Err = String;
+
+    /// Parses `random`, `hybrid:<N>`, `greedy`, which is `hybrid:0`, or `baseline`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "random" => Ok(Self::Random),
+            None if s == "greedy" => Ok(Self::Hybrid(0)),
+            None if s == "baseline" => Ok(Self::Baseline),
+            None if s == "optimal" => Err(
+                "there's no optimal policy for whole games, only for endgames, with `brutal finish`"
+                    .to_string(),
+            ),
+            Some(("hybrid", threshold)) => threshold
+                .parse()
+                .map(Self::Hybrid)
+                .map_err(|_| format!("invalid hybrid threshold: {}", threshold)),
+            _ => Err(format!("unknown policy: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Random => write!(f, "random"),
+            Self::Hybrid(threshold) => write!(f, "hybrid:{}", threshold),
+            Self::Baseline => write!(f, "baseline"),
+        }
+    }
+}
+
+/// Asks a running search to stop early, from any thread.  Clones of a token share it, so one can
+/// be kept to cancel with while another is given to the solver.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Hears what the workers are up to as they score openers, from whichever thread is doing the
+/// work.  Workers are numbered from 0, with 0 being the calling thread.
+pub trait ProgressSink: Sync {
+    /// A worker has started scoring `word` against `answer_count` answers.
+    fn word_started(&self, _worker: usize, _word: &Word, _answer_count: usize) {}
+
+    /// A worker is about to score its opener, `word`, against `answer`.
+    fn answer_started(&self, _worker: usize, _word: &Word, _answer: &Word) {}
+
+    /// A worker has finished scoring an opener, which took `elapsed`.  That's a fair guess at how
+    /// long the next one will take, for estimating how much longer the rest will.
+    fn word_finished(&self, _worker: usize, _result: WordResult, _elapsed: Duration) {}
+
+    /// A worker has dropped `word` partway through, since it can't make the top of the race.
+    fn word_dropped(&self, _worker: usize, _word: &Word) {}
+
+    /// A worker has run out of openers to score.
+    fn worker_finished(&self, _worker: usize, _stats: &WorkerStats) {}
+}
+
+/// For when nobody's listening.
+impl ProgressSink for () {}
+
+pub struct SolverConfig {
+    answers: Vec<Word>,
+    guesses: Vec<Word>,
+    guess_weights: Option<WordFrequencies>,
+    answer_weights: Option<WordFrequencies>,
+    first_guesses: Option<Vec<(Word, f32)>>,
+    policy: Policy,
+    hard_mode: bool,
+    guess_limit: usize,
+    unlimited: bool,
+    threads: usize,
+    keep_answer_scores: bool,
+    record_failures: bool,
+    keep_distributions: bool,
+    keep_answer_distributions: bool,
+    race: Option<(usize, f32, u64)>,
+    sample: Option<(usize, u64)>,
+    memo: bool,
+    cancellation: CancellationToken,
+}
+
+impl SolverConfig {
+    pub fn new(answers: Vec<Word>, guesses: Vec<Word>) -> Self {
+        Self {
+            answers,
+            guesses,
+            guess_weights: None,
+            answer_weights: None,
+            first_guesses: None,
+            policy: Policy::Random,
+            hard_mode: false,
+            guess_limit: GUESS_LIMIT,
+            unlimited: false,
+            threads: 1,
+            keep_answer_scores: false,
+            record_failures: false,
+            keep_distributions: false,
+            keep_answer_distributions: false,
+            race: None,
+            sample: None,
+            memo: false,
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// Weights the follow-up guesses, rather than treating them all as equally likely.
+    pub fn guess_weights(mut self, weights: Option<WordFrequencies>) -> Self {
+        self.guess_weights = weights;
+        self
+    }
+
+    /// Weights the answers, so the scores are how an opener does on the answers that are likelier
+    /// to come up, rather than on all of them alike.  They don't change how the games are played,
+    /// in hard mode or not, only how much each answer's games count for.  Not for races.
+    pub fn answer_weights(mut self, weights: Option<WordFrequencies>) -> Self {
+        self.answer_weights = weights;
+        self
+    }
+
+    /// Scores openers as second guesses instead, after a first guess drawn from `first_guesses` by
+    /// weight.
+    pub fn first_guesses(mut self, first_guesses: Option<Vec<(Word, f32)>>) -> Self {
+        self.first_guesses = first_guesses;
+        self
+    }
+
+    pub fn policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Plays by hard mode's rules, where every guess after the opener keeps to the greens and
+    /// yellows revealed so far.  The follow-up guesses always do, whatever the policy, so the games
+    /// and scores are the same; it's kept so the results can say which rules they're for.
+    pub fn hard_mode(mut self, hard_mode: bool) -> Self {
+        self.hard_mode = hard_mode;
+        self
+    }
+
+    /// Ends each game after `limit` guesses rather than the usual six, so the success rate is the
+    /// chance of finding the answer within that many.
+    pub fn guess_limit(mut self, limit: usize) -> Self {
+        self.guess_limit = limit;
+        self
+    }
+
+    /// Lets each game go on until the answer's found, rather than stopping at the guess limit, so
+    /// every opener always succeeds.
+    pub fn unlimited(mut self, unlimited: bool) -> Self {
+        self.unlimited = unlimited;
+        self
+    }
+
+    /// The calling thread is one of them, so there's always at least one.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Keeps each opener's score against each answer, not just its overall score.
+    pub fn keep_answer_scores(mut self, keep: bool) -> Self {
+        self.keep_answer_scores = keep;
+        self
+    }
+
+    /// Records why each opener fails to find the answers it does.
+    pub fn record_failures(mut self, record: bool) -> Self {
+        self.record_failures = record;
+        self
+    }
+
+    /// Keeps which guess each opener finds the answers on, searching without a guess limit to find
+    /// it, and working out the scores at the limit from that.  Not for second guesses, and the
+    /// limit can't be over [`distribution::TRACKED_GUESSES`], or failures recorded.
+    pub fn keep_distributions(mut self, keep: bool) -> Self {
+        self.keep_distributions = keep;
+        self
+    }
+
+    /// Keeps which guess each opener finds each answer on, as well, which means keeping
+    /// distributions.
+    pub fn keep_answer_distributions(mut self, keep: bool) -> Self {
+        self.keep_answer_distributions = keep;
+        self
+    }
+
+    /// Races the openers for the `top` best averages, dropping the ones more than `confidence`
+    /// standard errors behind, with the answers in an order from `seed`.  Not with distributions.
+    pub fn race(mut self, top: Option<usize>, confidence: f32, seed: u64) -> Self {
+        self.race = top.map(|top| (top, confidence, seed));
+        self
+    }
+
+    /// Scores each opener against only `count` of the answers, chosen with `seed`, for approximate
+    /// scores.  Not with distributions, and the openers aren't raced.
+    pub fn sample(mut self, count: Option<usize>, seed: u64) -> Self {
+        self.sample = count.map(|count| (count, seed));
+        self
+    }
+
+    /// Remembers the endgames the searches score, for any other search that reaches them.  Not with
+    /// distributions, which search their own way.
+    pub fn memo(mut self, memo: bool) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    /// Stops the solver's searches once `token` is cancelled.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// The answers and guesses have to have as many letters as each other.
+    pub fn build(self) -> Solver {
+        let answer_count = self.answers.len();
+        let guess_words = match self.policy {
+            Policy::Baseline => WordList::new(&self.answers, None),
+            _ => WordList::new(&self.guesses, self.guess_weights.as_ref()),
+        };
+        let word_length = self.answers.first().map_or(0, Word::length);
+        assert!(
+            self.answers.iter().all(|w| w.length() == word_length)
+                && (guess_words.is_empty() || guess_words.word_length() == word_length),
+            "answers and guesses of different lengths"
+        );
+        let guess_limit = if self.unlimited {
+            usize::MAX
+        } else {
+            self.guess_limit
+        };
+        assert!(
+            self.answer_weights.is_none() || self.race.is_none(),
+            "raced answers can't be weighted"
+        );
+        let answer_weights = self
+            .answer_weights
+            .map(|f| self.answers.iter().map(|a| f.get(a)).collect());
+        Solver {
+            memo: self
+                .memo
+                .then(|| Arc::new(Memo::new(&guess_words, guess_limit, self.policy))),
+            guess_words,
+            answers: self.answers,
+            answer_weights,
+            word_length,
+            first_guesses: self.first_guesses,
+            policy: self.policy,
+            hard_mode: self.hard_mode,
+            guess_limit,
+            threads: self.threads,
+            keep_answer_scores: self.keep_answer_scores,
+            record_failures: self.record_failures,
+            keep_distributions: self.keep_distributions || self.keep_answer_distributions,
+            keep_answer_distributions: self.keep_answer_distributions,
+            race: self
+                .race
+                .map(|(top, confidence, seed)| Race::new(top, confidence, seed, answer_count)),
+            sample: self
+                .sample
+                .map(|(count, seed)| Sample::new(count, seed, answer_count)),
+            cancellation: self.cancellation,
+        }
+    }
+}
+
+pub struct Solver {
+    answers: Vec<Word>,
+    /// Each answer's weight, in the same order.
+    answer_weights: Option<Vec<f32>>,
+    /// The letters every word has.
+    word_length: usize,
+    guess_words: WordList,
+    first_guesses: Option<Vec<(Word, f32)>>,
+    policy: Policy,
+    hard_mode: bool,
+    guess_limit: usize,
+    threads: usize,
+    keep_answer_scores: bool,
+    record_failures: bool,
+    keep_distributions: bool,
+    keep_answer_distributions: bool,
+    race: Option<Race>,
+    sample: Option<Sample>,
+    memo: Option<Arc<Memo>>,
+    cancellation: CancellationToken,
+}
+
+/// How scoring an opener turned out.
+enum Scored {
+    Finished(WordResult),
+    /// Dropped by the race.
+    Dropped,
+    Cancelled,
+}
+
+impl Solver {
+    pub fn answers(&self) -> &[Word] {
+        &self.answers
+    }
+
+    /// Each of the answers' weight, if they're weighted.
+    pub fn answer_weights(&self) -> Option<&[f32]> {
+        self.answer_weights.as_deref()
+    }
+
+    pub fn guess_words(&self) -> &WordList {
+        &self.guess_words
+    }
+
+    /// The letters every word has.
+    pub fn word_length(&self) -> usize {
+        self.word_length
+    }
+
+    pub fn first_guesses(&self) -> Option<&[(Word, f32)]> {
+        self.first_guesses.as_deref()
+    }
+
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    pub fn hard_mode(&self) -> bool {
+        self.hard_mode
+    }
+
+    /// `usize::MAX` if there's no limit.
+    pub fn guess_limit(&self) -> usize {
+        self.guess_limit
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn race(&self) -> Option<&Race> {
+        self.race.as_ref()
+    }
+
+    pub fn sample(&self) -> Option<&Sample> {
+        self.sample.as_ref()
+    }
+
+    pub fn memo(&self) -> Option<&Memo> {
+        self.memo.as_deref()
+    }
+
+    /// The best guess to make in a game whose feedback so far is `clues`, choosing from the answers
+    /// that fit them and the guesses, that can be found within `budget`, if there's one.  In hard
+    /// mode, only guesses that keep to the clues are suggested, though the rest of the game after
+    /// it isn't held to them.  Of equally good guesses, the more familiar is suggested if they're
+    /// weighted, and otherwise the first in the guess list.  `refined` hears of each better guess
+    /// found along the way.  `None` if no answers fit.
+    pub fn suggest(
+        &self,
+        clues: &[Clue],
+        budget: Option<Duration>,
+        refined: impl FnMut(&Suggestion),
+    ) -> Option<Suggestion> {
+        let answers = clue::enumerate_answers(&self.answers, clues).collect::<Vec<_>>();
+        let mut guesses = self
+            .guess_words
+            .indices()
+            .iter()
+            .filter(|i| !self.hard_mode || clue::keeps_to(self.guess_words.word(**i), clues))
+            .map(|i| (*self.guess_words.word(*i), self.guess_words.weight(*i)))
+            .collect::<Vec<_>>();
+        guesses.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        let guesses = guesses.into_iter().map(|(w, _)| w).collect::<Vec<_>>();
+        finish::suggest(&answers, &guesses, budget, refined)
+    }
+
+    /// Scores a single opener, on the calling thread, unless it's cancelled first.
+    pub fn score(&self, word: &Word) -> Option<WordResult> {
+        let mut state = self.search_state();
+        match self.score_with(word, &mut state, |_| ()) {
+            Scored::Finished(result) => Some(result),
+            Scored::Dropped | Scored::Cancelled => None,
+        }
+    }
+
+    /// Scores every opener in `words`, telling `progress` how it's going.  Once it's cancelled,
+    /// the openers being scored are dropped without being reported, and the rest aren't started.
+    /// Returns what each worker did, in order.
+    pub fn score_all(
+        &self,
+        words: impl Iterator<Item = Word> + Send,
+        progress: &impl ProgressSink,
+    ) -> Vec<WorkerStats> {
+        let words = Mutex::new(words);
+        let next = || words.lock().unwrap().next();
+        let next = &next;
+
+        thread::scope(|scope| {
+            let workers = (1..self.threads)
+                .map(|worker| scope.spawn(move || self.work(worker, next, progress)))
+                .collect::<Vec<_>>();
+
+            let first = self.work(0, next, progress);
+            std::iter::once(first)
+                .chain(workers.into_iter().map(|w| w.join().unwrap()))
+                .collect()
+        })
+    }
+
+    fn work(
+        &self,
+        worker: usize,
+        next: &impl Fn() -> Option<Word>,
+        progress: &impl ProgressSink,
+    ) -> WorkerStats {
+        let mut state = self.search_state();
+        let mut stats = WorkerStats::default();
+
+        while let Some(word) = (!self.cancellation.is_cancelled()).then(next).flatten() {
+            let started = Instant::now();
+            let answer_count = self
+                .sample
+                .as_ref()
+                .map_or(self.answers.len(), Sample::count);
+            progress.word_started(worker, &word, answer_count);
+            let result = self.score_with(&word, &mut state, |answer| {
+                progress.answer_started(worker, &word, answer)
+            });
+            let elapsed = started.elapsed();
+            stats.busy += elapsed;
+            match result {
+                Scored::Finished(result) => {
+                    stats.words += 1;
+                    stats.search.add(result.search.unwrap_or_default());
+                    if let Some(race) = &self.race {
+                        race.enter(result.score.0);
+                    }
+                    progress.word_finished(worker, result, elapsed);
+                }
+                Scored::Dropped => {
+                    stats.dropped += 1;
+                    progress.word_dropped(worker, &word);
+                }
+                Scored::Cancelled => (),
+            }
+        }
+
+        stats.allocations = state.allocations;
+        progress.worker_finished(worker, &stats);
+        stats
+    }
+
+    fn search_state(&self) -> SearchState {
+        let guess_limit = if self.keep_distributions {
+            usize::MAX
+        } else {
+            self.guess_limit
+        };
+        let mut state = SearchState::new(self.guess_words.len(), guess_limit);
+        state.cancellation = Some(self.cancellation.clone());
+        state.memo = self.memo.clone();
+        if let Policy::Hybrid(threshold) = self.policy {
+            state.greedy_above = threshold;
+        }
+        state
+    }
+
+    fn score_with(
+        &self,
+        word: &Word,
+        state: &mut SearchState,
+        mut on_answer: impl FnMut(&Word),
+    ) -> Scored {
+        if self.record_failures {
+            state.failures = Some(FailureLog::default());
+        }
+
+        let started = state.search;
+        let mut distribution = None;
+        let mut answer_distributions = None;
+        let mut weights = None;
+        let answer_scores = match &self.first_guesses {
+            _ if self.keep_distributions => {
+                let distributions = distribution::score_word(
+                    word,
+                    &self.answers,
+                    &self.guess_words,
+                    state,
+                    on_answer,
+                );
+                distribution = Some(Distribution::weighted_mean(
+                    &distributions,
+                    self.answer_weights(),
+                ));
+                let answer_scores = distributions
+                    .iter()
+                    .map(|d| d.score(self.guess_limit))
+                    .collect();
+                answer_distributions = self.keep_answer_distributions.then_some(distributions);
+                answer_scores
+            }
+            _ => {
+                let mut score =
+                    |answers: &[Word], state: &mut SearchState| match &self.first_guesses {
+                        Some(first_guesses) => score_second_guess(
+                            word,
+                            first_guesses,
+                            answers,
+                            &self.guess_words,
+                            state,
+                            &mut on_answer,
+                        ),
+                        None => score_word(word, answers, &self.guess_words, state, &mut on_answer),
+                    };
+                match (&self.sample, &self.race) {
+                    (Some(sample), _) => {
+                        let (chosen, mut sample_weights) = sample.choose(word, &self.answers);
+                        let answers = chosen.iter().map(|i| self.answers[*i]).collect::<Vec<_>>();
+                        if let Some(answer_weights) = self.answer_weights() {
+                            for (weight, i) in sample_weights.iter_mut().zip(&chosen) {
+                                *weight *= answer_weights[*i];
+                            }
+                        }
+                        weights = Some(sample_weights);
+                        score(&answers, state)
+                    }
+                    (None, None) => score(&self.answers, state),
+                    (None, Some(race)) => {
+                        // The answers are played in the race's order, a batch at a time, and their
+                        // scores put back in the usual order once they're all in.
+                        let mut raced = Vec::with_capacity(self.answers.len());
+                        for batch in race.order().chunks(race::BATCH) {
+                            let answers =
+                                batch.iter().map(|i| self.answers[*i]).collect::<Vec<_>>();
+                            raced.extend(score(&answers, state));
+                            if state.cancelled {
+                                return Scored::Cancelled;
+                            }
+                            if race.is_out(&raced) {
+                                return Scored::Dropped;
+                            }
+                        }
+                        let mut answer_scores = vec![(0.0, 0.0); self.answers.len()];
+                        for (i, score) in race.order().iter().zip(raced) {
+                            answer_scores[*i] = score;
+                        }
+                        answer_scores
+                    }
+                }
+            }
+        };
+        if state.cancelled {
+            return Scored::Cancelled;
+        }
+
+        Scored::Finished(WordResult {
+            word: *word,
+            score: combine_scores(
+                &answer_scores,
+                weights.as_deref().or_else(|| self.answer_weights()),
+            ),
+            answer_scores: self.keep_answer_scores.then_some(answer_scores),
+            failures: state.failures.take(),
+            distribution,
+            answer_distributions,
+            search: Some(SearchStats {
+                nodes: state.search.nodes - started.nodes,
+                leaves: state.search.leaves - started.leaves,
+            }),
+        })
+    }
+}
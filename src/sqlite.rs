@@ -0,0 +1,177 @@
+//! A store in a SQLite database, so that a server, or the runs on one machine, can keep everything
+//! in one file that's safe to use from several processes at once, rather than a directory of them.
+//! It's named like `sqlite:<FILE>`, and the file and its table are made if they aren't there.
+//!
+//! Each key is a row, and a log adds to its row's contents in place, a transaction at a time, so
+//! runs can add to the same key at once without losing each other's lines.
+//!
+//! The player's own [`Games`] are kept in a database too, in a table of their own.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::storage::{Log, Storage};
+
+/// How long to wait for another process to finish with the database before giving up.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct Database {
+    path: PathBuf,
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl Database {
+    pub fn open(path: impl Into<PathBuf>) -> rusqlite::Result<Self> {
+        let path = path.into();
+        let connection = Connection::open(&path)?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS kept (key TEXT PRIMARY KEY, contents BLOB NOT NULL)",
+            [],
+        )?;
+        Ok(Self {
+            path,
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+}
+
+/// The player's games, a row to a day: its date, like `2022-01-31`, its answer, and its guesses
+/// separated by spaces.
+pub struct Games {
+    connection: Connection,
+}
+
+impl Games {
+    /// Opens the games in the database at `path`, making the file and the table if they aren't
+    /// there.
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS games \
+             (date TEXT PRIMARY KEY, answer TEXT NOT NULL, guesses TEXT NOT NULL)",
+            [],
+        )?;
+        Ok(Self { connection })
+    }
+
+    /// Every game's date, answer and guesses, by date.
+    pub fn read(&self) -> rusqlite::Result<Vec<[String; 3]>> {
+        self.connection
+            .prepare("SELECT date, answer, guesses FROM games ORDER BY date")?
+            .query_map([], |r| Ok([r.get(0)?, r.get(1)?, r.get(2)?]))?
+            .collect()
+    }
+
+    /// Adds all of `games` or, if any is on a day that already has one, none of them.
+    pub fn add(&mut self, games: &[[String; 3]]) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        for [date, answer, guesses] in games {
+            transaction.execute(
+                "INSERT INTO games (date, answer, guesses) VALUES (?1, ?2, ?3)",
+                params![date, answer, guesses],
+            )?;
+        }
+        transaction.commit()
+    }
+}
+
+/// The row `key` is kept in, by the same name on every platform.
+fn row(key: &Path) -> String {
+    key.iter()
+        .map(|c| c.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl Storage for Database {
+    fn read(&self, key: &Path) -> io::Result<Option<Vec<u8>>> {
+        self.connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT contents FROM kept WHERE key = ?1",
+                [row(key)],
+                |r| r.get(0),
+            )
+            .optional()
+            .map_err(io::Error::other)
+    }
+
+    fn write(&self, key: &Path, contents: &[u8]) -> io::Result<()> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT OR REPLACE INTO kept (key, contents) VALUES (?1, ?2)",
+                params![row(key), contents],
+            )
+            .map(drop)
+            .map_err(io::Error::other)
+    }
+
+    fn append(&self, key: &Path) -> io::Result<Box<dyn Log>> {
+        Ok(Box::new(Appender {
+            connection: Arc::clone(&self.connection),
+            key: row(key),
+            pending: Vec::new(),
+        }))
+    }
+
+    fn locate(&self, key: &Path) -> String {
+        format!("{}, {}", self.path.display(), row(key))
+    }
+}
+
+/// Adds to the end of a row, holding what's written until it's flushed or synced, since each
+/// addition is a transaction of its own.
+struct Appender {
+    connection: Arc<Mutex<Connection>>,
+    key: String,
+    pending: Vec<u8>,
+}
+
+impl Write for Appender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO kept (key, contents) VALUES (?1, ?2) \
+                 ON CONFLICT (key) DO UPDATE SET contents = CAST(contents || excluded.contents AS BLOB)",
+                params![self.key, self.pending],
+            )
+            .map_err(io::Error::other)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl Log for Appender {
+    /// Each transaction's already on disk once it's committed.
+    fn sync(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+impl Drop for Appender {
+    /// Keeps what was written since the last sync, as a file would, if it can.
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            eprintln!("warning: cannot write to {}: {}", self.key, e);
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Facts about the shape of the lists themselves, before any searching: how much there is to find
+//! out about the answers, how much the feedback for an opener can tell, and how many answers share
+//! their letters.  They're the numbers a writeup about the lists starts with.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use crate::{get_pattern, pattern_count, Word};
+
+/// The bucket sizes the openers' partitions are counted in, as the smallest in each.
+const SIZES: [usize; 5] = [1, 2, 6, 21, 101];
+
+/// How an opener splits the answers by feedback.
+struct Split {
+    opener: Word,
+    buckets: usize,
+    /// The information its feedback gives, on average.
+    bits: f64,
+    /// The answers it leaves, on average.
+    expected: f64,
+    largest: usize,
+    /// How many of its buckets are of each of [`SIZES`].
+    sizes: [usize; SIZES.len()],
+}
+
+impl Split {
+    fn new(opener: Word, answers: &[Word], realized: &mut [bool]) -> Self {
+        let mut counts = vec![0usize; pattern_count(opener.length())];
+        for answer in answers {
+            counts[get_pattern(answer, &opener)] += 1;
+        }
+
+        let total = answers.len() as f64;
+        let mut split = Self {
+            opener,
+            buckets: 0,
+            bits: 0.0,
+            expected: 0.0,
+            largest: 0,
+            sizes: [0; SIZES.len()],
+        };
+        for (pattern, count) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+            realized[pattern] = true;
+            let p = *count as f64 / total;
+            split.buckets += 1;
+            split.bits -= p * p.log2();
+            split.expected += p * *count as f64;
+            split.largest = split.largest.max(*count);
+            split.sizes[SIZES.iter().rposition(|s| count >= s).unwrap()] += 1;
+        }
+        split
+    }
+}
+
+/// The groups of words with the same letters, biggest first.
+fn anagram_classes(words: &[Word]) -> Vec<Vec<Word>> {
+    let mut classes = HashMap::<_, Vec<Word>>::new();
+    for word in words {
+        let mut letters = *word;
+        letters.letters_mut().sort_unstable();
+        classes.entry(letters).or_default().push(*word);
+    }
+    let mut classes = classes.into_values().collect::<Vec<_>>();
+    classes.iter_mut().for_each(|c| c.sort());
+    classes.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    classes
+}
+
+/// Writes the facts about `answers` and the words that can be guessed, them and `guesses`, with
+/// how the `openers` best and worst at splitting the answers do it.
+pub fn write(
+    out: &mut impl Write,
+    answers: &[Word],
+    guesses: &[Word],
+    openers: usize,
+) -> io::Result<()> {
+    let mut answers = answers.to_vec();
+    answers.sort();
+    answers.dedup();
+    let mut candidates = answers.clone();
+    candidates.extend(guesses);
+    candidates.sort();
+    candidates.dedup();
+
+    writeln!(
+        out,
+        "Answers: {}, guesses: {}, of which {} are answers",
+        answers.len(),
+        candidates.len(),
+        answers.len()
+    )?;
+    if answers.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "Entropy of the answers: {:.3} bits, each as likely as any other",
+        (answers.len() as f64).log2()
+    )?;
+
+    for (name, words) in [("answers", &answers), ("guesses", &candidates)] {
+        let classes = anagram_classes(words);
+        let shared = classes.iter().filter(|c| c.len() > 1).collect::<Vec<_>>();
+        let largest = classes[0].iter().map(Word::to_string).collect::<Vec<_>>();
+        writeln!(
+            out,
+            "Anagram classes among the {}: {}, {} of them shared by {} words; largest: {}",
+            name,
+            classes.len(),
+            shared.len(),
+            shared.iter().map(|c| c.len()).sum::<usize>(),
+            largest.join(" ")
+        )?;
+    }
+
+    let patterns = pattern_count(answers.first().map_or(0, Word::length));
+    let mut realized = vec![false; patterns];
+    let mut splits = candidates
+        .iter()
+        .map(|c| Split::new(*c, &answers, &mut realized))
+        .collect::<Vec<_>>();
+    writeln!(
+        out,
+        "Feedback patterns that some guess gets for some answer: {} of {}",
+        realized.iter().filter(|r| **r).count(),
+        patterns
+    )?;
+
+    // Most informative first.
+    splits.sort_by(|a, b| {
+        b.bits
+            .partial_cmp(&a.bits)
+            .unwrap_or(Ordering::Equal)
+            .then(a.opener.cmp(&b.opener))
+    });
+    let openers = openers.min(splits.len());
+    for (title, splits) in [
+        ("Best openers", &splits[..openers]),
+        ("Worst openers", &splits[splits.len() - openers..]),
+    ] {
+        writeln!(out)?;
+        writeln!(out, "{}, by the information their feedback gives:", title)?;
+        let sizes = SIZES
+            .iter()
+            .zip(SIZES.iter().skip(1).map(Some).chain([None]))
+            .map(|(from, to)| match to {
+                Some(to) if *to == from + 1 => format!("{:>7}", from),
+                Some(to) => format!("{:>7}", format!("{}-{}", from, to - 1)),
+                None => format!("{:>7}", format!("{}+", from)),
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            out,
+            "opener, buckets,    bits, expected, largest, {}",
+            sizes.join(", ")
+        )?;
+        for split in splits {
+            let sizes = split
+                .sizes
+                .iter()
+                .map(|n| format!("{:7}", n))
+                .collect::<Vec<_>>();
+            writeln!(
+                out,
+                "{},  {:7}, {:7.3}, {:8.2}, {:7}, {}",
+                split.opener,
+                split.buckets,
+                split.bits,
+                split.expected,
+                split.largest,
+                sizes.join(", ")
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Where what a run keeps for later runs is stored: its journal, the score cache and memo tables.
+//! They go through `Storage` rather than straight to files, so that a run can keep them somewhere
+//! else by naming another store, like a cache shared by every machine in a distributed run.  There's
+//! `Files`, named like `file:<DIR>`, or just `<DIR>`, a SQLite [`Database`], named like
+//! `sqlite:<FILE>`, and an S3 [`Bucket`], named like `s3://<BUCKET>/<PREFIX>`.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::s3::Bucket;
+use crate::sqlite::Database;
+
+/// Contents kept by key, a relative path like `scores/0123456789abcdef`.
+pub trait Storage: Send + Sync {
+    /// What's kept under `key`, or `None` if nothing is.
+    fn read(&self, key: &Path) -> io::Result<Option<Vec<u8>>>;
+
+    /// Keeps `contents` under `key`, in place of whatever was there.
+    fn write(&self, key: &Path, contents: &[u8]) -> io::Result<()>;
+
+    /// Opens what's kept under `key` to add to the end of, starting it empty if there's nothing.
+    fn append(&self, key: &Path) -> io::Result<Box<dyn Log>>;
+
+    /// Tidies how `key` is kept, without changing what's read from it, for a store that keeps
+    /// what's added to a key in pieces.
+    fn compact(&self, _key: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Where `key` is kept, for messages.
+    fn locate(&self, key: &Path) -> String;
+}
+
+/// What's written to the end of something kept.
+pub trait Log: Write + Send {
+    /// Makes sure everything written so far is kept, even if the machine goes down.
+    fn sync(&mut self) -> io::Result<()>;
+}
+
+impl Log for File {
+    fn sync(&mut self) -> io::Result<()> {
+        self.sync_data()
+    }
+}
+
+/// Keeps each key in a file of its own, under a directory.
+#[derive(Clone, Debug, Default)]
+pub struct Files {
+    /// Empty for the working directory.
+    root: PathBuf,
+}
+
+impl Files {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The file `key` is kept in, after making the directories it goes in.
+    fn path(&self, key: &Path) -> io::Result<PathBuf> {
+        let path = self.root.join(key);
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(path)
+    }
+}
+
+impl Storage for Files {
+    fn read(&self, key: &Path) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.root.join(key)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write(&self, key: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(self.path(key)?, contents)
+    }
+
+    fn append(&self, key: &Path) -> io::Result<Box<dyn Log>> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(key)?)?;
+        Ok(Box::new(file))
+    }
+
+    fn locate(&self, key: &Path) -> String {
+        self.root.join(key).display().to_string()
+    }
+}
+
+/// A store, as it's named on the command line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Store {
+    Files(PathBuf),
+    Database(PathBuf),
+    Bucket { bucket: String, prefix: String },
+}
+
+impl FromStr for Store {
+    type Err = String;
+
+    /// Parses `file:<DIR>`, `sqlite:<FILE>`, `s3://<BUCKET>/<PREFIX>`, or a directory on its own.
+    /// Anything else that starts with a scheme, like `redis:`, is refused rather than taken for a
+    /// directory.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("file", dir)) => Ok(Self::Files(dir.into())),
+            Some(("sqlite", "")) => Err(format!("missing database: {}", s)),
+            Some(("sqlite", file)) => Ok(Self::Database(file.into())),
+            Some(("s3", location)) => {
+                let location = location.strip_prefix("//").unwrap_or(location);
+                let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+                match bucket {
+                    "" => Err(format!("missing bucket: {}", s)),
+                    bucket => Ok(Self::Bucket {
+                        bucket: bucket.to_string(),
+                        prefix: prefix.to_string(),
+                    }),
+                }
+            }
+            Some((scheme, _))
+                if scheme.len() > 1 && scheme.chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                Err(format!("unknown store: {}", scheme))
+            }
+            _ => Ok(Self::Files(s.into())),
+        }
+    }
+}
+
+impl Store {
+    pub fn open(&self) -> Result<Box<dyn Storage>, String> {
+        match self {
+            Self::Files(dir) => Ok(Box::new(Files::new(dir))),
+            Self::Database(file) => Ok(Box::new(Database::open(file).map_err(|e| e.to_string())?)),
+            Self::Bucket { bucket, prefix } => Ok(Box::new(Bucket::from_env(bucket, prefix)?)),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Optimal play for endgames, worked out ahead of time like a chess tablebase, so the finish search
+//! can look up the positions it reaches instead of searching them again.  It starts with a line of
+//! the guesses it was built with, as `# brutal tablebase 0123456789abcdef`, and then has a line for
+//! each position, like `abase abate abuse: 5 abase`, with the answers still possible, the fewest
+//! guesses they take in total and the guess to play first.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::fingerprint::Fingerprint;
+use crate::Word;
+
+const HEADER: &str = "# brutal tablebase";
+
+/// Identifies the words that can be guessed in a position, which is all its best play depends on.
+pub fn fingerprint(candidates: &[Word]) -> u64 {
+    let mut candidates = candidates.to_vec();
+    candidates.sort();
+    candidates.dedup();
+
+    let mut fingerprint = Fingerprint::new("tablebase");
+    fingerprint.add(candidates.first().map_or(0, Word::length));
+    candidates.iter().for_each(|w| {
+        fingerprint.add(w);
+    });
+    fingerprint.finish()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Tablebase {
+    fingerprint: u64,
+    /// The fewest guesses each position's answers take in total, and the guess to play first, by
+    /// the answers in alphabetical order.
+    positions: HashMap<Vec<Word>, (usize, Word)>,
+}
+
+impl Tablebase {
+    pub fn new(fingerprint: u64) -> Self {
+        Self {
+            fingerprint,
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, mut answers: Vec<Word>, total: usize, guess: Word) {
+        answers.sort();
+        self.positions.insert(answers, (total, guess));
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (&[Word], usize, Word)> {
+        self.positions
+            .iter()
+            .map(|(answers, (total, guess))| (answers.as_slice(), *total, *guess))
+    }
+
+    /// The guesses it was built with, as [`fingerprint`] identifies them.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Reads the tablebase at `filename`, or what's wrong with it.
+    pub fn read(filename: &Path) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(filename).map_err(|e| format!("{}: {}", filename.display(), e))?;
+        let location = |i: usize| format!("{}:{}", filename.display(), i + 1);
+
+        let mut lines = contents.lines().enumerate();
+        let fingerprint = lines
+            .next()
+            .and_then(|(_, h)| h.strip_prefix(HEADER))
+            .and_then(|f| u64::from_str_radix(f.trim(), 16).ok())
+            .ok_or_else(|| format!("{} isn't a tablebase", filename.display()))?;
+
+        let mut tablebase = Self::new(fingerprint);
+        for (i, line) in lines.filter(|(_, l)| !l.trim().is_empty()) {
+            let (answers, play) = line.split_once(':').ok_or_else(|| location(i))?;
+            let answers = answers
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<Word>, _>>()
+                .map_err(|e| format!("{}: {}", location(i), e))?;
+            let mut play = play.split_whitespace();
+            let total = play
+                .next()
+                .ok_or_else(|| format!("{}: missing total", location(i)))?
+                .parse()
+                .map_err(|e| format!("{}: {}", location(i), e))?;
+            let guess = play
+                .next()
+                .ok_or_else(|| format!("{}: missing guess", location(i)))?
+                .parse()
+                .map_err(|e| format!("{}: {}", location(i), e))?;
+            tablebase.insert(answers, total, guess);
+        }
+        Ok(tablebase)
+    }
+
+    /// Writes the positions to `filename`, in order, so the same tablebase is always written the
+    /// same way.
+    pub fn write(&self, filename: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(filename)?);
+        writeln!(file, "{} {:016x}", HEADER, self.fingerprint)?;
+
+        let mut positions = self.positions.iter().collect::<Vec<_>>();
+        positions.sort_unstable_by(|a, b| (a.0.len(), a.0).cmp(&(b.0.len(), b.0)));
+        for (answers, (total, guess)) in positions {
+            let answers = answers.iter().map(Word::to_string).collect::<Vec<_>>();
+            writeln!(file, "{}: {} {}", answers.join(" "), total, guess)?;
+        }
+        file.flush()
+    }
+}
@@ -0,0 +1,295 @@
+//! Quizzes the player on positions from partway through games, grading each guess they'd make
+//! against every guess the exact search could make there, and keeping a record of how they did so
+//! they can see whether they're getting better.  The record is a line of `time, answers, rank,
+//! average, best` for each position, after a line of `# brutal training`: when it was played, the
+//! answers left, where the player's guess ranked, and the guesses on average with it and with the
+//! best.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::exit::{self, OrInvalidInput};
+use crate::{clue, finish, get_pattern, Word, GUESS_LIMIT};
+
+const HEADER: &str = "# brutal training";
+/// Positions leave at least this many answers, so there's a choice to make...
+const MIN_ANSWERS: usize = 3;
+/// ...and at most this many, so every guess can be ranked exactly in a moment.
+const MAX_ANSWERS: usize = 20;
+/// How many tries a position gets to come out with an answer count in range.
+const ATTEMPTS: usize = 1000;
+/// The most recent positions the record's summed up over, as well as all of them.
+const RECENT: usize = 20;
+
+/// How the player did on one position.
+#[derive(Clone, Copy, Debug)]
+struct Round {
+    /// The seconds since the Unix epoch it was played at.
+    time: u64,
+    answers: usize,
+    /// One more than the number of guesses that were better.
+    rank: usize,
+    /// The guesses on average with the player's guess, and with the best.
+    average: f32,
+    best: f32,
+}
+
+impl Round {
+    fn is_best(&self) -> bool {
+        self.rank == 1
+    }
+}
+
+/// The guesses and their feedback so far, and the answers they leave.
+struct Position {
+    answer: Word,
+    board: Vec<(Word, usize)>,
+    answers: Vec<Word>,
+}
+
+/// A position partway through a game for one of `answers`, played with any of the answers still
+/// possible each time, as the solver plays, until few enough are left.
+fn position(rng: &mut StdRng, answers: &[Word]) -> Position {
+    for _ in 0..ATTEMPTS {
+        let answer = *answers.choose(rng).unwrap();
+        let mut left = answers.to_vec();
+        let mut board = Vec::new();
+        let mut guess = *answers.choose(rng).unwrap();
+        while guess != answer && board.len() < GUESS_LIMIT - 1 {
+            let pattern = get_pattern(&answer, &guess);
+            board.push((guess, pattern));
+            left.retain(|w| get_pattern(w, &guess) == pattern);
+            if left.len() <= MAX_ANSWERS {
+                break;
+            }
+            guess = *left.choose(rng).unwrap();
+        }
+        if guess != answer && (MIN_ANSWERS..=MAX_ANSWERS).contains(&left.len()) {
+            return Position {
+                answer,
+                board,
+                answers: left,
+            };
+        }
+    }
+    exit::fail(
+        exit::INVALID_INPUT,
+        format!(
+            "no positions with {} to {} answers left turned up in the lists",
+            MIN_ANSWERS, MAX_ANSWERS
+        ),
+    )
+}
+
+/// The rounds recorded in `filename`, oldest first, or none if it's not there yet.
+fn read_record(filename: &Path) -> Vec<Round> {
+    let contents = match fs::read_to_string(filename) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => exit::fail(
+            exit::INVALID_INPUT,
+            format!("{}: {}", filename.display(), e),
+        ),
+    };
+    let mut lines = contents.lines().enumerate();
+    if lines.next().map(|(_, l)| l) != Some(HEADER) {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!("{} isn't a training record", filename.display()),
+        );
+    }
+    lines
+        .filter(|(_, l)| !l.trim().is_empty() && !l.starts_with("time,"))
+        .map(|(i, l)| {
+            let line = || format!("{}:{}", filename.display(), i + 1);
+            let columns = l.split(',').map(str::trim).collect::<Vec<_>>();
+            if columns.len() != 5 {
+                exit::fail(
+                    exit::INVALID_INPUT,
+                    format!("{}: expected 5 columns", line()),
+                );
+            }
+            Round {
+                time: columns[0].parse().or_invalid_input(line),
+                answers: columns[1].parse().or_invalid_input(line),
+                rank: columns[2].parse().or_invalid_input(line),
+                average: columns[3].parse().or_invalid_input(line),
+                best: columns[4].parse().or_invalid_input(line),
+            }
+        })
+        .collect()
+}
+
+/// Adds `round` to the record in `filename`, starting it if it's new.
+fn record(filename: &Path, round: &Round) {
+    if let Some(dir) = filename.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).or_invalid_input(|| dir.display().to_string());
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(filename)
+        .or_invalid_input(|| filename.display().to_string());
+    let new = file.metadata().map_or(true, |m| m.len() == 0);
+    if new {
+        writeln!(file, "{}", HEADER).expect("cannot write header");
+        writeln!(file, "time, answers,  rank, average,    best").expect("cannot write header");
+    }
+    writeln!(
+        file,
+        "{}, {:7}, {:5}, {:7.3}, {:7.3}",
+        round.time, round.answers, round.rank, round.average, round.best
+    )
+    .expect("cannot write line");
+}
+
+/// Writes how often `rounds` were played best, and how many guesses on average were lost to the
+/// best in them.
+fn write_summary(out: &mut impl Write, title: &str, rounds: &[Round]) -> io::Result<()> {
+    if rounds.is_empty() {
+        return Ok(());
+    }
+    let best = rounds.iter().filter(|r| r.is_best()).count();
+    let lost = rounds.iter().map(|r| r.average - r.best).sum::<f32>() / rounds.len() as f32;
+    writeln!(
+        out,
+        "{}: the best guess in {} of {} positions ({:.1}%), {:.3} guesses on average over the best",
+        title,
+        best,
+        rounds.len(),
+        100.0 * best as f32 / rounds.len() as f32,
+        lost
+    )
+}
+
+/// Quizzes the player on `rounds` positions, or until they quit, from games for `answers` played
+/// with any of them or `guesses`, keeping their record in `record_file`.
+pub fn run(
+    answers: &[Word],
+    guesses: &[Word],
+    rounds: Option<usize>,
+    rng: &mut StdRng,
+    record_file: &Path,
+) {
+    if answers.len() < MIN_ANSWERS {
+        exit::fail(
+            exit::INVALID_INPUT,
+            format!("training takes at least {} answers", MIN_ANSWERS),
+        );
+    }
+    let mut words = answers.iter().chain(guesses).copied().collect::<Vec<_>>();
+    words.sort();
+    words.dedup();
+
+    println!("Pick the next guess for each position, or type quit to stop.");
+    let mut played = Vec::new();
+    let mut input = io::stdin().lock().lines();
+    'rounds: while rounds.is_none_or(|r| played.len() < r) {
+        let position = position(rng, answers);
+        println!();
+        println!(
+            "Position {}, with {} answers left:",
+            played.len() + 1,
+            position.answers.len()
+        );
+        for (guess, pattern) in &position.board {
+            println!("  {} {}", guess, clue::feedback(*pattern, guess.length()));
+        }
+
+        let guess = loop {
+            print!("Your guess: ");
+            io::stdout().flush().expect("cannot write prompt");
+            let line = match input.next() {
+                Some(line) => line.expect("cannot read guess"),
+                None => {
+                    println!();
+                    break 'rounds;
+                }
+            };
+            let line = line.trim().to_lowercase();
+            if line == "quit" {
+                break 'rounds;
+            }
+            match line.parse::<Word>() {
+                Ok(guess) if words.binary_search(&guess).is_ok() => break guess,
+                Ok(guess) => println!("{} isn't in the lists", guess),
+                Err(e) => println!("{}", e),
+            }
+        };
+
+        let ranked = finish::rank(&position.answers, guesses);
+        let count = position.answers.len() as f32;
+        let best = ranked[0].1;
+        // A guess that tells none of the answers apart costs a guess for each of them, on top of
+        // the best play after it.
+        let total = ranked
+            .iter()
+            .find(|(w, _)| *w == guess)
+            .map_or(best + position.answers.len(), |(_, t)| *t);
+        let round = Round {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            answers: position.answers.len(),
+            rank: 1 + ranked.iter().filter(|(_, t)| *t < total).count(),
+            average: total as f32 / count,
+            best: best as f32 / count,
+        };
+
+        if round.is_best() {
+            println!(
+                "{} is one of the best guesses: {:.3} on average",
+                guess, round.average
+            );
+        } else {
+            println!(
+                "{}: {:.3} on average, ranked {} of {}",
+                guess,
+                round.average,
+                round.rank,
+                words.len()
+            );
+        }
+        // The ones that could be the answer first.
+        let mut bests = ranked
+            .iter()
+            .take_while(|(_, t)| *t == best)
+            .map(|(w, _)| (!position.answers.contains(w), w.to_string()))
+            .collect::<Vec<_>>();
+        bests.sort();
+        let bests = bests.into_iter().map(|(_, w)| w).collect::<Vec<_>>();
+        println!(
+            "The best: {}{}, {:.3} on average",
+            bests.iter().take(5).cloned().collect::<Vec<_>>().join(" "),
+            match bests.len() {
+                0..=5 => String::new(),
+                n => format!(" and {} more", n - 5),
+            },
+            round.best
+        );
+        println!("The answer was {}", position.answer);
+
+        record(record_file, &round);
+        played.push(round);
+    }
+
+    let history = read_record(record_file);
+    let mut out = io::stdout();
+    println!();
+    write_summary(&mut out, "This session", &played).expect("cannot write line");
+    if history.len() > played.len() {
+        write_summary(
+            &mut out,
+            &format!("The last {}", RECENT.min(history.len())),
+            &history[history.len().saturating_sub(RECENT)..],
+        )
+        .expect("cannot write line");
+        write_summary(&mut out, "All time", &history).expect("cannot write line");
+    }
+    println!("Your record is kept in {}", record_file.display());
+}
@@ -0,0 +1,111 @@
+//! Games played by Wordle's rules with other words or rules of their own.  Primel's are the
+//! five-digit primes, with each digit as a letter, so its list is worked out rather than read.
+//! Hard mode on the New York Times' site is Wordle with its hard mode turned on.
+//!
+//! Quordle, Octordle and Absurdle aren't among them.  Their boards share every guess, or their
+//! answer is picked against the player, and the search only plays one board with an answer picked
+//! at random.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Word;
+
+/// How many digits Primel's words have.
+const DIGITS: u32 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+    Wordle,
+    HardModeNyt,
+    Primel,
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wordle" => Ok(Self::Wordle),
+            "hardmode-nyt" => Ok(Self::HardModeNyt),
+            "primel" => Ok(Self::Primel),
+            "quordle" | "octordle" => Err(format!(
+                "{} can't be searched: its boards share every guess, and brutal only plays one board",
+                s
+            )),
+            "absurdle" => Err(
+                "absurdle can't be searched: it picks the answer against you, and brutal only scores answers picked at random"
+                    .to_string(),
+            ),
+            _ => Err(format!(
+                "unknown variant: {} (expected wordle, hardmode-nyt or primel)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Wordle => write!(f, "wordle"),
+            Self::HardModeNyt => write!(f, "hardmode-nyt"),
+            Self::Primel => write!(f, "primel"),
+        }
+    }
+}
+
+impl Variant {
+    /// The words that are both the answers and the guesses, if they're built in rather than read
+    /// from the lists.
+    pub fn words(self) -> Option<Vec<Word>> {
+        match self {
+            Self::Wordle | Self::HardModeNyt => None,
+            Self::Primel => Some(primes()),
+        }
+    }
+
+    /// Whether it's played by hard mode's rules, whatever `--hard-mode` says.
+    pub fn hard_mode(self) -> bool {
+        self == Self::HardModeNyt
+    }
+
+    /// Whether `word` can be guessed at all.
+    pub fn allows(self, word: &Word) -> bool {
+        match self {
+            Self::Wordle | Self::HardModeNyt => true,
+            Self::Primel => word
+                .to_string()
+                .parse::<u32>()
+                .is_ok_and(|n| is_prime(n) && n >= smallest()),
+        }
+    }
+}
+
+/// The smallest number with `DIGITS` digits.
+fn smallest() -> u32 {
+    10u32.pow(DIGITS - 1)
+}
+
+fn is_prime(n: u32) -> bool {
+    n >= 2
+        && (2..)
+            .take_while(|d| d * d <= n)
+            .all(|d| !n.is_multiple_of(d))
+}
+
+/// The primes with `DIGITS` digits, in order.
+fn primes() -> Vec<Word> {
+    let end = 10 * smallest() as usize;
+    // A sieve of Eratosthenes, since every number up to the end is asked about.
+    let mut composite = vec![false; end];
+    for n in 2..end {
+        if !composite[n] {
+            (n * n..end).step_by(n).for_each(|m| composite[m] = true);
+        }
+    }
+    (smallest() as usize..end)
+        .filter(|n| !composite[*n])
+        .map(|n| n.to_string().parse().unwrap())
+        .collect()
+}
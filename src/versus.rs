@@ -0,0 +1,181 @@
+//! Races two strategies, each an opener and a policy for the guesses after it, on the same answers,
+//! as in competitive formats where solving in fewer guesses than the other player is all that
+//! counts.  The players' follow-up guesses are chosen independently, so the chance of each winning
+//! an answer comes from which guess each finds it on.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::thread;
+
+use crate::distribution::Distribution;
+use crate::solver::{Policy, SolverConfig};
+use crate::Word;
+
+/// An opener and the policy for the guesses after it, like `crane/hybrid:0`, or just `crane` for
+/// the random policy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Strategy {
+    pub opener: Word,
+    pub policy: Policy,
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (opener, policy) = match s.split_once('/') {
+            Some((opener, policy)) => (opener, policy.parse()?),
+            None => (s, Policy::Random),
+        };
+        Ok(Self {
+            opener: opener.parse().map_err(|e| format!("{}: {}", opener, e))?,
+            policy,
+        })
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.opener, self.policy)
+    }
+}
+
+/// How the players' guesses are counted against each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Turns {
+    /// Both guess at once, and solving on the same guess is a draw.
+    Simultaneous,
+    /// The first player guesses first each round, so solving on the same guess is their win.
+    Alternating,
+}
+
+impl FromStr for Turns {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "simultaneous" => Ok(Self::Simultaneous),
+            "alternating" => Ok(Self::Alternating),
+            _ => Err(format!("unknown turns: {}", s)),
+        }
+    }
+}
+
+/// The chances of the first player winning, of a draw, and of the second winning.
+#[derive(Clone, Copy, Debug, Default)]
+struct Outcome {
+    first: f64,
+    draw: f64,
+    second: f64,
+}
+
+/// The chances for one answer, with `first` and `second` each player's chance of solving it on
+/// each guess, up to the limit, after which the game's lost.
+fn race(first: &[f32], second: &[f32], turns: Turns) -> Outcome {
+    let mut outcome = Outcome::default();
+    // The chance of each player not having solved it yet.
+    let mut first_left = 1.0;
+    let mut second_left = 1.0;
+    for (a, b) in first.iter().zip(second) {
+        let (a, b) = (*a as f64, *b as f64);
+        let both = a * b;
+        outcome.first += a * (second_left - b);
+        outcome.second += b * (first_left - a);
+        match turns {
+            Turns::Simultaneous => outcome.draw += both,
+            Turns::Alternating => outcome.first += both,
+        }
+        first_left -= a;
+        second_left -= b;
+    }
+    // Nobody solving it within the limit is a draw too.
+    outcome.draw += (first_left * second_left).max(0.0);
+    outcome
+}
+
+/// Each answer's distribution for `strategy`.
+fn distributions(strategy: &Strategy, answers: &[Word], guesses: &[Word]) -> Vec<Distribution> {
+    SolverConfig::new(answers.to_vec(), guesses.to_vec())
+        .policy(strategy.policy)
+        .keep_answer_distributions(true)
+        .build()
+        .score(&strategy.opener)
+        .and_then(|r| r.answer_distributions)
+        .expect("distributions aren't being kept")
+}
+
+/// Writes how often each of `strategies` beats the other on `answers`, with games lost after
+/// `guess_limit` guesses, and each one's own average and success rate.
+pub fn run(
+    out: &mut impl Write,
+    answers: &[Word],
+    guesses: &[Word],
+    strategies: [&Strategy; 2],
+    turns: Turns,
+    guess_limit: usize,
+) -> io::Result<()> {
+    // Each opener's scored on a thread of its own.
+    let (first, second) = thread::scope(|scope| {
+        let first = scope.spawn(|| distributions(strategies[0], answers, guesses));
+        let second = distributions(strategies[1], answers, guesses);
+        (first.join().unwrap(), second)
+    });
+
+    let mut total = Outcome::default();
+    for (a, b) in first.iter().zip(&second) {
+        let outcome = race(
+            &a.on_guesses()[..guess_limit],
+            &b.on_guesses()[..guess_limit],
+            turns,
+        );
+        total.first += outcome.first;
+        total.draw += outcome.draw;
+        total.second += outcome.second;
+    }
+    let count = answers.len().max(1) as f64;
+
+    let names = strategies.map(Strategy::to_string);
+    let width = names.iter().map(|n| n.len() + 1).max().unwrap().max(9);
+    let turns = match turns {
+        Turns::Simultaneous => "guessing at once".to_string(),
+        Turns::Alternating => format!("{} guessing first", names[0]),
+    };
+    writeln!(
+        out,
+        "{} against {}, {}, with {} guesses each, over {} answers:",
+        names[0],
+        names[1],
+        turns,
+        guess_limit,
+        answers.len()
+    )?;
+    writeln!(
+        out,
+        "  {:w$}    wins,  average, success",
+        "strategy,",
+        w = width
+    )?;
+    for (name, wins, distributions) in [
+        (&names[0], total.first, &first),
+        (&names[1], total.second, &second),
+    ] {
+        let (average, success) = Distribution::mean(distributions).score(guess_limit);
+        writeln!(
+            out,
+            "  {:w$} {:6.2}%, {:8.3}, {:6.2}%",
+            format!("{},", name),
+            100.0 * wins / count,
+            average,
+            100.0 * success,
+            w = width
+        )?;
+    }
+    writeln!(
+        out,
+        "  {:w$} {:6.2}%",
+        "draws,",
+        100.0 * total.draw / count,
+        w = width
+    )
+}
@@ -0,0 +1,59 @@
+use crate::frequency::WordFrequencies;
+use crate::Word;
+
+/// A list of guess words, kept whole, each after the last, rather than as a column of letters per
+/// position: the deep levels of the search work on a handful of words, where gathering each one
+/// back out of the columns costs more than it saves.  The search only ever refers to its words by
+/// index, so narrowing it down copies indices rather than whole words.
+pub struct WordList {
+    words: Vec<Word>,
+    /// How likely each word is to be picked as a follow-up guess, if they aren't all equally so.
+    weights: Option<Vec<f32>>,
+    /// Every index, in order, for searching the whole list.
+    indices: Vec<u32>,
+    /// The letters every word has.
+    word_length: usize,
+}
+
+impl WordList {
+    /// Every word has to have as many letters as the others, which whoever read them should
+    /// already have made sure of.
+    pub fn new(words: &[Word], weights: Option<&WordFrequencies>) -> Self {
+        let word_length = words.first().map_or(0, Word::length);
+        assert!(
+            words.iter().all(|w| w.length() == word_length),
+            "words of different lengths in one list"
+        );
+        Self {
+            words: words.to_vec(),
+            weights: weights.map(|f| words.iter().map(|w| f.get(w)).collect()),
+            indices: (0..words.len() as u32).collect(),
+            word_length,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// The letters every word has, or 0 if there aren't any.
+    pub fn word_length(&self) -> usize {
+        self.word_length
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    pub fn word(&self, index: u32) -> &Word {
+        &self.words[index as usize]
+    }
+
+    pub fn weight(&self, index: u32) -> f32 {
+        self.weights.as_ref().map_or(1.0, |w| w[index as usize])
+    }
+}